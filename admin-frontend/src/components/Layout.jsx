@@ -21,7 +21,7 @@ import { useAuth } from "../contexts/AuthContext";
 import "./Layout.css";
 
 function Layout({ children }) {
-  const { user, logout } = useAuth();
+  const { user, logout, can } = useAuth();
   const navigate = useNavigate();
   const location = useLocation();
 
@@ -56,6 +56,14 @@ function Layout({ children }) {
               >
                 Access Logs
               </button>
+              {can("manage_admins") && (
+                <button
+                  className={`nav-link ${isActive("/users") ? "active" : ""}`}
+                  onClick={() => navigate("/users")}
+                >
+                  Admins
+                </button>
+              )}
               <button
                 className={`nav-link ${isActive("/settings") ? "active" : ""}`}
                 onClick={() => navigate("/settings")}
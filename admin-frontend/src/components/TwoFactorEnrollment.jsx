@@ -0,0 +1,112 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+import React, { useState, useEffect } from 'react';
+import { useAuth } from '../contexts/AuthContext';
+
+function RecoveryCodeList({ codes }) {
+  return (
+    <div className="success">
+      <p>
+        Store these recovery codes somewhere safe. Each one can be used once if you lose access to
+        your authenticator app, and they will not be shown again.
+      </p>
+      <pre>{codes.join('\n')}</pre>
+    </div>
+  );
+}
+
+function TwoFactorEnrollment({ onComplete }) {
+  const [setup, setSetup] = useState(null);
+  const [code, setCode] = useState('');
+  const [recoveryCodes, setRecoveryCodes] = useState(null);
+  const [error, setError] = useState('');
+  const [isLoading, setIsLoading] = useState(false);
+  const { setupTwoFactor, confirmTwoFactor } = useAuth();
+
+  useEffect(() => {
+    setupTwoFactor()
+      .then(setSetup)
+      .catch((err) => setError(err.message));
+  }, []);
+
+  async function handleSubmit(e) {
+    e.preventDefault();
+    setError('');
+    setIsLoading(true);
+
+    try {
+      const data = await confirmTwoFactor(code.trim());
+      setRecoveryCodes(data.recovery_codes);
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  if (recoveryCodes) {
+    return (
+      <div>
+        <RecoveryCodeList codes={recoveryCodes} />
+        <button type="button" className="btn" onClick={onComplete}>
+          Continue
+        </button>
+      </div>
+    );
+  }
+
+  return (
+    <div>
+      {error && <div className="error">{error}</div>}
+
+      {setup && (
+        <form onSubmit={handleSubmit}>
+          <p>
+            Add this account to your authenticator app using the setup key or link below, then
+            enter the 6-digit code it shows.
+          </p>
+          <div className="form-group">
+            <label htmlFor="totp-secret">Setup key</label>
+            <input type="text" id="totp-secret" value={setup.secret} readOnly />
+          </div>
+          <div className="link">
+            <a href={setup.otpauth_uri}>Open in authenticator app</a>
+          </div>
+          <div className="form-group">
+            <label htmlFor="totp-code">Authentication code</label>
+            <input
+              type="text"
+              id="totp-code"
+              inputMode="numeric"
+              autoComplete="one-time-code"
+              value={code}
+              onChange={(e) => setCode(e.target.value)}
+              required
+              placeholder="123456"
+            />
+          </div>
+          <button type="submit" className="btn" disabled={isLoading}>
+            {isLoading ? 'Verifying...' : 'Verify and enable'}
+          </button>
+        </form>
+      )}
+    </div>
+  );
+}
+
+export { RecoveryCodeList };
+export default TwoFactorEnrollment;
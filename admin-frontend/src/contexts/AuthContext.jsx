@@ -17,6 +17,7 @@
 
 import React, { createContext, useContext, useState, useEffect } from 'react';
 import { useNavigate } from 'react-router-dom';
+import { getPasskey } from '../passkeys';
 
 const AuthContext = createContext(null);
 
@@ -71,15 +72,78 @@ export function AuthProvider({ children }) {
     }
 
     const data = await response.json();
+    if (data.two_factor_required) {
+      return data;
+    }
+
     setUser(data);
     navigate('/dashboard');
+    return null;
   }
 
-  async function register(email, password) {
+  async function postJson(path, body, fallbackError) {
+    const response = await fetch(path, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(body),
+      credentials: 'include',
+    });
+
+    if (!response.ok) {
+      const error = await response.json();
+      throw new Error(error.error || fallbackError);
+    }
+
+    return await response.json();
+  }
+
+  async function verifyTwoFactor(code) {
+    const data = await postJson('/api/admin/login/2fa', { code }, 'Verification failed');
+    setUser(data);
+    navigate('/dashboard');
+  }
+
+  async function setupTwoFactor() {
+    return await postJson('/api/admin/2fa/setup', {}, 'Could not start two-factor setup');
+  }
+
+  async function confirmTwoFactor(code) {
+    return await postJson('/api/admin/2fa/confirm', { code }, 'Verification failed');
+  }
+
+  async function disableTwoFactor(password, code) {
+    return await postJson(
+      '/api/admin/2fa/disable',
+      { password, code },
+      'Could not disable two-factor authentication'
+    );
+  }
+
+  async function regenerateRecoveryCodes(code) {
+    return await postJson(
+      '/api/admin/2fa/recovery-codes',
+      { code },
+      'Could not regenerate recovery codes'
+    );
+  }
+
+  async function loginWithPasskey(email) {
+    const challenge = await postJson(
+      '/api/admin/login/passkey/start',
+      { email },
+      'Passkey login failed'
+    );
+    const credential = await getPasskey(challenge);
+    const data = await postJson('/api/admin/login/passkey/finish', credential, 'Passkey login failed');
+    setUser(data);
+    navigate('/dashboard');
+  }
+
+  async function register(email, password, inviteToken) {
     const response = await fetch('/api/admin/register', {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ email, password }),
+      body: JSON.stringify({ email, password, invite_token: inviteToken }),
       credentials: 'include',
     });
 
@@ -91,6 +155,11 @@ export function AuthProvider({ children }) {
     return await response.json();
   }
 
+  // Mirrors the role checks the server makes, only used to hide what would be refused
+  function can(permission) {
+    return Boolean(user && user.permissions && user.permissions.includes(permission));
+  }
+
   async function logout() {
     await fetch('/api/admin/logout', {
       method: 'POST',
@@ -104,9 +173,16 @@ export function AuthProvider({ children }) {
     user,
     loading,
     login,
+    loginWithPasskey,
     register,
     logout,
+    can,
     checkAuth,
+    verifyTwoFactor,
+    setupTwoFactor,
+    confirmTwoFactor,
+    disableTwoFactor,
+    regenerateRecoveryCodes,
   };
 
   return <AuthContext.Provider value={value}>{children}</AuthContext.Provider>;
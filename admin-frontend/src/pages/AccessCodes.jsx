@@ -17,6 +17,7 @@
 
 import React, { useState, useEffect } from "react";
 import Layout from "../components/Layout";
+import { useAuth } from "../contexts/AuthContext";
 import "./AccessCodes.css";
 
 function AccessCodes() {
@@ -24,6 +25,9 @@ function AccessCodes() {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState("");
   const [showCreateForm, setShowCreateForm] = useState(false);
+  const [analytics, setAnalytics] = useState({});
+  const { can } = useAuth();
+  const canManage = can("manage_access_codes");
   const [newCode, setNewCode] = useState({
     code: "",
     name: "",
@@ -127,6 +131,29 @@ function AccessCodes() {
     }
   }
 
+  async function handleToggleAnalytics(id) {
+    if (analytics[id]) {
+      const { [id]: _, ...rest } = analytics;
+      setAnalytics(rest);
+      return;
+    }
+
+    try {
+      const response = await fetch(`/api/admin/access-codes/${id}/analytics`, {
+        credentials: "include",
+      });
+
+      if (!response.ok) {
+        throw new Error("Failed to fetch analytics");
+      }
+
+      const data = await response.json();
+      setAnalytics({ ...analytics, [id]: data.engagement });
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
   function formatDate(dateString) {
     if (!dateString) return "Never";
     const date = new Date(dateString);
@@ -142,12 +169,14 @@ function AccessCodes() {
       <div className="access-codes-page">
         <header className="page-header">
           <h1>Access Code Management</h1>
-          <button
-            onClick={() => setShowCreateForm(!showCreateForm)}
-            className="btn-primary"
-          >
-            {showCreateForm ? "Cancel" : "+ New Access Code"}
-          </button>
+          {canManage && (
+            <button
+              onClick={() => setShowCreateForm(!showCreateForm)}
+              className="btn-primary"
+            >
+              {showCreateForm ? "Cancel" : "+ New Access Code"}
+            </button>
+          )}
         </header>
 
         {error && <div className="error">{error}</div>}
@@ -266,13 +295,59 @@ function AccessCodes() {
                     </div>
                   </div>
 
+                  {analytics[code.id] && (
+                    <div className="code-analytics">
+                      {analytics[code.id].tracked_views === 0 ? (
+                        <div>
+                          No engagement recorded. Enable the
+                          engagement_tracking setting to collect it.
+                        </div>
+                      ) : (
+                        <>
+                          <div>
+                            <strong>Tracked Views:</strong>{" "}
+                            {analytics[code.id].tracked_views}
+                          </div>
+                          <div>
+                            <strong>Avg. Time on Page:</strong>{" "}
+                            {Math.round(
+                              analytics[code.id].average_time_on_page_seconds,
+                            )}
+                            s
+                          </div>
+                          <div>
+                            <strong>Avg. Scroll Depth:</strong>{" "}
+                            {Math.round(analytics[code.id].average_scroll_depth)}
+                            %
+                          </div>
+                          <div>
+                            <strong>Read to the End:</strong>{" "}
+                            {analytics[code.id].read_through_views}
+                          </div>
+                          <div>
+                            <strong>Last Engaged:</strong>{" "}
+                            {formatDate(analytics[code.id].last_engaged_at)}
+                          </div>
+                        </>
+                      )}
+                    </div>
+                  )}
+
                   <div className="code-actions">
                     <button
-                      onClick={() => handleDeleteCode(code.id)}
-                      className="btn-delete"
+                      onClick={() => handleToggleAnalytics(code.id)}
+                      className="btn-secondary"
                     >
-                      Delete
+                      {analytics[code.id] ? "Hide Analytics" : "Analytics"}
                     </button>
+                    {canManage && (
+                      <button
+                        onClick={() => handleDeleteCode(code.id)}
+                        className="btn-delete"
+                      >
+                        Delete
+                      </button>
+                    )}
                   </div>
                 </div>
               ))}
@@ -17,6 +17,7 @@
 
 import React, { useState, useEffect } from "react";
 import Layout from "../components/Layout";
+import { useAuth } from "../contexts/AuthContext";
 import "./AccessLogs.css";
 
 function AccessLogs() {
@@ -27,6 +28,7 @@ function AccessLogs() {
   const [totalPages, setTotalPages] = useState(1);
   const [total, setTotal] = useState(0);
   const [perPage, setPerPage] = useState(100);
+  const { can } = useAuth();
 
   useEffect(() => {
     fetchLogs(currentPage);
@@ -113,9 +115,11 @@ function AccessLogs() {
       <div className="access-logs-page">
         <header className="page-header">
           <h1>Access Logs</h1>
-          <button onClick={handleClearLogs} className="btn-danger">
-            Clear All Logs
-          </button>
+          {can("clear_access_logs") && (
+            <button onClick={handleClearLogs} className="btn-danger">
+              Clear All Logs
+            </button>
+          )}
         </header>
 
         {error && <div className="error">{error}</div>}
@@ -0,0 +1,89 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+import React, { useState } from 'react';
+import { Link } from 'react-router-dom';
+
+function ForgotPassword() {
+  const [email, setEmail] = useState('');
+  const [message, setMessage] = useState('');
+  const [error, setError] = useState('');
+  const [isLoading, setIsLoading] = useState(false);
+
+  async function handleSubmit(e) {
+    e.preventDefault();
+    setError('');
+    setIsLoading(true);
+
+    try {
+      const response = await fetch('/api/admin/password-reset/request', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ email }),
+        credentials: 'include',
+      });
+
+      const data = await response.json();
+      if (!response.ok) {
+        throw new Error(data.error || 'Could not request a password reset');
+      }
+
+      setMessage(data.message);
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  return (
+    <div className="container">
+      <div className="card">
+        <h1>Forgot Password</h1>
+        <p>Enter your admin email and we'll send you a link to choose a new password</p>
+
+        {error && <div className="error">{error}</div>}
+        {message ? (
+          <div className="success">{message}</div>
+        ) : (
+          <form onSubmit={handleSubmit}>
+            <div className="form-group">
+              <label htmlFor="email">Email</label>
+              <input
+                type="email"
+                id="email"
+                value={email}
+                onChange={(e) => setEmail(e.target.value)}
+                required
+                placeholder="admin@cavebatsoftware.com"
+              />
+            </div>
+
+            <button type="submit" className="btn" disabled={isLoading}>
+              {isLoading ? 'Sending...' : 'Send reset link'}
+            </button>
+          </form>
+        )}
+
+        <div className="link">
+          <Link to="/login">Back to login</Link>
+        </div>
+      </div>
+    </div>
+  );
+}
+
+export default ForgotPassword;
@@ -15,16 +15,32 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-import React, { useState } from 'react';
-import { Link, Navigate } from 'react-router-dom';
+import React, { useState, useEffect } from 'react';
+import { Link, Navigate, useSearchParams } from 'react-router-dom';
 import { useAuth } from '../contexts/AuthContext';
+import TwoFactorEnrollment from '../components/TwoFactorEnrollment';
+import { passkeysSupported } from '../passkeys';
+
+// Single sign-on comes back through a redirect, with any error or 2FA step in the query
+const SSO_STEPS = { code: 'code', enroll: 'enroll' };
 
 function Login() {
+  const [searchParams] = useSearchParams();
   const [email, setEmail] = useState('');
   const [password, setPassword] = useState('');
-  const [error, setError] = useState('');
+  const [error, setError] = useState(searchParams.get('error') || '');
   const [isLoading, setIsLoading] = useState(false);
-  const { user, login } = useAuth();
+  const [step, setStep] = useState(SSO_STEPS[searchParams.get('two_factor')] || 'password');
+  const [code, setCode] = useState('');
+  const [ssoProvider, setSsoProvider] = useState(null);
+  const { user, login, loginWithPasskey, verifyTwoFactor, checkAuth } = useAuth();
+
+  useEffect(() => {
+    fetch('/api/admin/login/oidc')
+      .then((response) => (response.ok ? response.json() : null))
+      .then((provider) => setSsoProvider(provider?.enabled ? provider : null))
+      .catch(() => setSsoProvider(null));
+  }, []);
 
   if (user) {
     return <Navigate to="/dashboard" replace />;
@@ -36,7 +52,27 @@ function Login() {
     setIsLoading(true);
 
     try {
-      await login(email, password);
+      const challenge = await login(email, password);
+      if (challenge) {
+        setStep(challenge.enrollment_required ? 'enroll' : 'code');
+      }
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  async function handlePasskeyLogin() {
+    setError('');
+    if (!email) {
+      setError('Enter your email to sign in with a passkey');
+      return;
+    }
+    setIsLoading(true);
+
+    try {
+      await loginWithPasskey(email);
     } catch (err) {
       setError(err.message);
     } finally {
@@ -44,6 +80,65 @@ function Login() {
     }
   }
 
+  async function handleCodeSubmit(e) {
+    e.preventDefault();
+    setError('');
+    setIsLoading(true);
+
+    try {
+      await verifyTwoFactor(code.trim());
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  if (step === 'code') {
+    return (
+      <div className="container">
+        <div className="card">
+          <h1>Two-Factor Authentication</h1>
+          <p>Enter the code from your authenticator app, or one of your recovery codes</p>
+
+          {error && <div className="error">{error}</div>}
+
+          <form onSubmit={handleCodeSubmit}>
+            <div className="form-group">
+              <label htmlFor="code">Authentication code</label>
+              <input
+                type="text"
+                id="code"
+                autoComplete="one-time-code"
+                value={code}
+                onChange={(e) => setCode(e.target.value)}
+                required
+                autoFocus
+                placeholder="123456"
+              />
+            </div>
+
+            <button type="submit" className="btn" disabled={isLoading}>
+              {isLoading ? 'Verifying...' : 'Verify'}
+            </button>
+          </form>
+        </div>
+      </div>
+    );
+  }
+
+  if (step === 'enroll') {
+    return (
+      <div className="container">
+        <div className="card">
+          <h1>Set Up Two-Factor Authentication</h1>
+          <p>Two-factor authentication is required before you can sign in</p>
+          <TwoFactorEnrollment onComplete={checkAuth} />
+        </div>
+      </div>
+    );
+  }
+
   return (
     <div className="container">
       <div className="card">
@@ -82,6 +177,30 @@ function Login() {
           </button>
         </form>
 
+        {ssoProvider && (
+          <div className="link">
+            <a className="btn" href="/api/admin/login/oidc/start">
+              Sign in with {ssoProvider.name}
+            </a>
+          </div>
+        )}
+
+        {passkeysSupported() && (
+          <div className="link">
+            <button type="button" className="btn" onClick={handlePasskeyLogin} disabled={isLoading}>
+              Sign in with a passkey
+            </button>
+          </div>
+        )}
+
+        <div className="link">
+          <Link to="/forgot-password">Forgot your password?</Link>
+        </div>
+
+        <div className="link">
+          <Link to="/verify-email">Didn't get the verification email?</Link>
+        </div>
+
         <div className="link">
           Don't have an account? <Link to="/register">Register here</Link>
         </div>
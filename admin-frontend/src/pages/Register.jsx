@@ -15,8 +15,8 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-import React, { useState } from "react";
-import { Link, Navigate } from "react-router-dom";
+import React, { useState, useEffect } from "react";
+import { Link, Navigate, useSearchParams } from "react-router-dom";
 import { useAuth } from "../contexts/AuthContext";
 
 function Register() {
@@ -26,9 +26,41 @@ function Register() {
   const [error, setError] = useState("");
   const [success, setSuccess] = useState(false);
   const [isLoading, setIsLoading] = useState(false);
+  const [policy, setPolicy] = useState(null);
+  const [invite, setInvite] = useState(null);
+  const [verificationRequired, setVerificationRequired] = useState(true);
+  const [searchParams] = useSearchParams();
   const { user, register } = useAuth();
 
   const siteDomain = import.meta.env.VITE_SITE_DOMAIN;
+  const inviteToken = searchParams.get("invite");
+
+  useEffect(() => {
+    fetch("/api/admin/password-policy", { credentials: "include" })
+      .then((response) => (response.ok ? response.json() : null))
+      .then(setPolicy)
+      .catch(() => setPolicy(null));
+  }, []);
+
+  useEffect(() => {
+    if (!inviteToken) {
+      return;
+    }
+
+    fetch(
+      `/api/admin/invites/lookup?token=${encodeURIComponent(inviteToken)}`,
+      { credentials: "include" },
+    )
+      .then(async (response) => {
+        const data = await response.json();
+        if (!response.ok) {
+          throw new Error(data.error || "This invitation is not valid");
+        }
+        setInvite(data);
+        setEmail(data.email);
+      })
+      .catch((err) => setError(err.message));
+  }, [inviteToken]);
 
   if (user) {
     return <Navigate to="/dashboard" replace />;
@@ -39,7 +71,7 @@ function Register() {
     setError("");
     setIsLoading(true);
 
-    if (!email.endsWith(`@${siteDomain}`)) {
+    if (!inviteToken && !email.endsWith(`@${siteDomain}`)) {
       setError(`Email must be from ${siteDomain} domain`);
       setIsLoading(false);
       return;
@@ -52,15 +84,9 @@ function Register() {
       return;
     }
 
-    // Validate password strength
-    if (password.length < 8) {
-      setError("Password must be at least 8 characters long");
-      setIsLoading(false);
-      return;
-    }
-
     try {
-      await register(email, password);
+      const data = await register(email, password, inviteToken);
+      setVerificationRequired(data.verification_required);
       setSuccess(true);
     } catch (err) {
       setError(err.message);
@@ -75,14 +101,24 @@ function Register() {
         <div className="card">
           <div className="success">
             <h1>Registration Successful!</h1>
-            <p>
-              A verification email has been sent to <strong>{email}</strong>.
-            </p>
-            <p>
-              Please check your email and click the verification link to
-              activate your account.
-            </p>
-            <p>The verification link will expire in 24 hours.</p>
+            {verificationRequired ? (
+              <>
+                <p>
+                  A verification email has been sent to{" "}
+                  <strong>{email}</strong>.
+                </p>
+                <p>
+                  Please check your email and click the verification link to
+                  activate your account.
+                </p>
+                <p>The verification link will expire in 24 hours.</p>
+              </>
+            ) : (
+              <p>
+                Your account for <strong>{email}</strong> is ready. You can
+                log in now.
+              </p>
+            )}
           </div>
           <div className="link">
             <Link to="/login">Back to Login</Link>
@@ -96,7 +132,15 @@ function Register() {
     <div className="container">
       <div className="card">
         <h1>Admin Registration</h1>
-        <p>Create a new admin account ({siteDomain} only)</p>
+        {inviteToken ? (
+          <p>
+            {invite
+              ? `You've been invited to create an admin account for ${invite.email}`
+              : "Checking your invitation..."}
+          </p>
+        ) : (
+          <p>Create a new admin account ({siteDomain} only)</p>
+        )}
 
         {error && <div className="error">{error}</div>}
 
@@ -109,6 +153,7 @@ function Register() {
               value={email}
               onChange={(e) => setEmail(e.target.value)}
               required
+              readOnly={Boolean(invite)}
               placeholder={`admin@${siteDomain}`}
             />
           </div>
@@ -121,7 +166,14 @@ function Register() {
               value={password}
               onChange={(e) => setPassword(e.target.value)}
               required
-              placeholder="Minimum 8 characters"
+              minLength={policy ? policy.min_length : undefined}
+              maxLength={policy ? policy.max_length : undefined}
+              placeholder={
+                policy
+                  ? `Minimum ${policy.min_length} characters`
+                  : "Choose a strong password"
+              }
+              autoComplete="new-password"
             />
           </div>
 
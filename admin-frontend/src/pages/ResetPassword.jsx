@@ -0,0 +1,123 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+import React, { useState } from 'react';
+import { Link, useSearchParams } from 'react-router-dom';
+
+function ResetPassword() {
+  const [searchParams] = useSearchParams();
+  const [password, setPassword] = useState('');
+  const [confirmPassword, setConfirmPassword] = useState('');
+  const [message, setMessage] = useState('');
+  const [error, setError] = useState('');
+  const [isLoading, setIsLoading] = useState(false);
+  const token = searchParams.get('token');
+
+  async function handleSubmit(e) {
+    e.preventDefault();
+    setError('');
+
+    if (password !== confirmPassword) {
+      setError('Passwords do not match');
+      return;
+    }
+
+    setIsLoading(true);
+
+    try {
+      const response = await fetch('/api/admin/password-reset/confirm', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ token, password }),
+        credentials: 'include',
+      });
+
+      const data = await response.json();
+      if (!response.ok) {
+        throw new Error(data.error || 'Password reset failed');
+      }
+
+      setMessage(data.message);
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  if (!token) {
+    return (
+      <div className="container">
+        <div className="card">
+          <div className="error">No reset token provided</div>
+          <div className="link">
+            <Link to="/forgot-password">Request a new link</Link>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="container">
+      <div className="card">
+        <h1>Choose a New Password</h1>
+        <p>All existing sessions will be signed out</p>
+
+        {error && <div className="error">{error}</div>}
+        {message ? (
+          <div className="success">{message}</div>
+        ) : (
+          <form onSubmit={handleSubmit}>
+            <div className="form-group">
+              <label htmlFor="password">New password</label>
+              <input
+                type="password"
+                id="password"
+                value={password}
+                onChange={(e) => setPassword(e.target.value)}
+                required
+                autoComplete="new-password"
+              />
+            </div>
+
+            <div className="form-group">
+              <label htmlFor="confirmPassword">Confirm new password</label>
+              <input
+                type="password"
+                id="confirmPassword"
+                value={confirmPassword}
+                onChange={(e) => setConfirmPassword(e.target.value)}
+                required
+                autoComplete="new-password"
+              />
+            </div>
+
+            <button type="submit" className="btn" disabled={isLoading}>
+              {isLoading ? 'Saving...' : 'Reset password'}
+            </button>
+          </form>
+        )}
+
+        <div className="link">
+          <Link to="/login">Go to Login</Link>
+        </div>
+      </div>
+    </div>
+  );
+}
+
+export default ResetPassword;
@@ -17,13 +17,842 @@
 
 import React, { useState, useEffect } from "react";
 import Layout from "../components/Layout";
+import TwoFactorEnrollment, {
+  RecoveryCodeList,
+} from "../components/TwoFactorEnrollment";
+import { useAuth } from "../contexts/AuthContext";
+import { createPasskey, passkeysSupported } from "../passkeys";
 import "./Settings.css";
 
+function TwoFactorSettings() {
+  const [status, setStatus] = useState(null);
+  const [enrolling, setEnrolling] = useState(false);
+  const [password, setPassword] = useState("");
+  const [code, setCode] = useState("");
+  const [recoveryCodes, setRecoveryCodes] = useState(null);
+  const [error, setError] = useState("");
+  const [busy, setBusy] = useState(false);
+  const { disableTwoFactor, regenerateRecoveryCodes } = useAuth();
+
+  useEffect(() => {
+    fetchStatus();
+  }, []);
+
+  async function fetchStatus() {
+    try {
+      const response = await fetch("/api/admin/2fa", {
+        credentials: "include",
+      });
+
+      if (!response.ok) {
+        throw new Error("Failed to fetch two-factor status");
+      }
+
+      setStatus(await response.json());
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  async function handleDisable(e) {
+    e.preventDefault();
+    setBusy(true);
+    setError("");
+
+    try {
+      setStatus(await disableTwoFactor(password, code.trim()));
+      setPassword("");
+      setCode("");
+      setRecoveryCodes(null);
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleRegenerate() {
+    setBusy(true);
+    setError("");
+
+    try {
+      const data = await regenerateRecoveryCodes(code.trim());
+      setRecoveryCodes(data.recovery_codes);
+      setCode("");
+      await fetchStatus();
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  if (!status) {
+    return error ? <div className="error">{error}</div> : null;
+  }
+
+  return (
+    <div className="setting-item two-factor-panel">
+      <div className="setting-info">
+        <div className="setting-label">Two-Factor Authentication</div>
+        <div className="setting-description">
+          {status.enabled
+            ? `Enabled for your account, ${status.recovery_codes_remaining} recovery codes remaining`
+            : "Protect your account with codes from an authenticator app"}
+        </div>
+
+        {error && <div className="error">{error}</div>}
+        {recoveryCodes && <RecoveryCodeList codes={recoveryCodes} />}
+
+        {!status.enabled && !enrolling && (
+          <button
+            type="button"
+            className="btn-primary"
+            onClick={() => setEnrolling(true)}
+          >
+            Enable two-factor authentication
+          </button>
+        )}
+
+        {!status.enabled && enrolling && (
+          <TwoFactorEnrollment
+            onComplete={() => {
+              setEnrolling(false);
+              fetchStatus();
+            }}
+          />
+        )}
+
+        {status.enabled && (
+          <form onSubmit={handleDisable}>
+            <div className="form-group">
+              <label htmlFor="two-factor-code">Authentication code</label>
+              <input
+                type="text"
+                id="two-factor-code"
+                autoComplete="one-time-code"
+                value={code}
+                onChange={(e) => setCode(e.target.value)}
+                placeholder="123456"
+              />
+            </div>
+            <button
+              type="button"
+              className="btn-secondary"
+              onClick={handleRegenerate}
+              disabled={busy || !code}
+            >
+              Regenerate recovery codes
+            </button>
+            {!status.required && (
+              <>
+                <div className="form-group">
+                  <label htmlFor="two-factor-password">Password</label>
+                  <input
+                    type="password"
+                    id="two-factor-password"
+                    value={password}
+                    onChange={(e) => setPassword(e.target.value)}
+                    placeholder="Required to disable"
+                  />
+                </div>
+                <button
+                  type="submit"
+                  className="btn-danger"
+                  disabled={busy || !code || !password}
+                >
+                  Disable two-factor authentication
+                </button>
+              </>
+            )}
+          </form>
+        )}
+      </div>
+    </div>
+  );
+}
+
+function PasswordSettings() {
+  const [currentPassword, setCurrentPassword] = useState("");
+  const [newPassword, setNewPassword] = useState("");
+  const [confirmPassword, setConfirmPassword] = useState("");
+  const [policy, setPolicy] = useState(null);
+  const [message, setMessage] = useState("");
+  const [error, setError] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    fetch("/api/admin/password-policy", { credentials: "include" })
+      .then((response) => (response.ok ? response.json() : null))
+      .then(setPolicy)
+      .catch(() => setPolicy(null));
+  }, []);
+
+  async function handleSubmit(e) {
+    e.preventDefault();
+    setError("");
+    setMessage("");
+
+    if (newPassword !== confirmPassword) {
+      setError("Passwords do not match");
+      return;
+    }
+
+    setBusy(true);
+    try {
+      const response = await fetch("/api/admin/password", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+          current_password: currentPassword,
+          new_password: newPassword,
+        }),
+        credentials: "include",
+      });
+
+      const data = await response.json();
+      if (!response.ok) {
+        throw new Error(data.error || "Failed to change password");
+      }
+
+      setMessage(data.message);
+      setCurrentPassword("");
+      setNewPassword("");
+      setConfirmPassword("");
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <div className="setting-item password-panel">
+      <div className="setting-info">
+        <div className="setting-label">Password</div>
+        <div className="setting-description">
+          {policy
+            ? `At least ${policy.min_length} characters, not a common password and not containing your email address`
+            : "Change the password for your account"}
+        </div>
+
+        {error && <div className="error">{error}</div>}
+        {message && <div className="success">{message}</div>}
+
+        <form onSubmit={handleSubmit}>
+          <div className="form-group">
+            <label htmlFor="current-password">Current password</label>
+            <input
+              type="password"
+              id="current-password"
+              value={currentPassword}
+              onChange={(e) => setCurrentPassword(e.target.value)}
+              required
+              autoComplete="current-password"
+            />
+          </div>
+          <div className="form-group">
+            <label htmlFor="new-password">New password</label>
+            <input
+              type="password"
+              id="new-password"
+              value={newPassword}
+              onChange={(e) => setNewPassword(e.target.value)}
+              required
+              minLength={policy ? policy.min_length : undefined}
+              maxLength={policy ? policy.max_length : undefined}
+              autoComplete="new-password"
+            />
+          </div>
+          <div className="form-group">
+            <label htmlFor="confirm-new-password">Confirm new password</label>
+            <input
+              type="password"
+              id="confirm-new-password"
+              value={confirmPassword}
+              onChange={(e) => setConfirmPassword(e.target.value)}
+              required
+              autoComplete="new-password"
+            />
+          </div>
+          <button type="submit" className="btn-primary" disabled={busy}>
+            {busy ? "Saving..." : "Change password"}
+          </button>
+        </form>
+      </div>
+    </div>
+  );
+}
+
+async function apiRequest(path, options = {}) {
+  const response = await fetch(path, {
+    headers: { "Content-Type": "application/json" },
+    credentials: "include",
+    ...options,
+  });
+
+  if (!response.ok) {
+    const error = await response.json().catch(() => ({}));
+    throw new Error(error.error || "Request failed");
+  }
+
+  return response.status === 204 ? null : await response.json();
+}
+
+function PasskeySettings() {
+  const [passkeys, setPasskeys] = useState([]);
+  const [name, setName] = useState("");
+  const [error, setError] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    fetchPasskeys();
+  }, []);
+
+  async function fetchPasskeys() {
+    try {
+      setPasskeys(await apiRequest("/api/admin/passkeys"));
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  async function handleAdd(e) {
+    e.preventDefault();
+    setBusy(true);
+    setError("");
+
+    try {
+      const challenge = await apiRequest(
+        "/api/admin/passkeys/register/start",
+        { method: "POST" },
+      );
+      const credential = await createPasskey(challenge);
+      await apiRequest("/api/admin/passkeys/register/finish", {
+        method: "POST",
+        body: JSON.stringify({ name, credential }),
+      });
+      setName("");
+      await fetchPasskeys();
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleRename(passkey) {
+    const newName = window.prompt("Passkey name", passkey.name);
+    if (!newName || newName === passkey.name) {
+      return;
+    }
+
+    try {
+      await apiRequest(`/api/admin/passkeys/${passkey.id}`, {
+        method: "PUT",
+        body: JSON.stringify({ name: newName }),
+      });
+      await fetchPasskeys();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  async function handleRevoke(passkey) {
+    if (!window.confirm(`Revoke the passkey "${passkey.name}"?`)) {
+      return;
+    }
+
+    try {
+      await apiRequest(`/api/admin/passkeys/${passkey.id}`, {
+        method: "DELETE",
+      });
+      await fetchPasskeys();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  return (
+    <div className="setting-item passkey-panel">
+      <div className="setting-info">
+        <div className="setting-label">Passkeys</div>
+        <div className="setting-description">
+          Sign in with your device instead of a password
+        </div>
+
+        {error && <div className="error">{error}</div>}
+
+        <ul className="passkey-list">
+          {passkeys.map((passkey) => (
+            <li key={passkey.id}>
+              <span className="passkey-name">{passkey.name}</span>
+              <span className="passkey-used">
+                {passkey.last_used_at
+                  ? `Last used ${new Date(passkey.last_used_at).toLocaleString()}`
+                  : "Never used"}
+              </span>
+              <button
+                type="button"
+                className="btn-secondary"
+                onClick={() => handleRename(passkey)}
+              >
+                Rename
+              </button>
+              <button
+                type="button"
+                className="btn-delete"
+                onClick={() => handleRevoke(passkey)}
+              >
+                Revoke
+              </button>
+            </li>
+          ))}
+        </ul>
+
+        {passkeysSupported() ? (
+          <form onSubmit={handleAdd}>
+            <div className="form-group">
+              <label htmlFor="passkey-name">Name</label>
+              <input
+                type="text"
+                id="passkey-name"
+                value={name}
+                onChange={(e) => setName(e.target.value)}
+                required
+                placeholder="e.g. Work laptop"
+              />
+            </div>
+            <button type="submit" className="btn-primary" disabled={busy}>
+              {busy ? "Waiting for passkey..." : "Add passkey"}
+            </button>
+          </form>
+        ) : (
+          <p>This browser does not support passkeys.</p>
+        )}
+      </div>
+    </div>
+  );
+}
+
+function SessionSettings() {
+  const [sessions, setSessions] = useState([]);
+  const [error, setError] = useState("");
+  const { logout } = useAuth();
+
+  useEffect(() => {
+    fetchSessions();
+  }, []);
+
+  async function fetchSessions() {
+    try {
+      setSessions(await apiRequest("/api/admin/sessions"));
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  async function handleRevoke(session) {
+    setError("");
+    try {
+      await apiRequest(`/api/admin/sessions/${session.id}`, {
+        method: "DELETE",
+      });
+      if (session.current) {
+        await logout();
+        return;
+      }
+      await fetchSessions();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  async function handleRevokeOthers() {
+    if (!window.confirm("Sign out of every other session?")) {
+      return;
+    }
+
+    setError("");
+    try {
+      await apiRequest("/api/admin/sessions", { method: "DELETE" });
+      await fetchSessions();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  return (
+    <div className="setting-item session-panel">
+      <div className="setting-info">
+        <div className="setting-label">Sessions</div>
+        <div className="setting-description">
+          Where you're signed in. Sessions end after a week without use.
+        </div>
+
+        {error && <div className="error">{error}</div>}
+
+        <ul className="passkey-list">
+          {sessions.map((session) => (
+            <li key={session.id}>
+              <span className="passkey-name">
+                {session.user_agent || "Unknown device"}
+                {session.current && " (this session)"}
+              </span>
+              <span className="passkey-used">
+                {session.ip_address || "Unknown IP"} · last seen{" "}
+                {new Date(session.last_seen_at).toLocaleString()}
+              </span>
+              <button
+                type="button"
+                className="btn-delete"
+                onClick={() => handleRevoke(session)}
+              >
+                Sign out
+              </button>
+            </li>
+          ))}
+        </ul>
+
+        {sessions.length > 1 && (
+          <button
+            type="button"
+            className="btn-secondary"
+            onClick={handleRevokeOthers}
+          >
+            Sign out everywhere else
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
+
+const API_TOKEN_SCOPES = [
+  { scope: "codes:read", permission: "view_access_codes", label: "Read codes" },
+  {
+    scope: "codes:write",
+    permission: "manage_access_codes",
+    label: "Manage codes",
+  },
+  { scope: "logs:read", permission: "view_access_logs", label: "Read logs" },
+  { scope: "logs:write", permission: "clear_access_logs", label: "Clear logs" },
+  {
+    scope: "settings:read",
+    permission: "view_settings",
+    label: "Read settings",
+  },
+  {
+    scope: "settings:write",
+    permission: "manage_settings",
+    label: "Change settings",
+  },
+  {
+    scope: "documents:read",
+    permission: "view_documents",
+    label: "Read documents",
+  },
+  { scope: "posts:read", permission: "view_posts", label: "Read posts" },
+  { scope: "posts:write", permission: "publish_posts", label: "Publish posts" },
+  {
+    scope: "messages:read",
+    permission: "view_messages",
+    label: "Read messages",
+  },
+  {
+    scope: "messages:write",
+    permission: "manage_messages",
+    label: "Manage messages",
+  },
+];
+
+function ApiTokenSettings() {
+  const [tokens, setTokens] = useState([]);
+  const [name, setName] = useState("");
+  const [scopes, setScopes] = useState([]);
+  const [expiresInDays, setExpiresInDays] = useState(90);
+  const [created, setCreated] = useState(null);
+  const [error, setError] = useState("");
+  const [busy, setBusy] = useState(false);
+  const { can } = useAuth();
+
+  const availableScopes = API_TOKEN_SCOPES.filter(({ permission }) =>
+    can(permission),
+  );
+
+  useEffect(() => {
+    fetchTokens();
+  }, []);
+
+  async function fetchTokens() {
+    try {
+      setTokens(await apiRequest("/api/admin/tokens"));
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  function toggleScope(scope) {
+    setScopes((current) =>
+      current.includes(scope)
+        ? current.filter((s) => s !== scope)
+        : [...current, scope],
+    );
+  }
+
+  async function handleCreate(e) {
+    e.preventDefault();
+    setBusy(true);
+    setError("");
+    setCreated(null);
+
+    try {
+      const token = await apiRequest("/api/admin/tokens", {
+        method: "POST",
+        body: JSON.stringify({
+          name,
+          scopes,
+          expires_in_days: Number(expiresInDays),
+        }),
+      });
+      setCreated(token);
+      setName("");
+      setScopes([]);
+      await fetchTokens();
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleRevoke(token) {
+    if (
+      !window.confirm(
+        `Revoke "${token.name}"? Scripts using it will stop working.`,
+      )
+    ) {
+      return;
+    }
+
+    setError("");
+    try {
+      await apiRequest(`/api/admin/tokens/${token.id}`, { method: "DELETE" });
+      await fetchTokens();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  return (
+    <div className="setting-item api-token-panel">
+      <div className="setting-info">
+        <div className="setting-label">API Tokens</div>
+        <div className="setting-description">
+          Tokens let scripts call the admin API with an Authorization: Bearer
+          header. Each token only gets the scopes you pick.
+        </div>
+
+        {error && <div className="error">{error}</div>}
+        {created && (
+          <div className="success">
+            Copy this token now, it won't be shown again:
+            <code className="api-token-secret">{created.token}</code>
+          </div>
+        )}
+
+        <ul className="passkey-list">
+          {tokens.map((token) => (
+            <li key={token.id}>
+              <span className="passkey-name">
+                {token.name} <code>{token.token_prefix}…</code>
+              </span>
+              <span className="passkey-used">
+                {token.scopes.join(", ")} · expires{" "}
+                {new Date(token.expires_at).toLocaleDateString()} ·{" "}
+                {token.last_used_at
+                  ? `last used ${new Date(token.last_used_at).toLocaleString()}`
+                  : "never used"}
+              </span>
+              <button
+                type="button"
+                className="btn-delete"
+                onClick={() => handleRevoke(token)}
+              >
+                Revoke
+              </button>
+            </li>
+          ))}
+        </ul>
+
+        <form onSubmit={handleCreate}>
+          <div className="form-group">
+            <label htmlFor="api-token-name">Name</label>
+            <input
+              type="text"
+              id="api-token-name"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              required
+              maxLength={64}
+              placeholder="Deploy script"
+            />
+          </div>
+          <div className="form-group api-token-scopes">
+            {availableScopes.map(({ scope, label }) => (
+              <label key={scope}>
+                <input
+                  type="checkbox"
+                  checked={scopes.includes(scope)}
+                  onChange={() => toggleScope(scope)}
+                />
+                {label} <code>{scope}</code>
+              </label>
+            ))}
+          </div>
+          <div className="form-group">
+            <label htmlFor="api-token-expiry">Expires after (days)</label>
+            <input
+              type="number"
+              id="api-token-expiry"
+              min={1}
+              max={365}
+              value={expiresInDays}
+              onChange={(e) => setExpiresInDays(e.target.value)}
+              required
+            />
+          </div>
+          <button
+            type="submit"
+            className="btn-primary"
+            disabled={busy || scopes.length === 0}
+          >
+            {busy ? "Creating..." : "Create token"}
+          </button>
+        </form>
+      </div>
+    </div>
+  );
+}
+
+function InviteSettings() {
+  const [invites, setInvites] = useState([]);
+  const [email, setEmail] = useState("");
+  const [message, setMessage] = useState("");
+  const [error, setError] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    fetchInvites();
+  }, []);
+
+  async function fetchInvites() {
+    try {
+      setInvites(await apiRequest("/api/admin/invites"));
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  async function handleInvite(e) {
+    e.preventDefault();
+    setBusy(true);
+    setError("");
+    setMessage("");
+
+    try {
+      const invite = await apiRequest("/api/admin/invites", {
+        method: "POST",
+        body: JSON.stringify({ email }),
+      });
+      setMessage(`Invitation sent to ${invite.email}`);
+      setEmail("");
+      await fetchInvites();
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function handleRevoke(invite) {
+    if (!window.confirm(`Revoke the invitation for ${invite.email}?`)) {
+      return;
+    }
+
+    try {
+      await apiRequest(`/api/admin/invites/${invite.id}`, {
+        method: "DELETE",
+      });
+      await fetchInvites();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  return (
+    <div className="setting-item invite-panel">
+      <div className="setting-info">
+        <div className="setting-label">Invite Admins</div>
+        <div className="setting-description">
+          Invite someone from any domain to create an admin account. Links
+          expire after 7 days and work once.
+        </div>
+
+        {error && <div className="error">{error}</div>}
+        {message && <div className="success">{message}</div>}
+
+        <ul className="passkey-list">
+          {invites.map((invite) => (
+            <li key={invite.id}>
+              <span className="passkey-name">{invite.email}</span>
+              <span className="passkey-used">
+                {invite.status === "pending"
+                  ? `Expires ${new Date(invite.expires_at).toLocaleString()}`
+                  : invite.status === "accepted"
+                    ? `Accepted ${new Date(invite.accepted_at).toLocaleString()}`
+                    : "Expired"}
+              </span>
+              {invite.status === "pending" && (
+                <button
+                  type="button"
+                  className="btn-delete"
+                  onClick={() => handleRevoke(invite)}
+                >
+                  Revoke
+                </button>
+              )}
+            </li>
+          ))}
+        </ul>
+
+        <form onSubmit={handleInvite}>
+          <div className="form-group">
+            <label htmlFor="invite-email">Email</label>
+            <input
+              type="email"
+              id="invite-email"
+              value={email}
+              onChange={(e) => setEmail(e.target.value)}
+              required
+              placeholder="name@example.com"
+            />
+          </div>
+          <button type="submit" className="btn-primary" disabled={busy}>
+            {busy ? "Sending..." : "Send invitation"}
+          </button>
+        </form>
+      </div>
+    </div>
+  );
+}
+
 function Settings() {
   const [settings, setSettings] = useState([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState("");
   const [saving, setSaving] = useState(false);
+  const { can } = useAuth();
   const [showCreateForm, setShowCreateForm] = useState(false);
   const [newSetting, setNewSetting] = useState({
     key: "",
@@ -92,6 +921,7 @@ function Settings() {
   function getSettingLabel(key) {
     const labels = {
       admin_registration_enabled: "Admin Registration",
+      require_2fa: "Require Two-Factor Authentication",
     };
     return labels[key] || key;
   }
@@ -100,6 +930,8 @@ function Settings() {
     const descriptions = {
       admin_registration_enabled:
         "Allow new administrators to register accounts via the registration page",
+      require_2fa:
+        "Require every administrator to sign in with an authenticator app code, enrolling on their next login",
     };
     return descriptions[key] || "";
   }
@@ -146,7 +978,7 @@ function Settings() {
                       type="checkbox"
                       checked={setting.value === "true"}
                       onChange={() => handleToggleSetting(setting)}
-                      disabled={saving}
+                      disabled={saving || !can("manage_settings")}
                     />
                     <span className="toggle-slider"></span>
                   </label>
@@ -158,6 +990,15 @@ function Settings() {
             ))
           )}
         </div>
+
+        <div className="settings-list two-factor-section">
+          <PasswordSettings />
+          <SessionSettings />
+          <TwoFactorSettings />
+          <PasskeySettings />
+          <ApiTokenSettings />
+          {can("manage_admins") && <InviteSettings />}
+        </div>
       </div>
     </Layout>
   );
@@ -0,0 +1,199 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+import React, { useState, useEffect } from "react";
+import Layout from "../components/Layout";
+import { useAuth } from "../contexts/AuthContext";
+import "./Users.css";
+
+const ROLES = ["owner", "editor", "viewer"];
+
+async function userRequest(path, options = {}) {
+  const response = await fetch(path, {
+    headers: { "Content-Type": "application/json" },
+    credentials: "include",
+    ...options,
+  });
+
+  if (!response.ok) {
+    const error = await response.json().catch(() => ({}));
+    throw new Error(error.error || "Request failed");
+  }
+
+  return response.status === 204 ? null : await response.json();
+}
+
+function Users() {
+  const [admins, setAdmins] = useState([]);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState("");
+  const [message, setMessage] = useState("");
+  const { user } = useAuth();
+
+  useEffect(() => {
+    fetchAdmins();
+  }, []);
+
+  async function fetchAdmins() {
+    try {
+      setAdmins(await userRequest("/api/admin/users"));
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setLoading(false);
+    }
+  }
+
+  async function run(action) {
+    setError("");
+    setMessage("");
+    try {
+      await action();
+      await fetchAdmins();
+    } catch (err) {
+      setError(err.message);
+    }
+  }
+
+  function handleRoleChange(admin, role) {
+    run(() =>
+      userRequest(`/api/admin/users/${admin.id}/role`, {
+        method: "PUT",
+        body: JSON.stringify({ role }),
+      }),
+    );
+  }
+
+  function handleToggleDisabled(admin) {
+    const action = admin.disabled ? "enable" : "disable";
+    if (!admin.disabled && !window.confirm(`Disable ${admin.email}?`)) {
+      return;
+    }
+    run(() =>
+      userRequest(`/api/admin/users/${admin.id}/${action}`, { method: "POST" }),
+    );
+  }
+
+  function handleResendVerification(admin) {
+    run(async () => {
+      const data = await userRequest(
+        `/api/admin/users/${admin.id}/resend-verification`,
+        { method: "POST" },
+      );
+      setMessage(data.message);
+    });
+  }
+
+  function handleDelete(admin) {
+    if (!window.confirm(`Delete ${admin.email}? This can't be undone.`)) {
+      return;
+    }
+    run(() =>
+      userRequest(`/api/admin/users/${admin.id}`, { method: "DELETE" }),
+    );
+  }
+
+  if (loading) {
+    return <div className="loading">Loading admins...</div>;
+  }
+
+  return (
+    <Layout>
+      <div className="users-page">
+        <header className="page-header">
+          <h1>Admins</h1>
+        </header>
+
+        {error && <div className="error">{error}</div>}
+        {message && <div className="success">{message}</div>}
+
+        <div className="users-table-container">
+          <table className="users-table">
+            <thead>
+              <tr>
+                <th>Email</th>
+                <th>Role</th>
+                <th>Status</th>
+                <th>Created</th>
+                <th></th>
+              </tr>
+            </thead>
+            <tbody>
+              {admins.map((admin) => (
+                <tr key={admin.id} className={admin.disabled ? "disabled" : ""}>
+                  <td>
+                    {admin.email}
+                    {admin.id === user?.id && (
+                      <span className="user-you"> (you)</span>
+                    )}
+                  </td>
+                  <td>
+                    <select
+                      value={admin.role}
+                      onChange={(e) => handleRoleChange(admin, e.target.value)}
+                    >
+                      {ROLES.map((role) => (
+                        <option key={role} value={role}>
+                          {role}
+                        </option>
+                      ))}
+                    </select>
+                  </td>
+                  <td>
+                    {admin.disabled
+                      ? "Disabled"
+                      : !admin.email_verified
+                        ? "Unverified"
+                        : admin.locked_until &&
+                            new Date(admin.locked_until) > new Date()
+                          ? "Locked"
+                          : "Active"}
+                    {admin.two_factor_enabled && " · 2FA"}
+                  </td>
+                  <td>{new Date(admin.created_at).toLocaleDateString()}</td>
+                  <td className="user-actions">
+                    {!admin.email_verified && (
+                      <button
+                        className="btn-secondary"
+                        onClick={() => handleResendVerification(admin)}
+                      >
+                        Resend verification
+                      </button>
+                    )}
+                    <button
+                      className="btn-secondary"
+                      onClick={() => handleToggleDisabled(admin)}
+                    >
+                      {admin.disabled ? "Enable" : "Disable"}
+                    </button>
+                    <button
+                      className="btn-delete"
+                      onClick={() => handleDelete(admin)}
+                    >
+                      Delete
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      </div>
+    </Layout>
+  );
+}
+
+export default Users;
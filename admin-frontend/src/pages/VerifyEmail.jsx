@@ -18,6 +18,65 @@
 import React, { useEffect, useState } from 'react';
 import { Link, useSearchParams } from 'react-router-dom';
 
+// Sends a fresh link, which also replaces any earlier one
+function ResendVerification() {
+  const [email, setEmail] = useState('');
+  const [message, setMessage] = useState('');
+  const [error, setError] = useState('');
+  const [isLoading, setIsLoading] = useState(false);
+
+  async function handleSubmit(e) {
+    e.preventDefault();
+    setError('');
+    setIsLoading(true);
+
+    try {
+      const response = await fetch('/api/admin/verify-email/resend', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ email }),
+        credentials: 'include',
+      });
+
+      const data = await response.json();
+      if (!response.ok) {
+        throw new Error(data.error || 'Could not send a new verification link');
+      }
+
+      setMessage(data.message);
+    } catch (err) {
+      setError(err.message);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  if (message) {
+    return <div className="success">{message}</div>;
+  }
+
+  return (
+    <form onSubmit={handleSubmit}>
+      {error && <div className="error">{error}</div>}
+      <div className="form-group">
+        <label htmlFor="email">Email</label>
+        <input
+          type="email"
+          id="email"
+          value={email}
+          onChange={(e) => setEmail(e.target.value)}
+          required
+          placeholder="admin@cavebatsoftware.com"
+        />
+      </div>
+
+      <button type="submit" className="btn" disabled={isLoading}>
+        {isLoading ? 'Sending...' : 'Send a new link'}
+      </button>
+    </form>
+  );
+}
+
 function VerifyEmail() {
   const [searchParams] = useSearchParams();
   const [status, setStatus] = useState('verifying');
@@ -32,8 +91,7 @@ function VerifyEmail() {
     const token = searchParams.get('token');
 
     if (!token) {
-      setStatus('error');
-      setError('No verification token provided');
+      setStatus('resend');
       return;
     }
 
@@ -68,6 +126,21 @@ function VerifyEmail() {
     );
   }
 
+  if (status === 'resend') {
+    return (
+      <div className="container">
+        <div className="card">
+          <h1>Resend Verification</h1>
+          <p>Enter your admin email and we'll send you a new verification link</p>
+          <ResendVerification />
+          <div className="link">
+            <Link to="/login">Back to login</Link>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
   if (status === 'error') {
     return (
       <div className="container">
@@ -76,8 +149,9 @@ function VerifyEmail() {
             <h1>Verification Failed</h1>
             <p>{error}</p>
           </div>
+          <p>Links expire after 24 hours. Request a new one below.</p>
+          <ResendVerification />
           <div className="link">
-            <Link to="/register">Register Again</Link> or{' '}
             <Link to="/login">Login</Link>
           </div>
         </div>
@@ -0,0 +1,85 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Browser side of the passkey ceremonies. The server sends and expects binary fields as base64url
+// strings, navigator.credentials works with ArrayBuffers.
+
+function toBuffer(value) {
+  const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+  const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4);
+  return Uint8Array.from(atob(padded), (c) => c.charCodeAt(0)).buffer;
+}
+
+function toBase64Url(buffer) {
+  const bytes = String.fromCharCode(...new Uint8Array(buffer));
+  return btoa(bytes).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+function credentialDescriptors(list) {
+  return (list || []).map((credential) => ({ ...credential, id: toBuffer(credential.id) }));
+}
+
+export function passkeysSupported() {
+  return typeof window !== 'undefined' && Boolean(window.PublicKeyCredential);
+}
+
+export async function createPasskey(challenge) {
+  const options = challenge.publicKey;
+  const credential = await navigator.credentials.create({
+    publicKey: {
+      ...options,
+      challenge: toBuffer(options.challenge),
+      user: { ...options.user, id: toBuffer(options.user.id) },
+      excludeCredentials: credentialDescriptors(options.excludeCredentials),
+    },
+  });
+
+  return {
+    id: credential.id,
+    rawId: toBase64Url(credential.rawId),
+    type: credential.type,
+    response: {
+      attestationObject: toBase64Url(credential.response.attestationObject),
+      clientDataJSON: toBase64Url(credential.response.clientDataJSON),
+    },
+    extensions: credential.getClientExtensionResults(),
+  };
+}
+
+export async function getPasskey(challenge) {
+  const options = challenge.publicKey;
+  const credential = await navigator.credentials.get({
+    publicKey: {
+      ...options,
+      challenge: toBuffer(options.challenge),
+      allowCredentials: credentialDescriptors(options.allowCredentials),
+    },
+  });
+
+  const { response } = credential;
+  return {
+    id: credential.id,
+    rawId: toBase64Url(credential.rawId),
+    type: credential.type,
+    response: {
+      authenticatorData: toBase64Url(response.authenticatorData),
+      clientDataJSON: toBase64Url(response.clientDataJSON),
+      signature: toBase64Url(response.signature),
+      userHandle: response.userHandle ? toBase64Url(response.userHandle) : null,
+    },
+    extensions: credential.getClientExtensionResults(),
+  };
+}
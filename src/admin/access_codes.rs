@@ -15,14 +15,18 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::entities::{access_code, AccessCode};
+use crate::admin::roles::Permission;
+use crate::engagement::{EngagementService, EngagementSummary};
+use crate::entities::{access_code, engagement_event, AccessCode, EngagementEvent};
 use crate::errors::{AppError, AppResult};
-use crate::middleware::AuthenticatedUser;
+use crate::middleware::{require_permission, AuthenticatedUser};
+use crate::settings::SettingsService;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    middleware::from_fn_with_state,
     response::Json,
-    routing::{delete, get},
+    routing::{delete, get, post, put},
     Router,
 };
 use chrono::Utc;
@@ -33,12 +37,57 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct AccessCodeState {
     pub db: DatabaseConnection,
+    pub settings: SettingsService,
+    pub engagement: EngagementService,
 }
 
+/// Settings category used for per access code overrides (entity_id = access code id)
+pub const ACCESS_CODE_SETTINGS_CATEGORY: &str = "access_code";
+
 pub fn access_code_routes() -> Router<AccessCodeState> {
     Router::new()
-        .route("/api/admin/access-codes", get(list_codes).post(create_code))
-        .route("/api/admin/access-codes/{id}", delete(delete_code))
+        .route(
+            "/api/admin/access-codes",
+            get(list_codes).route_layer(from_fn_with_state(
+                Permission::ViewAccessCodes,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/access-codes",
+            post(create_code).route_layer(from_fn_with_state(
+                Permission::ManageAccessCodes,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/access-codes/{id}",
+            delete(delete_code).route_layer(from_fn_with_state(
+                Permission::ManageAccessCodes,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/access-codes/{id}/settings",
+            get(get_code_settings).route_layer(from_fn_with_state(
+                Permission::ViewAccessCodes,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/access-codes/{id}/settings",
+            put(update_code_setting).route_layer(from_fn_with_state(
+                Permission::ManageAccessCodes,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/access-codes/{id}/analytics",
+            get(get_code_analytics).route_layer(from_fn_with_state(
+                Permission::ViewAccessCodes,
+                require_permission,
+            )),
+        )
 }
 
 #[derive(Serialize)]
@@ -117,8 +166,7 @@ async fn create_code(
     let expires_at = if let Some(exp_str) = req.expires_at {
         Some(
             chrono::DateTime::parse_from_rfc3339(&exp_str)
-                .map_err(|_| AppError::AuthError("Invalid expiration date format".to_string()))?
-                .into(),
+                .map_err(|_| AppError::AuthError("Invalid expiration date format".to_string()))?,
         )
     } else {
         None
@@ -152,5 +200,107 @@ async fn delete_code(
     let active_model: access_code::ActiveModel = code.into();
     active_model.delete(&state.db).await?;
 
+    // Engagement data is only meaningful alongside its code
+    EngagementEvent::delete_many()
+        .filter(engagement_event::Column::AccessCodeId.eq(id))
+        .exec(&state.db)
+        .await?;
+
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[derive(Serialize)]
+struct CodeSettingResponse {
+    key: String,
+    value: String,
+}
+
+async fn get_code_settings(
+    State(state): State<AccessCodeState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<CodeSettingResponse>>> {
+    AccessCode::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::AuthError("Access code not found".to_string()))?;
+
+    let settings = state
+        .settings
+        .get_for_entity(ACCESS_CODE_SETTINGS_CATEGORY, id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    let response = settings
+        .into_iter()
+        .map(|s| CodeSettingResponse {
+            key: s.key,
+            value: s.value,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[derive(Deserialize)]
+struct UpdateCodeSettingRequest {
+    key: String,
+    value: String,
+}
+
+async fn update_code_setting(
+    State(state): State<AccessCodeState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateCodeSettingRequest>,
+) -> AppResult<StatusCode> {
+    AccessCode::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::AuthError("Access code not found".to_string()))?;
+
+    state
+        .settings
+        .set(
+            &req.key,
+            &req.value,
+            Some(ACCESS_CODE_SETTINGS_CATEGORY),
+            Some(id),
+        )
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Serialize)]
+struct CodeAnalyticsResponse {
+    id: Uuid,
+    code: String,
+    usage_count: i32,
+    engagement: EngagementSummary,
+}
+
+async fn get_code_analytics(
+    State(state): State<AccessCodeState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<CodeAnalyticsResponse>> {
+    let code = AccessCode::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::AuthError("Access code not found".to_string()))?;
+
+    let engagement = state
+        .engagement
+        .summary(id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(CodeAnalyticsResponse {
+        id: code.id,
+        code: code.code,
+        usage_count: code.usage_count,
+        engagement,
+    }))
+}
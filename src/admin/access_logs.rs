@@ -16,14 +16,16 @@
  */
 
 use crate::admin::pagination::{Paginated, PaginationParams};
+use crate::admin::roles::Permission;
 use crate::entities::{access_log, AccessLog};
 use crate::errors::AppResult;
-use crate::middleware::AuthenticatedUser;
+use crate::middleware::{require_permission, AuthenticatedUser};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
+    middleware::from_fn_with_state,
     response::Json,
-    routing::get,
+    routing::{delete, get},
     Router,
 };
 use sea_orm::{DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryOrder};
@@ -36,7 +38,22 @@ pub struct AccessLogState {
 }
 
 pub fn access_log_routes() -> Router<AccessLogState> {
-    Router::new().route("/api/admin/access-logs", get(list_logs).delete(clear_logs))
+    Router::new()
+        .route(
+            "/api/admin/access-logs",
+            get(list_logs).route_layer(from_fn_with_state(
+                Permission::ViewAccessLogs,
+                require_permission,
+            )),
+        )
+        // Wipes the whole log, so it gets a permission of its own
+        .route(
+            "/api/admin/access-logs",
+            delete(clear_logs).route_layer(from_fn_with_state(
+                Permission::ClearAccessLogs,
+                require_permission,
+            )),
+        )
 }
 
 #[derive(Serialize)]
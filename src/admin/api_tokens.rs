@@ -0,0 +1,210 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Personal API tokens
+// Admins create tokens for scripts, limited to a set of scopes and an expiry. They're sent as
+// `Authorization: Bearer <token>` to the routes behind require_admin_auth, where a request may use a
+// permission only if the token has its scope and the token's admin still has it. Only the SHA-256 hash of
+// a token is stored, the token itself is shown once.
+
+use super::roles::Permission;
+use super::routes::{AdminAuthSession, AdminState};
+use crate::entities::admin_api_token;
+use crate::errors::{AppError, AppResult};
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get},
+    Router,
+};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Start of every token, makes them recognisable to secret scanners and in logs
+pub const API_TOKEN_PREFIX: &str = "pat_";
+
+/// Characters of the token kept in the clear to tell tokens apart
+pub const API_TOKEN_DISPLAY_LENGTH: usize = 12;
+
+pub const MAX_API_TOKEN_NAME_LENGTH: usize = 64;
+
+pub const DEFAULT_API_TOKEN_DAYS: i64 = 90;
+pub const MAX_API_TOKEN_DAYS: i64 = 365;
+
+/// Minimum time between last used updates for one token
+pub const API_TOKEN_TOUCH_SECONDS: i64 = 60;
+
+/// Request extension set by require_admin_auth when it authenticated a token rather than a session
+#[derive(Debug, Clone)]
+pub struct TokenScopes(pub Vec<Permission>);
+
+impl TokenScopes {
+    pub fn allows(&self, permission: Permission) -> bool {
+        self.0.contains(&permission)
+    }
+}
+
+/// Trimmed token name, rejected when empty or too long
+pub fn normalize_token_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Token name is required");
+    }
+    if name.chars().count() > MAX_API_TOKEN_NAME_LENGTH {
+        anyhow::bail!(
+            "Token name must be at most {} characters",
+            MAX_API_TOKEN_NAME_LENGTH
+        );
+    }
+    Ok(name.to_string())
+}
+
+/// Permissions for the given scope names, rejecting unknown ones
+pub fn parse_scopes<S: AsRef<str>>(scopes: &[S]) -> Result<Vec<Permission>> {
+    let mut permissions = Vec::new();
+    for scope in scopes {
+        let scope = scope.as_ref();
+        let permission = Permission::from_scope(scope)
+            .ok_or_else(|| anyhow::anyhow!("Unknown scope: {}", scope))?;
+        if !permissions.contains(&permission) {
+            permissions.push(permission);
+        }
+    }
+    if permissions.is_empty() {
+        anyhow::bail!("At least one scope is required");
+    }
+    Ok(permissions)
+}
+
+/// Scope names as stored on the token
+pub fn format_scopes(permissions: &[Permission]) -> String {
+    permissions
+        .iter()
+        .filter_map(|permission| permission.scope())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn api_token_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/tokens", get(list).post(create))
+        .route("/api/admin/tokens/{id}", delete(revoke))
+}
+
+#[derive(Serialize)]
+struct ApiTokenResponse {
+    id: Uuid,
+    name: String,
+    token_prefix: String,
+    scopes: Vec<String>,
+    expires_at: DateTime<FixedOffset>,
+    last_used_at: Option<DateTime<FixedOffset>>,
+    created_at: DateTime<FixedOffset>,
+}
+
+impl From<admin_api_token::Model> for ApiTokenResponse {
+    fn from(token: admin_api_token::Model) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            scopes: token.scopes.split_whitespace().map(String::from).collect(),
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+async fn list(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<Vec<ApiTokenResponse>>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let tokens = state
+        .auth_backend
+        .list_api_tokens(user.id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Deserialize)]
+struct CreateTokenRequest {
+    name: String,
+    scopes: Vec<String>,
+    #[serde(default)]
+    expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct CreatedTokenResponse {
+    #[serde(flatten)]
+    details: ApiTokenResponse,
+    /// The only time the token is available
+    token: String,
+}
+
+async fn create(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Json(req): Json<CreateTokenRequest>,
+) -> AppResult<Json<CreatedTokenResponse>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let (token, secret) = state
+        .auth_backend
+        .create_api_token(
+            user.id,
+            &req.name,
+            &req.scopes,
+            req.expires_in_days.unwrap_or(DEFAULT_API_TOKEN_DAYS),
+        )
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(CreatedTokenResponse {
+        details: token.into(),
+        token: secret,
+    }))
+}
+
+async fn revoke(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    state
+        .auth_backend
+        .revoke_api_token(user.id, id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -15,25 +15,100 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::entities::{admin_user, AdminUser};
+use super::api_tokens::{
+    self, TokenScopes, API_TOKEN_DISPLAY_LENGTH, API_TOKEN_PREFIX, API_TOKEN_TOUCH_SECONDS,
+    MAX_API_TOKEN_DAYS,
+};
+use super::lockout::{self, FAILURE_WINDOW_HOURS, LOCKOUT_THRESHOLD};
+use super::oidc::{self, OidcProvider, PendingOidcLogin};
+use super::password_policy::PasswordPolicy;
+use super::roles::{Permission, Role};
+use super::sessions::{SESSION_INACTIVITY_DAYS, SESSION_TOUCH_SECONDS};
+use super::{passkeys, two_factor};
+use crate::contact::is_plausible_email;
+use crate::email::EmailService;
+use crate::entities::{
+    admin_api_token, admin_invite, admin_passkey, admin_session, admin_user, AdminApiToken,
+    AdminInvite, AdminPasskey, AdminSession, AdminUser,
+};
 use anyhow::Result;
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
 use axum_login::{AuthUser, AuthnBackend, UserId};
-use chrono::Utc;
+use chrono::{DateTime, FixedOffset, Utc};
+use openidconnect::{
+    core::CoreAuthenticationFlow, AuthorizationCode, CsrfToken, Nonce, PkceCodeChallenge,
+    PkceCodeVerifier, Scope, TokenResponse,
+};
 use rand::Rng;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
-use std::{env, fmt};
+use sha2::{Digest, Sha256};
+use std::{env, fmt, sync::Arc};
+use tower_sessions::{session::Id, SessionStore};
 use uuid::Uuid;
+use webauthn_rs::prelude::{
+    CreationChallengeResponse, CredentialID, Passkey, PasskeyAuthentication, PasskeyRegistration,
+    PublicKeyCredential, RegisterPublicKeyCredential, RequestChallengeResponse, Url, Webauthn,
+    WebauthnBuilder,
+};
+
+/// How long an emailed verification link stays valid
+const VERIFICATION_TTL_HOURS: i64 = 24;
+
+/// Minimum time between two verification emails for the same account
+const VERIFICATION_RESEND_SECONDS: i64 = 60;
+
+/// Days a never-verified account is kept when UNVERIFIED_ADMIN_PURGE_DAYS isn't set
+const DEFAULT_UNVERIFIED_ADMIN_PURGE_DAYS: i64 = 7;
+
+/// How long an emailed password reset link stays valid
+const PASSWORD_RESET_TTL_MINUTES: i64 = 60;
+
+/// Minimum time between two reset emails for the same account
+const PASSWORD_RESET_RESEND_SECONDS: i64 = 60;
+
+/// How long an emailed invite link stays valid
+pub const INVITE_TTL_DAYS: i64 = 7;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdminUserAuth {
     pub id: Uuid,
     pub email: String,
     pub email_verified: bool,
+    pub totp_enabled: bool,
+    pub role: Role,
+    /// Derived from the password hash, so changing the password ends every existing session
+    #[serde(skip)]
+    auth_hash: Vec<u8>,
+}
+
+impl From<admin_user::Model> for AdminUserAuth {
+    fn from(admin: admin_user::Model) -> Self {
+        Self {
+            id: admin.id,
+            email: admin.email,
+            email_verified: admin.email_verified,
+            totp_enabled: admin.totp_enabled,
+            role: admin.role.parse().unwrap_or_else(|e| {
+                tracing::warn!("Admin {}: {}, treating as {}", admin.id, e, Role::Viewer);
+                Role::Viewer
+            }),
+            auth_hash: Sha256::digest(admin.password_hash.as_bytes()).to_vec(),
+        }
+    }
+}
+
+impl AdminUserAuth {
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.role.allows(permission)
+    }
 }
 
 impl AuthUser for AdminUserAuth {
@@ -44,7 +119,7 @@ impl AuthUser for AdminUserAuth {
     }
 
     fn session_auth_hash(&self) -> &[u8] {
-        self.email.as_bytes()
+        &self.auth_hash
     }
 }
 
@@ -52,13 +127,49 @@ impl AuthUser for AdminUserAuth {
 pub struct AdminAuthBackend {
     db: DatabaseConnection,
     allowed_domain: String,
+    webauthn: Arc<Webauthn>,
+    password_policy: PasswordPolicy,
+    email_service: Option<Arc<EmailService>>,
+    session_store: Option<Arc<dyn SessionStore>>,
+    oidc: Option<Arc<OidcProvider>>,
 }
 
 impl AdminAuthBackend {
     pub fn new(db: DatabaseConnection) -> Self {
-        let allowed_domain = env::var("SITE_DOMAIN").unwrap();
+        let allowed_domain = env::var("SITE_DOMAIN").unwrap().to_lowercase();
+        let webauthn = Arc::new(build_webauthn().expect("Invalid SITE_URL for passkeys"));
+
+        Self {
+            db,
+            allowed_domain,
+            webauthn,
+            password_policy: PasswordPolicy::from_env(),
+            email_service: None,
+            session_store: None,
+            oidc: None,
+        }
+    }
+
+    /// Used to tell admins when their account gets locked
+    pub fn with_email_service(mut self, email_service: Arc<EmailService>) -> Self {
+        self.email_service = Some(email_service);
+        self
+    }
 
-        Self { db, allowed_domain }
+    /// Offers single sign-on through an OpenID Connect provider
+    pub fn with_oidc(mut self, provider: OidcProvider) -> Self {
+        self.oidc = Some(Arc::new(provider));
+        self
+    }
+
+    pub fn oidc_provider(&self) -> Option<&OidcProvider> {
+        self.oidc.as_deref()
+    }
+
+    /// Lets revoked sessions be deleted from the store right away
+    pub fn with_session_store(mut self, session_store: Arc<dyn SessionStore>) -> Self {
+        self.session_store = Some(session_store);
+        self
     }
 
     pub async fn create_admin(
@@ -66,18 +177,22 @@ impl AdminAuthBackend {
         email: &str,
         password: &str,
     ) -> Result<(admin_user::Model, String)> {
-        // Validate email domain
-        if !email.ends_with(&format!("@{}", self.allowed_domain)) {
-            anyhow::bail!("Email must be from {} domain", self.allowed_domain);
-        }
+        let email = normalize_email(email);
+        self.ensure_allowed_domain(&email)?;
+        self.check_password_policy(password, &email)?;
 
         // Check if user already exists
         let existing = AdminUser::find()
-            .filter(admin_user::Column::Email.eq(email))
+            .filter(admin_user::Column::Email.eq(&email))
             .one(&self.db)
             .await?;
 
-        if existing.is_some() {
+        if let Some(existing) = existing {
+            if !existing.email_verified {
+                anyhow::bail!(
+                    "This email is waiting to be verified, request a new verification link instead"
+                );
+            }
             anyhow::bail!("Admin user with this email already exists");
         }
 
@@ -86,24 +201,37 @@ impl AdminAuthBackend {
 
         // Generate verification token
         let verification_token = generate_verification_token();
-        let verification_expires = Utc::now() + chrono::Duration::hours(24);
+        let verification_expires = Utc::now() + chrono::Duration::hours(VERIFICATION_TTL_HOURS);
 
-        let admin = admin_user::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            email: Set(email.to_string()),
-            password_hash: Set(password_hash),
-            email_verified: Set(false),
-            verification_token: Set(Some(verification_token.clone())),
-            verification_token_expires_at: Set(Some(verification_expires.into())),
-            created_at: Set(Utc::now().into()),
-            updated_at: Set(Utc::now().into()),
-        };
+        let admin = new_admin(
+            &email,
+            password_hash,
+            self.role_for_new_admin(&self.db).await?,
+            Some((verification_token.clone(), verification_expires)),
+        );
 
         let result = admin.insert(&self.db).await?;
 
         Ok((result, verification_token))
     }
 
+    fn ensure_allowed_domain(&self, email: &str) -> Result<()> {
+        if !email.ends_with(&format!("@{}", self.allowed_domain)) {
+            anyhow::bail!("Email must be from {} domain", self.allowed_domain);
+        }
+        Ok(())
+    }
+
+    /// The first admin owns the site, everyone after starts with the default role
+    async fn role_for_new_admin(&self, db: &impl ConnectionTrait) -> Result<Role> {
+        let admins = AdminUser::find().count(db).await?;
+        Ok(if admins == 0 {
+            Role::Owner
+        } else {
+            Role::DEFAULT
+        })
+    }
+
     pub async fn verify_email(&self, token: &str) -> Result<admin_user::Model> {
         let admin = AdminUser::find()
             .filter(admin_user::Column::VerificationToken.eq(token))
@@ -131,6 +259,1300 @@ impl AdminAuthBackend {
 
         Ok(updated)
     }
+
+    /// New verification token for an unverified admin, None when there is no such account or a
+    /// link was sent moments ago
+    pub async fn request_verification_resend(
+        &self,
+        email: &str,
+    ) -> Result<Option<(admin_user::Model, String)>> {
+        let admin = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(normalize_email(email)))
+            .filter(admin_user::Column::EmailVerified.eq(false))
+            .filter(admin_user::Column::Disabled.eq(false))
+            .one(&self.db)
+            .await?;
+        let Some(admin) = admin else {
+            return Ok(None);
+        };
+
+        if let Some(previous) = admin.verification_token_expires_at {
+            let issued_at =
+                previous.with_timezone(&Utc) - chrono::Duration::hours(VERIFICATION_TTL_HOURS);
+            if Utc::now() - issued_at < chrono::Duration::seconds(VERIFICATION_RESEND_SECONDS) {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(self.rotate_verification_token(admin).await?))
+    }
+
+    /// Replace the verification token, links sent before stop working
+    async fn rotate_verification_token(
+        &self,
+        admin: admin_user::Model,
+    ) -> Result<(admin_user::Model, String)> {
+        let now = Utc::now();
+        let token = generate_verification_token();
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.verification_token = Set(Some(token.clone()));
+        admin_active.verification_token_expires_at = Set(Some(
+            (now + chrono::Duration::hours(VERIFICATION_TTL_HOURS)).into(),
+        ));
+        admin_active.updated_at = Set(now.into());
+        let admin = admin_active.update(&self.db).await?;
+
+        Ok((admin, token))
+    }
+
+    /// Delete accounts created more than `older_than` ago that never verified their email, unless
+    /// a verification link sent to them is still valid. Returns how many were deleted
+    pub async fn purge_unverified_admins(&self, older_than: chrono::Duration) -> Result<u64> {
+        let now = Utc::now();
+        let result = AdminUser::delete_many()
+            .filter(admin_user::Column::EmailVerified.eq(false))
+            .filter(
+                admin_user::Column::CreatedAt.lt(DateTime::<FixedOffset>::from(now - older_than)),
+            )
+            .filter(
+                Condition::any()
+                    .add(admin_user::Column::VerificationTokenExpiresAt.is_null())
+                    .add(
+                        admin_user::Column::VerificationTokenExpiresAt
+                            .lt(DateTime::<FixedOffset>::from(now)),
+                    ),
+            )
+            .exec(&self.db)
+            .await?;
+
+        if result.rows_affected > 0 {
+            tracing::info!(
+                "Purged {} admin accounts that were never verified",
+                result.rows_affected
+            );
+        }
+        Ok(result.rows_affected)
+    }
+}
+
+/// How long never-verified accounts are kept, from UNVERIFIED_ADMIN_PURGE_DAYS and never less than
+/// the lifetime of a verification link
+pub fn unverified_admin_retention() -> chrono::Duration {
+    let days = env::var("UNVERIFIED_ADMIN_PURGE_DAYS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_UNVERIFIED_ADMIN_PURGE_DAYS);
+    chrono::Duration::days(days).max(chrono::Duration::hours(VERIFICATION_TTL_HOURS))
+}
+
+impl AdminAuthBackend {
+    /// Issue a reset token for a verified admin, None when there is no such account or a
+    /// token was sent moments ago
+    pub async fn request_password_reset(
+        &self,
+        email: &str,
+    ) -> Result<Option<(admin_user::Model, String)>> {
+        let admin = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(normalize_email(email)))
+            .filter(admin_user::Column::EmailVerified.eq(true))
+            .filter(admin_user::Column::Disabled.eq(false))
+            .one(&self.db)
+            .await?;
+        let Some(admin) = admin else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::minutes(PASSWORD_RESET_TTL_MINUTES);
+        if let Some(previous) = admin.password_reset_expires_at {
+            let issued_at = previous.with_timezone(&Utc)
+                - chrono::Duration::minutes(PASSWORD_RESET_TTL_MINUTES);
+            if now - issued_at < chrono::Duration::seconds(PASSWORD_RESET_RESEND_SECONDS) {
+                return Ok(None);
+            }
+        }
+
+        // Only the hash is stored, a new request replaces any earlier token
+        let token = generate_verification_token();
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.password_reset_token_hash = Set(Some(hash_token(&token)));
+        admin_active.password_reset_expires_at = Set(Some(expires_at.into()));
+        admin_active.updated_at = Set(now.into());
+        let admin = admin_active.update(&self.db).await?;
+
+        Ok(Some((admin, token)))
+    }
+
+    /// Set a new password with a reset token, the token can only be used once
+    pub async fn reset_password(&self, token: &str, password: &str) -> Result<admin_user::Model> {
+        let token_hash = hash_token(token);
+        let admin = AdminUser::find()
+            .filter(admin_user::Column::PasswordResetTokenHash.eq(&token_hash))
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Invalid or expired reset link"))?;
+
+        // Check if token is expired
+        let expired = admin
+            .password_reset_expires_at
+            .is_none_or(|expires_at| Utc::now() > expires_at.with_timezone(&Utc));
+        if expired {
+            anyhow::bail!("Invalid or expired reset link");
+        }
+        self.check_password_policy(password, &admin.email)?;
+
+        let now = Utc::now();
+        let password_hash = hash_password(password)?;
+
+        // Conditional on the token so two requests racing with it can't both succeed
+        let result = AdminUser::update_many()
+            .col_expr(admin_user::Column::PasswordHash, Expr::value(password_hash))
+            .col_expr(
+                admin_user::Column::PasswordResetTokenHash,
+                Expr::value(Option::<String>::None),
+            )
+            .col_expr(
+                admin_user::Column::PasswordResetExpiresAt,
+                Expr::value(Option::<DateTime<FixedOffset>>::None),
+            )
+            // Proving control of the mailbox lifts any lockout
+            .col_expr(admin_user::Column::FailedLoginAttempts, Expr::value(0))
+            .col_expr(
+                admin_user::Column::LockedUntil,
+                Expr::value(Option::<DateTime<FixedOffset>>::None),
+            )
+            .col_expr(
+                admin_user::Column::UpdatedAt,
+                Expr::value(DateTime::<FixedOffset>::from(now)),
+            )
+            .filter(admin_user::Column::Id.eq(admin.id))
+            .filter(admin_user::Column::PasswordResetTokenHash.eq(&token_hash))
+            .exec(&self.db)
+            .await?;
+        if result.rows_affected != 1 {
+            anyhow::bail!("Invalid or expired reset link");
+        }
+
+        // The new password already invalidates them, this clears them from the session list
+        self.revoke_sessions(admin.id, None).await?;
+
+        tracing::info!("Password reset for admin {}", admin.id);
+        self.find_admin(admin.id).await
+    }
+}
+
+impl AdminAuthBackend {
+    /// Invite an address from any domain, replacing its earlier pending invites
+    pub async fn create_invite(
+        &self,
+        invited_by: Uuid,
+        email: &str,
+    ) -> Result<(admin_invite::Model, String)> {
+        let email = normalize_email(email);
+        if !is_plausible_email(&email) {
+            anyhow::bail!("Invalid email address");
+        }
+
+        let existing = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(&email))
+            .one(&self.db)
+            .await?;
+        if existing.is_some() {
+            anyhow::bail!("Admin user with this email already exists");
+        }
+
+        AdminInvite::delete_many()
+            .filter(admin_invite::Column::Email.eq(&email))
+            .filter(admin_invite::Column::AcceptedAt.is_null())
+            .exec(&self.db)
+            .await?;
+
+        let token = generate_verification_token();
+        let now = Utc::now();
+        let invite = admin_invite::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            email: Set(email),
+            token_hash: Set(hash_token(&token)),
+            invited_by: Set(Some(invited_by)),
+            expires_at: Set((now + chrono::Duration::days(INVITE_TTL_DAYS)).into()),
+            accepted_at: Set(None),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+        }
+        .insert(&self.db)
+        .await?;
+
+        tracing::info!("Admin {} invited {}", invited_by, invite.email);
+        Ok((invite, token))
+    }
+
+    pub async fn list_invites(&self) -> Result<Vec<admin_invite::Model>> {
+        Ok(AdminInvite::find()
+            .order_by_desc(admin_invite::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Withdraw an invite that hasn't been accepted yet
+    pub async fn revoke_invite(&self, id: Uuid) -> Result<()> {
+        let result = AdminInvite::delete_many()
+            .filter(admin_invite::Column::Id.eq(id))
+            .filter(admin_invite::Column::AcceptedAt.is_null())
+            .exec(&self.db)
+            .await?;
+        if result.rows_affected == 0 {
+            anyhow::bail!("Invite not found");
+        }
+        Ok(())
+    }
+
+    /// The pending, unexpired invite for this token
+    pub async fn find_invite(&self, token: &str) -> Result<admin_invite::Model> {
+        AdminInvite::find()
+            .filter(admin_invite::Column::TokenHash.eq(hash_token(token)))
+            .filter(admin_invite::Column::AcceptedAt.is_null())
+            .filter(admin_invite::Column::ExpiresAt.gt(Utc::now()))
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Invalid or expired invite"))
+    }
+
+    /// Create the invited account, verified since the invite link proved the address
+    pub async fn accept_invite(
+        &self,
+        token: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<admin_user::Model> {
+        let invite = self.find_invite(token).await?;
+        if normalize_email(email) != invite.email {
+            anyhow::bail!("This invite is for a different email address");
+        }
+        self.check_password_policy(password, &invite.email)?;
+
+        let existing = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(&invite.email))
+            .one(&self.db)
+            .await?;
+        if existing.is_some() {
+            anyhow::bail!("Admin user with this email already exists");
+        }
+
+        let password_hash = hash_password(password)?;
+        let now = Utc::now();
+
+        // Using up the invite and creating the account succeed or fail together
+        let txn = self.db.begin().await?;
+        let result = AdminInvite::update_many()
+            .col_expr(
+                admin_invite::Column::AcceptedAt,
+                Expr::value(DateTime::<FixedOffset>::from(now)),
+            )
+            .col_expr(
+                admin_invite::Column::UpdatedAt,
+                Expr::value(DateTime::<FixedOffset>::from(now)),
+            )
+            .filter(admin_invite::Column::Id.eq(invite.id))
+            .filter(admin_invite::Column::AcceptedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if result.rows_affected != 1 {
+            anyhow::bail!("Invalid or expired invite");
+        }
+        let role = self.role_for_new_admin(&txn).await?;
+        let admin = new_admin(&invite.email, password_hash, role, None)
+            .insert(&txn)
+            .await?;
+        txn.commit().await?;
+
+        tracing::info!("Invite {} accepted by admin {}", invite.id, admin.id);
+        Ok(admin)
+    }
+}
+
+impl AdminAuthBackend {
+    pub async fn list_admins(&self) -> Result<Vec<admin_user::Model>> {
+        Ok(AdminUser::find()
+            .order_by_asc(admin_user::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn set_admin_role(&self, id: Uuid, role: Role) -> Result<admin_user::Model> {
+        let txn = self.db.begin().await?;
+        let admin = self.find_admin(id).await?;
+        if role != Role::Owner {
+            ensure_not_last_owner(&txn, &admin).await?;
+        }
+
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.role = Set(role.as_str().to_string());
+        admin_active.updated_at = Set(Utc::now().into());
+        let admin = admin_active.update(&txn).await?;
+        txn.commit().await?;
+
+        tracing::info!("Admin {} is now {}", admin.id, role);
+        Ok(admin)
+    }
+
+    /// Disabled admins can't log in and lose their sessions, their account is kept as is
+    pub async fn set_admin_disabled(&self, id: Uuid, disabled: bool) -> Result<admin_user::Model> {
+        let txn = self.db.begin().await?;
+        let admin = self.find_admin(id).await?;
+        if disabled {
+            ensure_not_last_owner(&txn, &admin).await?;
+        }
+
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.disabled = Set(disabled);
+        admin_active.updated_at = Set(Utc::now().into());
+        let admin = admin_active.update(&txn).await?;
+        txn.commit().await?;
+        if disabled {
+            self.revoke_sessions(admin.id, None).await?;
+        }
+
+        tracing::info!(
+            "Admin {} {}",
+            admin.id,
+            if disabled { "disabled" } else { "enabled" }
+        );
+        Ok(admin)
+    }
+
+    /// Passkeys go with the account, invites it sent are kept
+    pub async fn delete_admin(&self, id: Uuid) -> Result<()> {
+        let txn = self.db.begin().await?;
+        let admin = self.find_admin(id).await?;
+        ensure_not_last_owner(&txn, &admin).await?;
+
+        // The rows go with the account, the store entries are removed once it's gone
+        let sessions = AdminSession::find()
+            .filter(admin_session::Column::AdminUserId.eq(admin.id))
+            .all(&txn)
+            .await?;
+        AdminUser::delete_by_id(admin.id).exec(&txn).await?;
+        txn.commit().await?;
+        self.end_sessions(sessions).await?;
+
+        tracing::info!("Admin {} deleted", admin.id);
+        Ok(())
+    }
+
+    /// New verification token for an admin who hasn't verified yet, the old link stops working
+    pub async fn resend_verification(&self, id: Uuid) -> Result<(admin_user::Model, String)> {
+        let admin = self.find_admin(id).await?;
+        if admin.email_verified {
+            return Err(ManagementConflict("Email is already verified".to_string()).into());
+        }
+
+        self.rotate_verification_token(admin).await
+    }
+}
+
+impl AdminAuthBackend {
+    /// Start tracking a logged in session, `session_id` is None until the store has saved it
+    pub async fn start_session(
+        &self,
+        admin_id: Uuid,
+        session_id: Option<String>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<admin_session::Model> {
+        let now = Utc::now();
+        Ok(admin_session::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            admin_user_id: Set(admin_id),
+            session_id: Set(session_id),
+            ip_address: Set(ip_address),
+            user_agent: Set(user_agent),
+            created_at: Set(now.into()),
+            last_seen_at: Set(now.into()),
+        }
+        .insert(&self.db)
+        .await?)
+    }
+
+    /// Record another request on a tracked session, None once it has been revoked
+    pub async fn touch_session(
+        &self,
+        id: Uuid,
+        session_id: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<Option<admin_session::Model>> {
+        let Some(session) = AdminSession::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        // The store id changes on login, the rest only needs to be roughly current
+        let now = Utc::now();
+        let stale = now - session.last_seen_at.with_timezone(&Utc)
+            >= chrono::Duration::seconds(SESSION_TOUCH_SECONDS);
+        let moved = session_id.is_some() && session_id != session.session_id;
+        if !stale && !moved && ip_address == session.ip_address {
+            return Ok(Some(session));
+        }
+
+        let mut session: admin_session::ActiveModel = session.into();
+        if session_id.is_some() {
+            session.session_id = Set(session_id);
+        }
+        session.ip_address = Set(ip_address);
+        session.last_seen_at = Set(now.into());
+        Ok(Some(session.update(&self.db).await?))
+    }
+
+    /// Stop tracking a session without ending it, for when another admin logs in on it
+    pub async fn forget_session(&self, id: Uuid) -> Result<()> {
+        AdminSession::delete_by_id(id).exec(&self.db).await?;
+        Ok(())
+    }
+
+    /// Sessions used within the inactivity timeout, most recent first
+    pub async fn list_sessions(&self, admin_id: Uuid) -> Result<Vec<admin_session::Model>> {
+        let cutoff = Utc::now() - chrono::Duration::days(SESSION_INACTIVITY_DAYS);
+
+        // The store has already expired these
+        AdminSession::delete_many()
+            .filter(admin_session::Column::AdminUserId.eq(admin_id))
+            .filter(admin_session::Column::LastSeenAt.lt(cutoff))
+            .exec(&self.db)
+            .await?;
+
+        Ok(AdminSession::find()
+            .filter(admin_session::Column::AdminUserId.eq(admin_id))
+            .order_by_desc(admin_session::Column::LastSeenAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn revoke_session(&self, admin_id: Uuid, id: Uuid) -> Result<()> {
+        let session = AdminSession::find_by_id(id)
+            .filter(admin_session::Column::AdminUserId.eq(admin_id))
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+
+        self.end_sessions(vec![session]).await
+    }
+
+    /// Every session of the admin except `keep`, returns how many were ended
+    pub async fn revoke_sessions(&self, admin_id: Uuid, keep: Option<Uuid>) -> Result<usize> {
+        let mut query =
+            AdminSession::find().filter(admin_session::Column::AdminUserId.eq(admin_id));
+        if let Some(keep) = keep {
+            query = query.filter(admin_session::Column::Id.ne(keep));
+        }
+        let sessions = query.all(&self.db).await?;
+        let count = sessions.len();
+
+        self.end_sessions(sessions).await?;
+        Ok(count)
+    }
+
+    async fn end_sessions(&self, sessions: Vec<admin_session::Model>) -> Result<()> {
+        if sessions.is_empty() {
+            return Ok(());
+        }
+
+        // Without its row a session is logged out on its next request, deleting it from the store
+        // as well just means it can't even make that request
+        AdminSession::delete_many()
+            .filter(admin_session::Column::Id.is_in(sessions.iter().map(|s| s.id)))
+            .exec(&self.db)
+            .await?;
+
+        let Some(store) = &self.session_store else {
+            return Ok(());
+        };
+        for session in sessions {
+            let Some(session_id) = session.session_id.and_then(|id| id.parse::<Id>().ok()) else {
+                continue;
+            };
+            if let Err(e) = store.delete(&session_id).await {
+                tracing::warn!(
+                    "Failed to delete session {} from the store: {}",
+                    session.id,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AdminAuthBackend {
+    /// New token limited to scopes the admin's role allows, returns the token itself once
+    pub async fn create_api_token<S: AsRef<str>>(
+        &self,
+        admin_id: Uuid,
+        name: &str,
+        scopes: &[S],
+        expires_in_days: i64,
+    ) -> Result<(admin_api_token::Model, String)> {
+        let name = api_tokens::normalize_token_name(name)?;
+        let permissions = api_tokens::parse_scopes(scopes)?;
+        if !(1..=MAX_API_TOKEN_DAYS).contains(&expires_in_days) {
+            anyhow::bail!("Tokens must expire within 1 to {} days", MAX_API_TOKEN_DAYS);
+        }
+
+        let admin = AdminUserAuth::from(self.find_admin(admin_id).await?);
+        if let Some(denied) = permissions.iter().find(|p| !admin.has_permission(**p)) {
+            anyhow::bail!(
+                "The {} role can't grant the {} scope",
+                admin.role,
+                denied.scope().unwrap_or_default()
+            );
+        }
+
+        let secret = format!("{}{}", API_TOKEN_PREFIX, generate_verification_token());
+        let now = Utc::now();
+        let token = admin_api_token::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            admin_user_id: Set(admin_id),
+            name: Set(name),
+            token_prefix: Set(secret[..API_TOKEN_DISPLAY_LENGTH].to_string()),
+            token_hash: Set(hash_token(&secret)),
+            scopes: Set(api_tokens::format_scopes(&permissions)),
+            expires_at: Set((now + chrono::Duration::days(expires_in_days)).into()),
+            last_used_at: Set(None),
+            created_at: Set(now.into()),
+        }
+        .insert(&self.db)
+        .await?;
+
+        tracing::info!("API token {} created for admin {}", token.id, admin_id);
+        Ok((token, secret))
+    }
+
+    pub async fn list_api_tokens(&self, admin_id: Uuid) -> Result<Vec<admin_api_token::Model>> {
+        Ok(AdminApiToken::find()
+            .filter(admin_api_token::Column::AdminUserId.eq(admin_id))
+            .order_by_desc(admin_api_token::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn revoke_api_token(&self, admin_id: Uuid, id: Uuid) -> Result<()> {
+        let result = AdminApiToken::delete_many()
+            .filter(admin_api_token::Column::Id.eq(id))
+            .filter(admin_api_token::Column::AdminUserId.eq(admin_id))
+            .exec(&self.db)
+            .await?;
+        if result.rows_affected == 0 {
+            anyhow::bail!("Token not found");
+        }
+
+        tracing::info!("API token {} revoked for admin {}", id, admin_id);
+        Ok(())
+    }
+
+    /// The admin behind a bearer token and the token's scopes, None for unknown or expired tokens and
+    /// admins who couldn't log in right now
+    pub async fn authenticate_api_token(
+        &self,
+        secret: &str,
+    ) -> Result<Option<(AdminUserAuth, TokenScopes)>> {
+        if !secret.starts_with(API_TOKEN_PREFIX) {
+            return Ok(None);
+        }
+
+        let now = Utc::now();
+        let Some(token) = AdminApiToken::find()
+            .filter(admin_api_token::Column::TokenHash.eq(hash_token(secret)))
+            .filter(admin_api_token::Column::ExpiresAt.gt(now))
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let admin = self.find_admin(token.admin_user_id).await?;
+        if !admin.email_verified || admin.disabled || ensure_not_locked(&admin).is_err() {
+            return Ok(None);
+        }
+
+        let scopes = TokenScopes(
+            token
+                .scopes
+                .split_whitespace()
+                .filter_map(Permission::from_scope)
+                .collect(),
+        );
+
+        let stale = token.last_used_at.is_none_or(|at| {
+            now - at.with_timezone(&Utc) >= chrono::Duration::seconds(API_TOKEN_TOUCH_SECONDS)
+        });
+        if stale {
+            let mut token: admin_api_token::ActiveModel = token.into();
+            token.last_used_at = Set(Some(now.into()));
+            token.update(&self.db).await?;
+        }
+
+        Ok(Some((admin.into(), scopes)))
+    }
+}
+
+impl AdminAuthBackend {
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+
+    fn check_password_policy(&self, password: &str, email: &str) -> Result<()> {
+        self.password_policy
+            .check(password, email)
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Replace the password of a logged in admin who knows the current one
+    pub async fn change_password(
+        &self,
+        id: Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<admin_user::Model> {
+        let admin = self.find_admin(id).await?;
+        if !verify_password(current_password, &admin.password_hash)? {
+            anyhow::bail!("Current password is incorrect");
+        }
+        if current_password == new_password {
+            anyhow::bail!("New password must be different from the current one");
+        }
+        self.check_password_policy(new_password, &admin.email)?;
+
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.password_hash = Set(hash_password(new_password)?);
+        // A pending reset link must not undo the change
+        admin_active.password_reset_token_hash = Set(None);
+        admin_active.password_reset_expires_at = Set(None);
+        admin_active.updated_at = Set(Utc::now().into());
+        let admin = admin_active.update(&self.db).await?;
+
+        tracing::info!("Password changed for admin {}", id);
+        Ok(admin)
+    }
+}
+
+impl AdminAuthBackend {
+    pub async fn find_admin(&self, id: Uuid) -> Result<admin_user::Model> {
+        AdminUser::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Admin user not found"))
+    }
+
+    pub async fn verify_admin_password(&self, id: Uuid, password: &str) -> Result<bool> {
+        let admin = self.find_admin(id).await?;
+        verify_password(password, &admin.password_hash)
+    }
+
+    /// Store a new, not yet enabled TOTP secret and return it with its provisioning URI
+    pub async fn begin_totp_enrollment(&self, id: Uuid) -> Result<(String, String)> {
+        let admin = self.find_admin(id).await?;
+        if admin.totp_enabled {
+            anyhow::bail!("Two-factor authentication is already enabled");
+        }
+
+        let secret = two_factor::generate_secret();
+        let uri = two_factor::provisioning_uri(&secret, &self.allowed_domain, &admin.email)?;
+
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.totp_secret = Set(Some(secret.clone()));
+        admin_active.updated_at = Set(Utc::now().into());
+        admin_active.update(&self.db).await?;
+
+        Ok((secret, uri))
+    }
+
+    /// Enable TOTP once the user proves their app has the secret, returns new recovery codes
+    pub async fn confirm_totp_enrollment(&self, id: Uuid, code: &str) -> Result<Vec<String>> {
+        let admin = self.find_admin(id).await?;
+        if admin.totp_enabled {
+            anyhow::bail!("Two-factor authentication is already enabled");
+        }
+        let Some(secret) = admin.totp_secret.clone() else {
+            anyhow::bail!("Two-factor setup has not been started");
+        };
+
+        let Some(step) = two_factor::verify_totp(&secret, code, None, unix_now())? else {
+            anyhow::bail!("Invalid authentication code");
+        };
+
+        let recovery_codes = two_factor::generate_recovery_codes();
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.totp_enabled = Set(true);
+        admin_active.totp_last_step = Set(Some(step));
+        admin_active.recovery_codes = Set(Some(hash_recovery_codes(&recovery_codes)?));
+        admin_active.updated_at = Set(Utc::now().into());
+        admin_active.update(&self.db).await?;
+
+        tracing::info!("Two-factor authentication enabled for admin {}", id);
+        Ok(recovery_codes)
+    }
+
+    /// Check a TOTP code or consume a recovery code
+    pub async fn verify_second_factor(&self, id: Uuid, code: &str) -> Result<bool> {
+        let admin = self.find_admin(id).await?;
+        let (true, Some(secret)) = (admin.totp_enabled, admin.totp_secret.as_deref()) else {
+            return Ok(false);
+        };
+
+        if two_factor::is_totp_code(code) {
+            let Some(step) =
+                two_factor::verify_totp(secret, code, admin.totp_last_step, unix_now())?
+            else {
+                return Ok(false);
+            };
+
+            // Conditional update so two requests racing with the same code can't both succeed
+            let result = AdminUser::update_many()
+                .col_expr(admin_user::Column::TotpLastStep, Expr::value(step))
+                .filter(admin_user::Column::Id.eq(id))
+                .filter(
+                    Condition::any()
+                        .add(admin_user::Column::TotpLastStep.is_null())
+                        .add(admin_user::Column::TotpLastStep.lt(step)),
+                )
+                .exec(&self.db)
+                .await?;
+            return Ok(result.rows_affected == 1);
+        }
+
+        let hash = two_factor::hash_recovery_code(code);
+        let mut remaining: Vec<String> = admin
+            .recovery_codes
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        let Some(index) = remaining.iter().position(|h| *h == hash) else {
+            return Ok(false);
+        };
+        remaining.remove(index);
+
+        let result = AdminUser::update_many()
+            .col_expr(
+                admin_user::Column::RecoveryCodes,
+                Expr::value(serde_json::to_string(&remaining)?),
+            )
+            .filter(admin_user::Column::Id.eq(id))
+            .filter(admin_user::Column::RecoveryCodes.eq(admin.recovery_codes))
+            .exec(&self.db)
+            .await?;
+
+        if result.rows_affected == 1 {
+            tracing::info!(
+                "Recovery code used for admin {}, {} remaining",
+                id,
+                remaining.len()
+            );
+        }
+        Ok(result.rows_affected == 1)
+    }
+
+    pub async fn regenerate_recovery_codes(&self, id: Uuid) -> Result<Vec<String>> {
+        let admin = self.find_admin(id).await?;
+        if !admin.totp_enabled {
+            anyhow::bail!("Two-factor authentication is not enabled");
+        }
+
+        let recovery_codes = two_factor::generate_recovery_codes();
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.recovery_codes = Set(Some(hash_recovery_codes(&recovery_codes)?));
+        admin_active.updated_at = Set(Utc::now().into());
+        admin_active.update(&self.db).await?;
+
+        Ok(recovery_codes)
+    }
+
+    pub async fn disable_totp(&self, id: Uuid) -> Result<()> {
+        let admin = self.find_admin(id).await?;
+
+        let mut admin_active: admin_user::ActiveModel = admin.into();
+        admin_active.totp_secret = Set(None);
+        admin_active.totp_enabled = Set(false);
+        admin_active.totp_last_step = Set(None);
+        admin_active.recovery_codes = Set(None);
+        admin_active.updated_at = Set(Utc::now().into());
+        admin_active.update(&self.db).await?;
+
+        tracing::info!("Two-factor authentication disabled for admin {}", id);
+        Ok(())
+    }
+}
+
+impl AdminAuthBackend {
+    pub async fn list_passkeys(&self, id: Uuid) -> Result<Vec<admin_passkey::Model>> {
+        Ok(AdminPasskey::find()
+            .filter(admin_passkey::Column::AdminUserId.eq(id))
+            .order_by_asc(admin_passkey::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    async fn load_passkeys(&self, id: Uuid) -> Result<Vec<Passkey>> {
+        self.list_passkeys(id)
+            .await?
+            .iter()
+            .map(|row| Ok(serde_json::from_str(&row.passkey)?))
+            .collect()
+    }
+
+    /// Registration challenge that excludes the admin's existing passkeys
+    pub async fn begin_passkey_registration(
+        &self,
+        id: Uuid,
+    ) -> Result<(CreationChallengeResponse, PasskeyRegistration)> {
+        let admin = self.find_admin(id).await?;
+        let exclude: Vec<CredentialID> = self
+            .load_passkeys(id)
+            .await?
+            .iter()
+            .map(|passkey| passkey.cred_id().clone())
+            .collect();
+
+        Ok(self.webauthn.start_passkey_registration(
+            admin.id,
+            &admin.email,
+            &admin.email,
+            Some(exclude),
+        )?)
+    }
+
+    pub async fn finish_passkey_registration(
+        &self,
+        id: Uuid,
+        name: &str,
+        response: &RegisterPublicKeyCredential,
+        state: &PasskeyRegistration,
+    ) -> Result<admin_passkey::Model> {
+        let name = passkeys::normalize_passkey_name(name)?;
+        let passkey = self.webauthn.finish_passkey_registration(response, state)?;
+
+        let now = Utc::now().into();
+        let row = admin_passkey::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            admin_user_id: Set(id),
+            name: Set(name),
+            credential_id: Set(hex::encode(passkey.cred_id())),
+            passkey: Set(serde_json::to_string(&passkey)?),
+            last_used_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await?;
+
+        tracing::info!("Passkey {} registered for admin {}", row.id, id);
+        Ok(row)
+    }
+
+    pub async fn rename_passkey(
+        &self,
+        id: Uuid,
+        passkey_id: Uuid,
+        name: &str,
+    ) -> Result<admin_passkey::Model> {
+        let name = passkeys::normalize_passkey_name(name)?;
+        let row = AdminPasskey::find_by_id(passkey_id)
+            .filter(admin_passkey::Column::AdminUserId.eq(id))
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Passkey not found"))?;
+
+        let mut row: admin_passkey::ActiveModel = row.into();
+        row.name = Set(name);
+        row.updated_at = Set(Utc::now().into());
+        Ok(row.update(&self.db).await?)
+    }
+
+    pub async fn delete_passkey(&self, id: Uuid, passkey_id: Uuid) -> Result<()> {
+        let result = AdminPasskey::delete_many()
+            .filter(admin_passkey::Column::Id.eq(passkey_id))
+            .filter(admin_passkey::Column::AdminUserId.eq(id))
+            .exec(&self.db)
+            .await?;
+        if result.rows_affected == 0 {
+            anyhow::bail!("Passkey not found");
+        }
+
+        tracing::info!("Passkey {} revoked for admin {}", passkey_id, id);
+        Ok(())
+    }
+
+    /// Login challenge for the passkeys of the admin with this email, None if there are none
+    pub async fn begin_passkey_authentication(
+        &self,
+        email: &str,
+    ) -> Result<Option<(RequestChallengeResponse, PasskeyAuthentication)>> {
+        let admin = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(normalize_email(email)))
+            .one(&self.db)
+            .await?;
+        let Some(admin) = admin else {
+            return Ok(None);
+        };
+
+        let passkeys = self.load_passkeys(admin.id).await?;
+        if passkeys.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.webauthn.start_passkey_authentication(&passkeys)?))
+    }
+
+    async fn authenticate_password(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<Option<admin_user::Model>> {
+        let admin = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(normalize_email(email)))
+            .one(&self.db)
+            .await?;
+
+        let Some(admin) = admin else {
+            return Ok(None);
+        };
+        ensure_not_locked(&admin)?;
+
+        // Verify password
+        if !verify_password(password, &admin.password_hash)? {
+            self.record_failed_login(admin.id).await?;
+            return Ok(None);
+        }
+
+        Ok(Some(admin))
+    }
+
+    /// Passkeys can't be guessed, so unlike passwords they still work while the account is
+    /// locked out, and a successful one clears the lock
+    async fn authenticate_passkey(
+        &self,
+        response: &PublicKeyCredential,
+        state: &PasskeyAuthentication,
+    ) -> Result<Option<admin_user::Model>> {
+        let result = match self.webauthn.finish_passkey_authentication(response, state) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("Passkey authentication failed: {}", e);
+                return Ok(None);
+            }
+        };
+
+        let row = AdminPasskey::find()
+            .filter(admin_passkey::Column::CredentialId.eq(hex::encode(result.cred_id())))
+            .one(&self.db)
+            .await?;
+        let Some(row) = row else {
+            // Revoked between the start and finish of the ceremony
+            return Ok(None);
+        };
+
+        // Persist the new signature counter and backup state
+        let mut passkey: Passkey = serde_json::from_str(&row.passkey)?;
+        passkey.update_credential(&result);
+        let admin_user_id = row.admin_user_id;
+        let now = Utc::now();
+        let mut row: admin_passkey::ActiveModel = row.into();
+        row.passkey = Set(serde_json::to_string(&passkey)?);
+        row.last_used_at = Set(Some(now.into()));
+        row.updated_at = Set(now.into());
+        row.update(&self.db).await?;
+
+        Ok(AdminUser::find_by_id(admin_user_id).one(&self.db).await?)
+    }
+}
+
+impl AdminAuthBackend {
+    /// Authorization URL at the provider, and the secrets needed to finish the login
+    pub async fn begin_oidc_authentication(&self) -> Result<(Url, PendingOidcLogin)> {
+        let provider = self
+            .oidc
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Single sign-on is not configured"))?;
+        let client = provider.client().await?;
+
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let (auth_url, csrf_state, nonce) = client
+            .authorize_url(
+                CoreAuthenticationFlow::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .add_scope(Scope::new("email".to_string()))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        Ok((
+            auth_url,
+            PendingOidcLogin {
+                csrf_state: csrf_state.secret().clone(),
+                nonce: nonce.secret().clone(),
+                pkce_verifier: pkce_verifier.secret().clone(),
+            },
+        ))
+    }
+
+    async fn authenticate_oidc(
+        &self,
+        code: String,
+        csrf_state: &str,
+        pending: PendingOidcLogin,
+        provision: bool,
+    ) -> Result<Option<admin_user::Model>> {
+        let Some(provider) = &self.oidc else {
+            return Ok(None);
+        };
+        if csrf_state != pending.csrf_state {
+            tracing::warn!("OIDC callback with a mismatched state");
+            return Ok(None);
+        }
+
+        let client = provider.client().await?;
+        let token_response = match client
+            .exchange_code(AuthorizationCode::new(code))?
+            .set_pkce_verifier(PkceCodeVerifier::new(pending.pkce_verifier))
+            .request_async(provider.http_client())
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("OIDC code exchange failed: {}", e);
+                return Ok(None);
+            }
+        };
+
+        let id_token = token_response
+            .id_token()
+            .ok_or_else(|| anyhow::anyhow!("The identity provider did not return an ID token"))?;
+        let claims = match id_token.claims(&client.id_token_verifier(), &Nonce::new(pending.nonce))
+        {
+            Ok(claims) => claims,
+            Err(e) => {
+                tracing::warn!("OIDC ID token rejected: {}", e);
+                return Ok(None);
+            }
+        };
+        let email = oidc::verified_email(claims)?;
+        self.ensure_allowed_domain(&email)?;
+
+        let admin = AdminUser::find()
+            .filter(admin_user::Column::Email.eq(&email))
+            .one(&self.db)
+            .await?;
+        let admin = match admin {
+            // The provider has verified the address, which is what the emailed link is for. Whoever
+            // registered it may not own it, so nothing they set up survives
+            Some(admin) if !admin.email_verified => {
+                let txn = self.db.begin().await?;
+                AdminPasskey::delete_many()
+                    .filter(admin_passkey::Column::AdminUserId.eq(admin.id))
+                    .exec(&txn)
+                    .await?;
+                let mut admin: admin_user::ActiveModel = admin.into();
+                admin.email_verified = Set(true);
+                admin.verification_token = Set(None);
+                admin.verification_token_expires_at = Set(None);
+                admin.password_hash = Set(hash_password(&generate_verification_token())?);
+                admin.password_reset_token_hash = Set(None);
+                admin.password_reset_expires_at = Set(None);
+                admin.totp_secret = Set(None);
+                admin.totp_enabled = Set(false);
+                admin.totp_last_step = Set(None);
+                admin.recovery_codes = Set(None);
+                admin.updated_at = Set(Utc::now().into());
+                let admin = admin.update(&txn).await?;
+                txn.commit().await?;
+                admin
+            }
+            Some(admin) => admin,
+            None if provision => {
+                // Single sign-on only, a password can be set later through a reset
+                let password_hash = hash_password(&generate_verification_token())?;
+                let txn = self.db.begin().await?;
+                let role = self.role_for_new_admin(&txn).await?;
+                let admin = new_admin(&email, password_hash, role, None)
+                    .insert(&txn)
+                    .await?;
+                txn.commit().await?;
+                tracing::info!("Created admin {} through single sign-on", admin.email);
+                admin
+            }
+            None => anyhow::bail!("There is no admin account for {}", email),
+        };
+
+        Ok(Some(admin))
+    }
+}
+
+impl AdminAuthBackend {
+    /// Refuse login steps while the account is locked
+    pub async fn ensure_login_allowed(&self, id: Uuid) -> Result<()> {
+        ensure_not_locked(&self.find_admin(id).await?)
+    }
+
+    /// Count a failed password or second factor, returns when the account is locked until
+    pub async fn record_failed_login(&self, id: Uuid) -> Result<Option<DateTime<Utc>>> {
+        // Optimistic retries so parallel guesses can't share one count
+        for _ in 0..5 {
+            let admin = self.find_admin(id).await?;
+            let now = Utc::now();
+            let recent = admin.last_failed_login_at.is_some_and(|at| {
+                now - at.with_timezone(&Utc) < chrono::Duration::hours(FAILURE_WINDOW_HOURS)
+            });
+            let failures = if recent {
+                admin.failed_login_attempts + 1
+            } else {
+                1
+            };
+            let locked_until = lockout::lockout_duration(failures).map(|duration| now + duration);
+
+            let result = AdminUser::update_many()
+                .col_expr(
+                    admin_user::Column::FailedLoginAttempts,
+                    Expr::value(failures),
+                )
+                .col_expr(
+                    admin_user::Column::LastFailedLoginAt,
+                    Expr::value(DateTime::<FixedOffset>::from(now)),
+                )
+                .col_expr(
+                    admin_user::Column::LockedUntil,
+                    Expr::value(locked_until.map(DateTime::<FixedOffset>::from)),
+                )
+                .filter(admin_user::Column::Id.eq(id))
+                .filter(admin_user::Column::FailedLoginAttempts.eq(admin.failed_login_attempts))
+                .exec(&self.db)
+                .await?;
+            if result.rows_affected != 1 {
+                continue;
+            }
+
+            if let Some(until) = locked_until {
+                tracing::warn!(
+                    "Admin {} locked until {} after {} failed logins",
+                    id,
+                    until,
+                    failures
+                );
+                // Only the first lockout of a run is emailed, the longer ones after it are not
+                if failures == LOCKOUT_THRESHOLD {
+                    self.notify_lockout(admin.email, until);
+                }
+            }
+            return Ok(locked_until);
+        }
+
+        anyhow::bail!("Could not record failed login")
+    }
+
+    /// Reset the failure count once a login has fully succeeded, second factor included.
+    /// Only passwords are refused while locked out, passkeys and single sign-on can't be guessed
+    /// so they still get through and lift the lock
+    pub async fn clear_failed_logins(&self, id: Uuid) -> Result<()> {
+        AdminUser::update_many()
+            .col_expr(admin_user::Column::FailedLoginAttempts, Expr::value(0))
+            .col_expr(
+                admin_user::Column::LastFailedLoginAt,
+                Expr::value(Option::<DateTime<FixedOffset>>::None),
+            )
+            .col_expr(
+                admin_user::Column::LockedUntil,
+                Expr::value(Option::<DateTime<FixedOffset>>::None),
+            )
+            .filter(admin_user::Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(admin_user::Column::FailedLoginAttempts.ne(0))
+                    .add(admin_user::Column::LockedUntil.is_not_null()),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Sent in the background so a lockout doesn't answer slower than other failures
+    fn notify_lockout(&self, to_email: String, locked_until: DateTime<Utc>) {
+        let Some(email_service) = self.email_service.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            if let Err(e) = email_service
+                .send_account_locked_email(&to_email, locked_until)
+                .await
+            {
+                tracing::error!("{}", e);
+            }
+        });
+    }
+}
+
+/// A user management change the rules don't allow, answered as a conflict rather than an
+/// authentication failure
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct ManagementConflict(pub String);
+
+/// Someone has to be left who can manage admins. The active owners stay locked until `txn`
+/// ends, so two owners demoting or removing each other can't both get through
+async fn ensure_not_last_owner(txn: &DatabaseTransaction, admin: &admin_user::Model) -> Result<()> {
+    if admin.role != Role::Owner.as_str() || admin.disabled {
+        return Ok(());
+    }
+
+    let owners = AdminUser::find()
+        .filter(admin_user::Column::Role.eq(Role::Owner.as_str()))
+        .filter(admin_user::Column::Disabled.eq(false))
+        .order_by_asc(admin_user::Column::Id)
+        .lock_exclusive()
+        .all(txn)
+        .await?;
+    if !owners.iter().any(|owner| owner.id != admin.id) {
+        return Err(ManagementConflict("At least one active owner must remain".to_string()).into());
+    }
+    Ok(())
+}
+
+fn ensure_not_locked(admin: &admin_user::Model) -> Result<()> {
+    let Some(locked_until) = admin.locked_until else {
+        return Ok(());
+    };
+    let remaining = locked_until.with_timezone(&Utc) - Utc::now();
+    if remaining > chrono::Duration::zero() {
+        anyhow::bail!(
+            "Account temporarily locked after too many failed logins, try again in {} minutes",
+            lockout::minutes_remaining(remaining)
+        );
+    }
+    Ok(())
+}
+
+/// Passkeys are bound to the host of SITE_URL, where the admin panel is served
+fn build_webauthn() -> Result<Webauthn> {
+    let site_url = env::var("SITE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let origin = Url::parse(&site_url)?;
+    let rp_id = origin
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("SITE_URL has no host"))?
+        .to_string();
+
+    Ok(WebauthnBuilder::new(&rp_id, &origin)?
+        .rp_name(&rp_id)
+        .build()?)
+}
+
+fn hash_recovery_codes(codes: &[String]) -> Result<String> {
+    let hashes: Vec<String> = codes
+        .iter()
+        .map(|code| two_factor::hash_recovery_code(code))
+        .collect();
+    Ok(serde_json::to_string(&hashes)?)
+}
+
+fn unix_now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
 }
 
 #[derive(Debug)]
@@ -165,25 +1587,31 @@ impl AuthnBackend for AdminAuthBackend {
         &self,
         creds: Self::Credentials,
     ) -> impl std::future::Future<Output = Result<Option<Self::User>, Self::Error>> + Send {
-        let db = self.db.clone();
+        let backend = self.clone();
         async move {
-            let admin = AdminUser::find()
-                .filter(admin_user::Column::Email.eq(&creds.email))
-                .one(&db)
-                .await
-                .map_err(AuthError::from)?;
+            let admin = match creds {
+                Credentials::Password { email, password } => {
+                    backend.authenticate_password(&email, &password).await?
+                }
+                Credentials::Passkey { response, state } => {
+                    backend.authenticate_passkey(&response, &state).await?
+                }
+                Credentials::Oidc {
+                    code,
+                    csrf_state,
+                    pending,
+                    provision,
+                } => {
+                    backend
+                        .authenticate_oidc(code, &csrf_state, *pending, provision)
+                        .await?
+                }
+            };
 
             let Some(admin) = admin else {
                 return Ok(None);
             };
 
-            // Verify password
-            let valid =
-                verify_password(&creds.password, &admin.password_hash).map_err(AuthError::from)?;
-            if !valid {
-                return Ok(None);
-            }
-
             // Check if email is verified
             if !admin.email_verified {
                 return Err(AuthError(anyhow::anyhow!(
@@ -191,11 +1619,11 @@ impl AuthnBackend for AdminAuthBackend {
                 )));
             }
 
-            Ok(Some(AdminUserAuth {
-                id: admin.id,
-                email: admin.email,
-                email_verified: admin.email_verified,
-            }))
+            if admin.disabled {
+                return Err(AuthError(anyhow::anyhow!("This account has been disabled")));
+            }
+
+            Ok(Some(admin.into()))
         }
     }
 
@@ -211,19 +1639,33 @@ impl AuthnBackend for AdminAuthBackend {
                 .await
                 .map_err(AuthError::from)?;
 
-            Ok(admin.map(|a| AdminUserAuth {
-                id: a.id,
-                email: a.email,
-                email_verified: a.email_verified,
-            }))
+            // Disabling an account ends its sessions on their next request
+            Ok(admin
+                .filter(|admin| !admin.disabled)
+                .map(AdminUserAuth::from))
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct Credentials {
-    pub email: String,
-    pub password: String,
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    Password {
+        email: String,
+        password: String,
+    },
+    /// The authenticator's response to a challenge, with the ceremony state kept server side
+    Passkey {
+        response: Box<PublicKeyCredential>,
+        state: Box<PasskeyAuthentication>,
+    },
+    /// The code the identity provider redirected back with, and the login it belongs to
+    Oidc {
+        code: String,
+        csrf_state: String,
+        pending: Box<PendingOidcLogin>,
+        /// Create an admin for a new address in the allowed domain
+        provision: bool,
+    },
 }
 
 fn hash_password(password: &str) -> Result<String> {
@@ -245,7 +1687,47 @@ fn verify_password(password: &str, password_hash: &str) -> Result<bool> {
         .is_ok())
 }
 
+/// A new admin row, already verified when there's no verification token to send
+fn new_admin(
+    email: &str,
+    password_hash: String,
+    role: Role,
+    verification: Option<(String, DateTime<Utc>)>,
+) -> admin_user::ActiveModel {
+    let now = Utc::now();
+    admin_user::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        email: Set(email.to_string()),
+        password_hash: Set(password_hash),
+        email_verified: Set(verification.is_none()),
+        verification_token: Set(verification.as_ref().map(|(token, _)| token.clone())),
+        verification_token_expires_at: Set(verification.map(|(_, expires)| expires.into())),
+        totp_secret: Set(None),
+        totp_enabled: Set(false),
+        totp_last_step: Set(None),
+        recovery_codes: Set(None),
+        password_reset_token_hash: Set(None),
+        password_reset_expires_at: Set(None),
+        failed_login_attempts: Set(0),
+        last_failed_login_at: Set(None),
+        locked_until: Set(None),
+        role: Set(role.as_str().to_string()),
+        disabled: Set(false),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+    }
+}
+
+/// Emails are stored and looked up in this form, so any casing of an address is the same admin
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
 fn generate_verification_token() -> String {
-    let token_bytes: [u8; 32] = rand::thread_rng().gen();
+    let token_bytes: [u8; 32] = rand::rng().random();
     hex::encode(token_bytes)
 }
@@ -0,0 +1,51 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::admin::roles::Permission;
+use crate::cleanup::{CleanupReport, DocumentCleanupService};
+use crate::errors::{AppError, AppResult};
+use crate::middleware::{require_permission, AuthenticatedUser};
+use axum::{extract::State, middleware::from_fn_with_state, response::Json, routing::get, Router};
+
+#[derive(Clone)]
+pub struct DocumentState {
+    pub cleanup: DocumentCleanupService,
+}
+
+pub fn document_routes() -> Router<DocumentState> {
+    Router::new().route(
+        "/api/admin/documents/cleanup",
+        get(cleanup_report).route_layer(from_fn_with_state(
+            Permission::ViewDocuments,
+            require_permission,
+        )),
+    )
+}
+
+/// Dry-run report of the documents the cleanup job would archive or delete
+async fn cleanup_report(
+    State(state): State<DocumentState>,
+    _user: AuthenticatedUser,
+) -> AppResult<Json<CleanupReport>> {
+    let report = state
+        .cleanup
+        .dry_run()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(report))
+}
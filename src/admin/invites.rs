@@ -0,0 +1,173 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Admin invites
+// Owners can invite an email address from any domain. The emailed link carries a single-use token that
+// lets that address register even while open registration is off; only its SHA-256 hash is stored.
+
+use super::roles::Permission;
+use super::routes::{ensure_permission, AdminAuthSession, AdminState};
+use crate::entities::admin_invite;
+use crate::errors::{AppError, AppResult};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get},
+    Router,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub fn invite_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/invites", get(list).post(create))
+        .route("/api/admin/invites/lookup", get(lookup))
+        .route("/api/admin/invites/{id}", delete(revoke))
+}
+
+#[derive(Serialize)]
+struct InviteResponse {
+    id: Uuid,
+    email: String,
+    invited_by: Option<Uuid>,
+    status: &'static str,
+    expires_at: DateTime<FixedOffset>,
+    accepted_at: Option<DateTime<FixedOffset>>,
+    created_at: DateTime<FixedOffset>,
+}
+
+impl From<admin_invite::Model> for InviteResponse {
+    fn from(invite: admin_invite::Model) -> Self {
+        let status = if invite.accepted_at.is_some() {
+            "accepted"
+        } else if invite.expires_at < Utc::now() {
+            "expired"
+        } else {
+            "pending"
+        };
+
+        Self {
+            id: invite.id,
+            email: invite.email,
+            invited_by: invite.invited_by,
+            status,
+            expires_at: invite.expires_at,
+            accepted_at: invite.accepted_at,
+            created_at: invite.created_at,
+        }
+    }
+}
+
+async fn list(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<Vec<InviteResponse>>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+    ensure_permission(&user, Permission::ManageAdmins)?;
+
+    let invites = state
+        .auth_backend
+        .list_invites()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(invites.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Deserialize)]
+struct CreateInviteRequest {
+    email: String,
+}
+
+async fn create(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Json(req): Json<CreateInviteRequest>,
+) -> AppResult<Json<InviteResponse>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+    ensure_permission(&user, Permission::ManageAdmins)?;
+
+    let (invite, token) = state
+        .auth_backend
+        .create_invite(user.id, &req.email)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    // An invite nobody received is useless, withdraw it so the admin can retry
+    if let Err(e) = state
+        .email_service
+        .send_invite_email(&invite.email, &user.email, &token)
+        .await
+    {
+        state.auth_backend.revoke_invite(invite.id).await.ok();
+        return Err(AppError::Internal(e.to_string()));
+    }
+
+    Ok(Json(invite.into()))
+}
+
+#[derive(Deserialize)]
+struct LookupQuery {
+    token: String,
+}
+
+#[derive(Serialize)]
+struct LookupResponse {
+    email: String,
+    expires_at: DateTime<FixedOffset>,
+}
+
+/// Lets the registration page show which address an invite is for
+async fn lookup(
+    State(state): State<AdminState>,
+    Query(query): Query<LookupQuery>,
+) -> AppResult<Json<LookupResponse>> {
+    let invite = state
+        .auth_backend
+        .find_invite(&query.token)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(LookupResponse {
+        email: invite.email,
+        expires_at: invite.expires_at,
+    }))
+}
+
+async fn revoke(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+    ensure_permission(&user, Permission::ManageAdmins)?;
+
+    state
+        .auth_backend
+        .revoke_invite(id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
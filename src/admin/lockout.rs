@@ -0,0 +1,47 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Per-account login throttling
+// Consecutive failed logins are counted on the admin account. From LOCKOUT_THRESHOLD failures on, each
+// further failure locks the account for twice as long as the one before, up to MAX_LOCKOUT_SECONDS.
+// Failures older than FAILURE_WINDOW_HOURS are forgotten.
+
+use chrono::Duration;
+
+pub const LOCKOUT_THRESHOLD: i32 = 5;
+
+const BASE_LOCKOUT_SECONDS: i64 = 60;
+const MAX_LOCKOUT_SECONDS: i64 = 60 * 60;
+
+/// Failures spread further apart than this start counting from one again
+pub const FAILURE_WINDOW_HOURS: i64 = 24;
+
+/// How long the account is locked after this many consecutive failures
+pub fn lockout_duration(failures: i32) -> Option<Duration> {
+    if failures < LOCKOUT_THRESHOLD {
+        return None;
+    }
+
+    // Capping the exponent keeps the shift from overflowing, the cap below applies long before
+    let doublings = (failures - LOCKOUT_THRESHOLD).min(16) as u32;
+    let seconds = BASE_LOCKOUT_SECONDS.saturating_mul(1 << doublings);
+    Some(Duration::seconds(seconds.min(MAX_LOCKOUT_SECONDS)))
+}
+
+/// Rounded up so "try again in 0 minutes" never shows
+pub fn minutes_remaining(remaining: Duration) -> i64 {
+    (remaining.num_seconds() + 59).max(60) / 60
+}
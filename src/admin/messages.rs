@@ -0,0 +1,146 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::admin::pagination::{Paginated, PaginationParams};
+use crate::admin::roles::Permission;
+use crate::contact::ContactService;
+use crate::entities::contact_message;
+use crate::errors::{AppError, AppResult};
+use crate::middleware::{require_permission, AuthenticatedUser};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware::from_fn_with_state,
+    response::Json,
+    routing::{get, put},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct MessageState {
+    pub contact: ContactService,
+}
+
+pub fn message_routes() -> Router<MessageState> {
+    Router::new()
+        .route(
+            "/api/admin/messages",
+            get(list_messages).route_layer(from_fn_with_state(
+                Permission::ViewMessages,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/messages/{id}/read",
+            put(mark_read).route_layer(from_fn_with_state(
+                Permission::ManageMessages,
+                require_permission,
+            )),
+        )
+}
+
+#[derive(Serialize)]
+struct MessageResponse {
+    id: Uuid,
+    name: String,
+    email: String,
+    message: String,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    read: bool,
+    delivered: bool,
+    created_at: String,
+}
+
+impl From<contact_message::Model> for MessageResponse {
+    fn from(model: contact_message::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            email: model.email,
+            message: model.message,
+            ip_address: model.ip_address,
+            user_agent: model.user_agent,
+            read: model.read,
+            delivered: model.delivered,
+            created_at: model.created_at.with_timezone(&chrono::Utc).to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct InboxResponse {
+    #[serde(flatten)]
+    messages: Paginated<MessageResponse>,
+    unread: u64,
+}
+
+async fn list_messages(
+    State(state): State<MessageState>,
+    _user: AuthenticatedUser,
+    Query(params): Query<PaginationParams>,
+) -> AppResult<Json<InboxResponse>> {
+    let validated = params.validate();
+
+    let (messages, total, total_pages) = state
+        .contact
+        .list(validated.page, validated.per_page)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let unread = state
+        .contact
+        .unread_count()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(InboxResponse {
+        messages: Paginated::new(
+            messages.into_iter().map(Into::into).collect(),
+            total,
+            validated.page,
+            validated.per_page,
+            total_pages,
+        ),
+        unread,
+    }))
+}
+
+#[derive(Deserialize)]
+struct MarkReadRequest {
+    read: bool,
+}
+
+async fn mark_read(
+    State(state): State<MessageState>,
+    _user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(req): Json<MarkReadRequest>,
+) -> AppResult<StatusCode> {
+    let found = state
+        .contact
+        .set_read(id, req.read)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if !found {
+        return Err(AppError::AuthError("Message not found".to_string()));
+    }
+
+    Ok(StatusCode::OK)
+}
@@ -17,9 +17,22 @@
 
 pub mod access_codes;
 pub mod access_logs;
+pub mod api_tokens;
 pub mod auth;
+pub mod documents;
+pub mod invites;
+pub mod lockout;
+pub mod messages;
+pub mod oidc;
 pub mod pagination;
+pub mod passkeys;
+pub mod password_policy;
+pub mod posts;
+pub mod roles;
 pub mod routes;
+pub mod sessions;
 pub mod settings;
+pub mod two_factor;
+pub mod users;
 
 pub use auth::{AdminAuthBackend, AdminUserAuth, Credentials};
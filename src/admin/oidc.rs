@@ -0,0 +1,297 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// OpenID Connect login for admins
+// Admins can sign in with the configured identity provider using the authorization code flow with PKCE.
+// The ID token's verified email is mapped to an admin_user row under the SITE_DOMAIN restriction, and
+// accounts are only created on first login while admin registration is enabled. Provider metadata and
+// signing keys are discovered on each login so key rotation at the provider needs no restart.
+
+use super::auth::normalize_email;
+use super::passkeys::{start_ceremony, take_ceremony};
+use super::routes::{AdminAuthSession, AdminState};
+use super::two_factor::{self, REQUIRE_2FA_SETTING_KEY};
+use super::Credentials;
+use crate::errors::{AppError, AppResult};
+use anyhow::Result;
+use axum::{
+    extract::{Query, State},
+    response::{Json, Redirect},
+    routing::get,
+    Router,
+};
+use openidconnect::{
+    core::{CoreClient, CoreIdTokenClaims, CoreProviderMetadata},
+    reqwest, url, ClientId, ClientSecret, EndpointMaybeSet, EndpointNotSet, EndpointSet, IssuerUrl,
+    RedirectUrl,
+};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+const PENDING_OIDC_LOGIN_KEY: &str = "admin.pending_oidc_login";
+
+const DEFAULT_PROVIDER_NAME: &str = "single sign-on";
+
+/// Identity provider settings, from the OIDC_* environment variables
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Public clients rely on PKCE alone and have no secret
+    pub client_secret: Option<String>,
+    pub redirect_url: String,
+    /// Shown on the login button
+    pub provider_name: String,
+}
+
+impl OidcConfig {
+    /// None unless OIDC_ISSUER_URL and OIDC_CLIENT_ID are both set
+    pub fn from_env() -> Option<Self> {
+        let issuer_url = non_empty_env("OIDC_ISSUER_URL")?;
+        let client_id = non_empty_env("OIDC_CLIENT_ID")?;
+        let redirect_url = non_empty_env("OIDC_REDIRECT_URL").unwrap_or_else(|| {
+            let site_url =
+                env::var("SITE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+            format!(
+                "{}/api/admin/login/oidc/callback",
+                site_url.trim_end_matches('/')
+            )
+        });
+
+        Some(Self {
+            issuer_url,
+            client_id,
+            client_secret: non_empty_env("OIDC_CLIENT_SECRET"),
+            redirect_url,
+            provider_name: non_empty_env("OIDC_PROVIDER_NAME")
+                .unwrap_or_else(|| DEFAULT_PROVIDER_NAME.to_string()),
+        })
+    }
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub type OidcClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+/// The configured provider and the HTTP client used to talk to it
+#[derive(Debug, Clone)]
+pub struct OidcProvider {
+    pub config: OidcConfig,
+    http_client: reqwest::Client,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcConfig) -> Result<Self> {
+        // Following redirects would open the token exchange to SSRF
+        let http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self {
+            config,
+            http_client,
+        })
+    }
+
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.http_client
+    }
+
+    /// Client for the provider's current metadata and keys
+    pub async fn client(&self) -> Result<OidcClient> {
+        let issuer = IssuerUrl::new(self.config.issuer_url.clone())?;
+        let metadata = CoreProviderMetadata::discover_async(issuer, &self.http_client)
+            .await
+            .map_err(|e| anyhow::anyhow!("Could not reach the identity provider: {}", e))?;
+
+        Ok(CoreClient::from_provider_metadata(
+            metadata,
+            ClientId::new(self.config.client_id.clone()),
+            self.config.client_secret.clone().map(ClientSecret::new),
+        )
+        .set_redirect_uri(RedirectUrl::new(self.config.redirect_url.clone())?))
+    }
+}
+
+/// Secrets of a started login, kept in the session until the provider redirects back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingOidcLogin {
+    pub csrf_state: String,
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+/// The normalized email of verified ID token claims
+pub fn verified_email(claims: &CoreIdTokenClaims) -> Result<String> {
+    let email = claims
+        .email()
+        .ok_or_else(|| anyhow::anyhow!("The identity provider did not share an email address"))?;
+    if claims.email_verified() != Some(true) {
+        anyhow::bail!("The identity provider has not verified {}", email.as_str());
+    }
+    Ok(normalize_email(email.as_str()))
+}
+
+pub fn oidc_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/login/oidc", get(provider))
+        .route("/api/admin/login/oidc/start", get(start_login))
+        .route("/api/admin/login/oidc/callback", get(finish_login))
+}
+
+#[derive(Serialize)]
+struct ProviderResponse {
+    enabled: bool,
+    name: Option<String>,
+}
+
+/// Whether the login page should offer single sign-on
+async fn provider(State(state): State<AdminState>) -> Json<ProviderResponse> {
+    let provider = state.auth_backend.oidc_provider();
+    Json(ProviderResponse {
+        enabled: provider.is_some(),
+        name: provider.map(|provider| provider.config.provider_name.clone()),
+    })
+}
+
+/// Back to the login page with a message, since these requests are browser navigations
+fn login_page_error(message: &str) -> Redirect {
+    let message: String = url::form_urlencoded::byte_serialize(message.as_bytes()).collect();
+    Redirect::to(&format!("/admin/login?error={}", message))
+}
+
+/// Redirect to the identity provider
+async fn start_login(State(state): State<AdminState>, auth_session: AdminAuthSession) -> Redirect {
+    let (auth_url, pending) = match state.auth_backend.begin_oidc_authentication().await {
+        Ok(started) => started,
+        Err(e) => {
+            tracing::warn!("Could not start OIDC login: {}", e);
+            return login_page_error(&e.to_string());
+        }
+    };
+
+    if let Err(e) =
+        start_ceremony(&auth_session.session, PENDING_OIDC_LOGIN_KEY, None, pending).await
+    {
+        return login_page_error(&e.to_string());
+    }
+
+    Redirect::to(auth_url.as_str())
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+async fn finish_login(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Query(query): Query<CallbackQuery>,
+) -> Redirect {
+    match complete_login(state, auth_session, query).await {
+        Ok(redirect) => redirect,
+        Err(e) => login_page_error(&e.to_string()),
+    }
+}
+
+async fn complete_login(
+    state: AdminState,
+    mut auth_session: AdminAuthSession,
+    query: CallbackQuery,
+) -> AppResult<Redirect> {
+    let pending: PendingOidcLogin =
+        take_ceremony(&auth_session.session, PENDING_OIDC_LOGIN_KEY, None)
+            .await?
+            .ok_or_else(|| {
+                AppError::AuthError("Single sign-on expired, please try again".to_string())
+            })?;
+
+    if let Some(error) = query.error {
+        return Err(AppError::AuthError(format!(
+            "The identity provider refused the login: {}",
+            query.error_description.unwrap_or(error)
+        )));
+    }
+    let (Some(code), Some(csrf_state)) = (query.code, query.state) else {
+        return Err(AppError::AuthError(
+            "The identity provider sent an incomplete response".to_string(),
+        ));
+    };
+
+    // Single sign-on creates accounts on the same terms as registering
+    let provision = state
+        .settings
+        .get_bool("admin_registration_enabled", Some("system"), None)
+        .await
+        .unwrap_or(false);
+
+    let user = auth_session
+        .authenticate(Credentials::Oidc {
+            code,
+            csrf_state,
+            pending: Box::new(pending),
+            provision,
+        })
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?
+        .ok_or_else(|| AppError::AuthError("Single sign-on was not accepted".to_string()))?;
+
+    let two_factor_required = state
+        .settings
+        .get_bool(REQUIRE_2FA_SETTING_KEY, Some("system"), None)
+        .await
+        .unwrap_or(false);
+
+    // The provider stands in for the password, the second factor is still asked for
+    if user.totp_enabled || two_factor_required {
+        let enrollment_required = !user.totp_enabled;
+        two_factor::start_pending_login(&auth_session.session, user.id, enrollment_required)
+            .await?;
+        let step = if enrollment_required {
+            "enroll"
+        } else {
+            "code"
+        };
+        return Ok(Redirect::to(&format!("/admin/login?two_factor={}", step)));
+    }
+
+    auth_session
+        .backend
+        .clear_failed_logins(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+    auth_session
+        .login(&user)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Redirect::to("/admin/dashboard"))
+}
@@ -0,0 +1,323 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// WebAuthn passkeys for admin accounts
+// An admin can register several passkeys and sign in with any of them instead of a password. Passkey
+// login requires user verification on the authenticator, so it also satisfies the 2FA step. The state of
+// each ceremony is kept in the session between its start and finish requests and can only be used once.
+
+use super::routes::{AdminAuthSession, AdminState, UserResponse};
+use super::Credentials;
+use crate::entities::admin_passkey;
+use crate::errors::{AppError, AppResult};
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post, put},
+    Router,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+use webauthn_rs::prelude::{
+    CreationChallengeResponse, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+    RegisterPublicKeyCredential, RequestChallengeResponse,
+};
+
+pub const MAX_PASSKEY_NAME_LENGTH: usize = 64;
+
+const PENDING_REGISTRATION_KEY: &str = "admin.pending_passkey_registration";
+const PENDING_AUTHENTICATION_KEY: &str = "admin.pending_passkey_authentication";
+
+/// How long the browser has to complete a ceremony
+const CEREMONY_TTL_SECONDS: i64 = 300;
+
+/// Trimmed passkey name, rejected when empty or too long
+pub fn normalize_passkey_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Passkey name is required");
+    }
+    if name.chars().count() > MAX_PASSKEY_NAME_LENGTH {
+        anyhow::bail!(
+            "Passkey name must be at most {} characters",
+            MAX_PASSKEY_NAME_LENGTH
+        );
+    }
+    Ok(name.to_string())
+}
+
+/// State of a started ceremony, bound to the admin that started it when logged in
+#[derive(Serialize, Deserialize)]
+struct Ceremony<T> {
+    user_id: Option<Uuid>,
+    state: T,
+    expires_at: i64,
+}
+
+pub(super) async fn start_ceremony<T: Serialize>(
+    session: &Session,
+    key: &str,
+    user_id: Option<Uuid>,
+    state: T,
+) -> AppResult<()> {
+    let ceremony = Ceremony {
+        user_id,
+        state,
+        expires_at: Utc::now().timestamp() + CEREMONY_TTL_SECONDS,
+    };
+    session
+        .insert(key, ceremony)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Remove the ceremony from the session, None if it is missing, expired or not this admin's
+pub(super) async fn take_ceremony<T: DeserializeOwned>(
+    session: &Session,
+    key: &str,
+    user_id: Option<Uuid>,
+) -> AppResult<Option<T>> {
+    let ceremony: Option<Ceremony<T>> = session
+        .remove(key)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(ceremony
+        .filter(|c| c.expires_at > Utc::now().timestamp() && c.user_id == user_id)
+        .map(|c| c.state))
+}
+
+pub fn passkey_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/login/passkey/start", post(start_login))
+        .route("/api/admin/login/passkey/finish", post(finish_login))
+        .route("/api/admin/passkeys", get(list))
+        .route(
+            "/api/admin/passkeys/register/start",
+            post(start_registration),
+        )
+        .route(
+            "/api/admin/passkeys/register/finish",
+            post(finish_registration),
+        )
+        .route("/api/admin/passkeys/{id}", put(rename).delete(revoke))
+}
+
+#[derive(Serialize)]
+struct PasskeyResponse {
+    id: Uuid,
+    name: String,
+    created_at: DateTime<FixedOffset>,
+    last_used_at: Option<DateTime<FixedOffset>>,
+}
+
+impl From<admin_passkey::Model> for PasskeyResponse {
+    fn from(passkey: admin_passkey::Model) -> Self {
+        Self {
+            id: passkey.id,
+            name: passkey.name,
+            created_at: passkey.created_at,
+            last_used_at: passkey.last_used_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct StartLoginRequest {
+    email: String,
+}
+
+/// Challenge for the passkeys registered to an email address
+/// Unknown emails and accounts without passkeys get the same answer as a rejected passkey
+async fn start_login(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Json(req): Json<StartLoginRequest>,
+) -> AppResult<Json<RequestChallengeResponse>> {
+    let (challenge, ceremony) = state
+        .auth_backend
+        .begin_passkey_authentication(req.email.trim())
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?
+        .ok_or_else(|| AppError::AuthError("Passkey was not accepted".to_string()))?;
+
+    start_ceremony(
+        &auth_session.session,
+        PENDING_AUTHENTICATION_KEY,
+        None,
+        ceremony,
+    )
+    .await?;
+
+    Ok(Json(challenge))
+}
+
+async fn finish_login(
+    mut auth_session: AdminAuthSession,
+    Json(response): Json<PublicKeyCredential>,
+) -> AppResult<Json<UserResponse>> {
+    let ceremony: PasskeyAuthentication =
+        take_ceremony(&auth_session.session, PENDING_AUTHENTICATION_KEY, None)
+            .await?
+            .ok_or_else(|| {
+                AppError::AuthError("Passkey login expired, please try again".to_string())
+            })?;
+
+    let user = auth_session
+        .authenticate(Credentials::Passkey {
+            response: Box::new(response),
+            state: Box::new(ceremony),
+        })
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?
+        .ok_or_else(|| AppError::AuthError("Passkey was not accepted".to_string()))?;
+
+    auth_session
+        .backend
+        .clear_failed_logins(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+    auth_session
+        .login(&user)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(user.into()))
+}
+
+async fn list(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<Vec<PasskeyResponse>>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let passkeys = state
+        .auth_backend
+        .list_passkeys(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(passkeys.into_iter().map(Into::into).collect()))
+}
+
+async fn start_registration(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<CreationChallengeResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let (challenge, ceremony) = state
+        .auth_backend
+        .begin_passkey_registration(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    start_ceremony(
+        &auth_session.session,
+        PENDING_REGISTRATION_KEY,
+        Some(user.id),
+        ceremony,
+    )
+    .await?;
+
+    Ok(Json(challenge))
+}
+
+#[derive(Deserialize)]
+struct FinishRegistrationRequest {
+    name: String,
+    credential: RegisterPublicKeyCredential,
+}
+
+async fn finish_registration(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Json(req): Json<FinishRegistrationRequest>,
+) -> AppResult<Json<PasskeyResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let ceremony: PasskeyRegistration = take_ceremony(
+        &auth_session.session,
+        PENDING_REGISTRATION_KEY,
+        Some(user.id),
+    )
+    .await?
+    .ok_or_else(|| {
+        AppError::AuthError("Passkey registration expired, please try again".to_string())
+    })?;
+
+    let passkey = state
+        .auth_backend
+        .finish_passkey_registration(user.id, &req.name, &req.credential, &ceremony)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(passkey.into()))
+}
+
+#[derive(Deserialize)]
+struct RenameRequest {
+    name: String,
+}
+
+async fn rename(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RenameRequest>,
+) -> AppResult<Json<PasskeyResponse>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let passkey = state
+        .auth_backend
+        .rename_passkey(user.id, id, &req.name)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(passkey.into()))
+}
+
+async fn revoke(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    state
+        .auth_backend
+        .delete_passkey(user.id, id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,97 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Rules every admin password has to satisfy, on registration, reset and change
+
+use std::collections::HashSet;
+use std::env;
+use std::sync::LazyLock;
+
+const DEFAULT_MIN_LENGTH: usize = 12;
+
+/// Upper bound so hashing a submitted password stays cheap
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// Email local parts shorter than this are too generic to reject passwords over
+const MIN_LOCAL_PART_LENGTH: usize = 3;
+
+static COMMON_PASSWORDS: LazyLock<HashSet<String>> = LazyLock::new(|| {
+    include_str!("common_passwords.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+});
+
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Minimum length from PASSWORD_MIN_LENGTH, never below 8 or above the maximum
+    pub fn from_env() -> Self {
+        let min_length = env::var("PASSWORD_MIN_LENGTH")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MIN_LENGTH)
+            .clamp(8, MAX_PASSWORD_LENGTH);
+
+        Self { min_length }
+    }
+
+    /// The first rule the password breaks, as a message for the user
+    pub fn check(&self, password: &str, email: &str) -> Result<(), String> {
+        let length = password.chars().count();
+        if length < self.min_length {
+            return Err(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            ));
+        }
+        if length > MAX_PASSWORD_LENGTH {
+            return Err(format!(
+                "Password must be at most {} characters",
+                MAX_PASSWORD_LENGTH
+            ));
+        }
+
+        let lowercase = password.to_lowercase();
+        if is_common_password(&lowercase) {
+            return Err("Password is too common, please choose another".to_string());
+        }
+
+        let local_part = email.split('@').next().unwrap_or_default().to_lowercase();
+        if local_part.chars().count() >= MIN_LOCAL_PART_LENGTH && lowercase.contains(&local_part) {
+            return Err("Password must not contain your email address".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+pub fn is_common_password(password: &str) -> bool {
+    COMMON_PASSWORDS.contains(&password.trim().to_lowercase())
+}
@@ -0,0 +1,106 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::admin::roles::Permission;
+use crate::content::{ContentService, PostStatus};
+use crate::errors::{AppError, AppResult};
+use crate::middleware::{require_permission, AuthenticatedUser};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware::from_fn_with_state,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+
+#[derive(Clone)]
+pub struct PostState {
+    pub content: ContentService,
+}
+
+pub fn post_routes() -> Router<PostState> {
+    Router::new()
+        .route(
+            "/api/admin/posts",
+            get(list_posts).route_layer(from_fn_with_state(
+                Permission::ViewPosts,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/posts/{slug}/publish",
+            post(publish_post).route_layer(from_fn_with_state(
+                Permission::PublishPosts,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/posts/{slug}/unpublish",
+            post(unpublish_post).route_layer(from_fn_with_state(
+                Permission::PublishPosts,
+                require_permission,
+            )),
+        )
+}
+
+async fn list_posts(
+    State(state): State<PostState>,
+    _user: AuthenticatedUser,
+) -> AppResult<Json<Vec<PostStatus>>> {
+    let posts = state
+        .content
+        .post_statuses()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(posts))
+}
+
+async fn publish_post(
+    State(state): State<PostState>,
+    user: AuthenticatedUser,
+    Path(slug): Path<String>,
+) -> AppResult<StatusCode> {
+    set_published(&state, &user, &slug, true).await
+}
+
+async fn unpublish_post(
+    State(state): State<PostState>,
+    user: AuthenticatedUser,
+    Path(slug): Path<String>,
+) -> AppResult<StatusCode> {
+    set_published(&state, &user, &slug, false).await
+}
+
+async fn set_published(
+    state: &PostState,
+    user: &AuthenticatedUser,
+    slug: &str,
+    published: bool,
+) -> AppResult<StatusCode> {
+    let found = state
+        .content
+        .set_published(slug, published, user.id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if !found {
+        return Err(AppError::AuthError("Post not found".to_string()));
+    }
+
+    Ok(StatusCode::OK)
+}
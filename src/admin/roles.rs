@@ -0,0 +1,172 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Admin roles and permissions
+// Every admin has exactly one role, stored by name on admin_users. Admin endpoints declare the Permission
+// they need instead of checking roles, so what each role may do is decided in one place below. API tokens
+// are limited further by scopes, each naming one permission.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Everything, including clearing logs, changing settings and managing other admins
+    Owner,
+    /// Day to day work: access codes, posts and messages
+    Editor,
+    /// Read only
+    Viewer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ViewAccessCodes,
+    ManageAccessCodes,
+    ViewAccessLogs,
+    ClearAccessLogs,
+    ViewSettings,
+    ManageSettings,
+    ViewDocuments,
+    ViewPosts,
+    PublishPosts,
+    ViewMessages,
+    ManageMessages,
+    ManageAdmins,
+}
+
+const VIEWER_PERMISSIONS: &[Permission] = &[
+    Permission::ViewAccessCodes,
+    Permission::ViewAccessLogs,
+    Permission::ViewSettings,
+    Permission::ViewDocuments,
+    Permission::ViewPosts,
+    Permission::ViewMessages,
+];
+
+const EDITOR_PERMISSIONS: &[Permission] = &[
+    Permission::ViewAccessCodes,
+    Permission::ManageAccessCodes,
+    Permission::ViewAccessLogs,
+    Permission::ViewSettings,
+    Permission::ViewDocuments,
+    Permission::ViewPosts,
+    Permission::PublishPosts,
+    Permission::ViewMessages,
+    Permission::ManageMessages,
+];
+
+const OWNER_PERMISSIONS: &[Permission] = &[
+    Permission::ViewAccessCodes,
+    Permission::ManageAccessCodes,
+    Permission::ViewAccessLogs,
+    Permission::ClearAccessLogs,
+    Permission::ViewSettings,
+    Permission::ManageSettings,
+    Permission::ViewDocuments,
+    Permission::ViewPosts,
+    Permission::PublishPosts,
+    Permission::ViewMessages,
+    Permission::ManageMessages,
+    Permission::ManageAdmins,
+];
+
+impl Permission {
+    pub const ALL: [Permission; 12] = [
+        Permission::ViewAccessCodes,
+        Permission::ManageAccessCodes,
+        Permission::ViewAccessLogs,
+        Permission::ClearAccessLogs,
+        Permission::ViewSettings,
+        Permission::ManageSettings,
+        Permission::ViewDocuments,
+        Permission::ViewPosts,
+        Permission::PublishPosts,
+        Permission::ViewMessages,
+        Permission::ManageMessages,
+        Permission::ManageAdmins,
+    ];
+
+    /// The API token scope granting this permission, None for what only a logged in admin may do
+    pub fn scope(self) -> Option<&'static str> {
+        match self {
+            Permission::ViewAccessCodes => Some("codes:read"),
+            Permission::ManageAccessCodes => Some("codes:write"),
+            Permission::ViewAccessLogs => Some("logs:read"),
+            Permission::ClearAccessLogs => Some("logs:write"),
+            Permission::ViewSettings => Some("settings:read"),
+            Permission::ManageSettings => Some("settings:write"),
+            Permission::ViewDocuments => Some("documents:read"),
+            Permission::ViewPosts => Some("posts:read"),
+            Permission::PublishPosts => Some("posts:write"),
+            Permission::ViewMessages => Some("messages:read"),
+            Permission::ManageMessages => Some("messages:write"),
+            Permission::ManageAdmins => None,
+        }
+    }
+
+    pub fn from_scope(scope: &str) -> Option<Permission> {
+        Permission::ALL
+            .into_iter()
+            .find(|permission| permission.scope() == Some(scope))
+    }
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Owner, Role::Editor, Role::Viewer];
+
+    /// Given to admins who register or accept an invite once an owner exists
+    pub const DEFAULT: Role = Role::Viewer;
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Editor => "editor",
+            Role::Viewer => "viewer",
+        }
+    }
+
+    pub fn permissions(self) -> &'static [Permission] {
+        match self {
+            Role::Owner => OWNER_PERMISSIONS,
+            Role::Editor => EDITOR_PERMISSIONS,
+            Role::Viewer => VIEWER_PERMISSIONS,
+        }
+    }
+
+    pub fn allows(self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("Unknown role: {}", s))
+    }
+}
@@ -15,7 +15,16 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use super::{AdminAuthBackend, Credentials};
+use super::api_tokens;
+use super::invites;
+use super::oidc;
+use super::passkeys;
+use super::password_policy::MAX_PASSWORD_LENGTH;
+use super::roles::{Permission, Role};
+use super::sessions;
+use super::two_factor::{self, REQUIRE_2FA_SETTING_KEY};
+use super::users;
+use super::{AdminAuthBackend, AdminUserAuth, Credentials};
 use crate::email::EmailService;
 use crate::errors::{AppError, AppResult};
 use crate::settings::SettingsService;
@@ -32,6 +41,19 @@ use std::sync::Arc;
 
 pub type AdminAuthSession = AuthSession<AdminAuthBackend>;
 
+/// Used by the `require_permission` middleware, and directly by handlers here that run behind the auth
+/// session instead of `require_admin_auth`
+pub fn ensure_permission(user: &AdminUserAuth, permission: Permission) -> AppResult<()> {
+    if user.has_permission(permission) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!(
+            "The {} role can't do this ({:?} required)",
+            user.role, permission
+        )))
+    }
+}
+
 #[derive(Clone)]
 pub struct AdminState {
     pub auth_backend: AdminAuthBackend,
@@ -45,25 +67,62 @@ pub fn admin_api_routes() -> Router<AdminState> {
         .route("/api/admin/login", post(login))
         .route("/api/admin/logout", post(logout))
         .route("/api/admin/verify-email", get(verify_email))
+        .route("/api/admin/verify-email/resend", post(resend_verification))
+        .route(
+            "/api/admin/password-reset/request",
+            post(request_password_reset),
+        )
+        .route(
+            "/api/admin/password-reset/confirm",
+            post(confirm_password_reset),
+        )
         .route("/api/admin/me", get(me))
+        .route("/api/admin/password", post(change_password))
+        .route("/api/admin/password-policy", get(password_policy))
+        .merge(two_factor::two_factor_routes())
+        .merge(passkeys::passkey_routes())
+        .merge(oidc::oidc_routes())
+        .merge(invites::invite_routes())
+        .merge(users::user_routes())
+        .merge(sessions::session_routes())
+        .merge(api_tokens::api_token_routes())
 }
 
 #[derive(Deserialize)]
 struct RegisterRequest {
     email: String,
     password: String,
+    /// Token from an invite link, required while open registration is off
+    #[serde(default)]
+    invite_token: Option<String>,
 }
 
 #[derive(Serialize)]
 struct RegisterResponse {
     message: String,
     email: String,
+    verification_required: bool,
 }
 
 async fn register(
     State(state): State<AdminState>,
     Json(req): Json<RegisterRequest>,
 ) -> AppResult<Json<RegisterResponse>> {
+    // Invited addresses may be from any domain and are verified by the invite link itself
+    if let Some(token) = req.invite_token.as_deref().filter(|t| !t.is_empty()) {
+        let admin = state
+            .auth_backend
+            .accept_invite(token, &req.email, &req.password)
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+        return Ok(Json(RegisterResponse {
+            message: "Registration successful. You can now log in.".to_string(),
+            email: admin.email,
+            verification_required: false,
+        }));
+    }
+
     // Check if registration is enabled
     let registration_enabled = state
         .settings
@@ -95,29 +154,70 @@ async fn register(
         message: "Registration successful. Please check your email to verify your account."
             .to_string(),
         email: admin.email,
+        verification_required: true,
     }))
 }
 
+/// Either the logged in user, or a prompt for the second factor
+#[derive(Serialize)]
+#[serde(untagged)]
+enum LoginResponse {
+    LoggedIn(UserResponse),
+    TwoFactor {
+        two_factor_required: bool,
+        /// The account has no 2FA yet but require_2fa is on, it has to enroll first
+        enrollment_required: bool,
+    },
+}
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
 async fn login(
+    State(state): State<AdminState>,
     mut auth_session: AdminAuthSession,
-    Json(creds): Json<Credentials>,
-) -> AppResult<Json<UserResponse>> {
+    Json(req): Json<LoginRequest>,
+) -> AppResult<Json<LoginResponse>> {
     let user = auth_session
-        .authenticate(creds)
+        .authenticate(Credentials::Password {
+            email: req.email,
+            password: req.password,
+        })
         .await
         .map_err(|e| AppError::AuthError(e.to_string()))?
         .ok_or_else(|| AppError::AuthError("Invalid email or password".to_string()))?;
 
+    let two_factor_required = state
+        .settings
+        .get_bool(REQUIRE_2FA_SETTING_KEY, Some("system"), None)
+        .await
+        .unwrap_or(false);
+
+    // The session isn't logged in until the second step succeeds
+    if user.totp_enabled || two_factor_required {
+        let enrollment_required = !user.totp_enabled;
+        two_factor::start_pending_login(&auth_session.session, user.id, enrollment_required)
+            .await?;
+        return Ok(Json(LoginResponse::TwoFactor {
+            two_factor_required: true,
+            enrollment_required,
+        }));
+    }
+
+    auth_session
+        .backend
+        .clear_failed_logins(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
     auth_session
         .login(&user)
         .await
         .map_err(|e| AppError::AuthError(e.to_string()))?;
 
-    Ok(Json(UserResponse {
-        id: user.id,
-        email: user.email,
-        email_verified: user.email_verified,
-    }))
+    Ok(Json(LoginResponse::LoggedIn(user.into())))
 }
 
 async fn logout(mut auth_session: AdminAuthSession) -> AppResult<StatusCode> {
@@ -156,11 +256,170 @@ async fn verify_email(
     }))
 }
 
+#[derive(Deserialize)]
+struct ResendVerificationRequest {
+    email: String,
+}
+
+/// Always answers the same way, whether or not the email is waiting to be verified
+async fn resend_verification(
+    State(state): State<AdminState>,
+    Json(req): Json<ResendVerificationRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    let resend = state
+        .auth_backend
+        .request_verification_resend(req.email.trim())
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if let Some((admin, token)) = resend {
+        if let Err(e) = state
+            .email_service
+            .send_verification_email(&admin.email, &token)
+            .await
+        {
+            tracing::error!("{}", e);
+        }
+    }
+
+    Ok(Json(MessageResponse {
+        message: "If that email is waiting to be verified, a new link has been sent.".to_string(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct PasswordResetRequest {
+    email: String,
+}
+
+#[derive(Serialize)]
+pub struct MessageResponse {
+    pub message: String,
+}
+
+/// Always answers the same way, whether or not the email belongs to an admin
+async fn request_password_reset(
+    State(state): State<AdminState>,
+    Json(req): Json<PasswordResetRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    let reset = state
+        .auth_backend
+        .request_password_reset(req.email.trim())
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if let Some((admin, token)) = reset {
+        if let Err(e) = state
+            .email_service
+            .send_password_reset_email(&admin.email, &token)
+            .await
+        {
+            tracing::error!("{}", e);
+        }
+    }
+
+    Ok(Json(MessageResponse {
+        message: "If an account exists for that email, a reset link has been sent.".to_string(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct PasswordResetConfirm {
+    token: String,
+    password: String,
+}
+
+async fn confirm_password_reset(
+    State(state): State<AdminState>,
+    Json(req): Json<PasswordResetConfirm>,
+) -> AppResult<Json<MessageResponse>> {
+    state
+        .auth_backend
+        .reset_password(&req.token, &req.password)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(MessageResponse {
+        message: "Password updated. You can now log in with your new password.".to_string(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct ChangePasswordRequest {
+    current_password: String,
+    new_password: String,
+}
+
+/// Other sessions are signed out, this one is renewed with the new password
+async fn change_password(
+    State(state): State<AdminState>,
+    mut auth_session: AdminAuthSession,
+    Json(req): Json<ChangePasswordRequest>,
+) -> AppResult<Json<MessageResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let admin = state
+        .auth_backend
+        .change_password(user.id, &req.current_password, &req.new_password)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    auth_session
+        .login(&AdminUserAuth::from(admin.clone()))
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    // Already signed out by the new password, this clears them from the session list
+    state
+        .auth_backend
+        .revoke_sessions(admin.id, sessions::current_session(&auth_session).await)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(MessageResponse {
+        message: "Password changed. Other sessions have been signed out.".to_string(),
+    }))
+}
+
+#[derive(Serialize)]
+struct PasswordPolicyResponse {
+    min_length: usize,
+    max_length: usize,
+}
+
+/// Lets the registration, reset and change forms describe the rules up front
+async fn password_policy(State(state): State<AdminState>) -> Json<PasswordPolicyResponse> {
+    Json(PasswordPolicyResponse {
+        min_length: state.auth_backend.password_policy().min_length,
+        max_length: MAX_PASSWORD_LENGTH,
+    })
+}
+
 #[derive(Serialize)]
-struct UserResponse {
+pub struct UserResponse {
     id: uuid::Uuid,
     email: String,
     email_verified: bool,
+    two_factor_enabled: bool,
+    role: Role,
+    /// Lets the frontend hide what the role can't do, the server checks again anyway
+    permissions: &'static [Permission],
+}
+
+impl From<AdminUserAuth> for UserResponse {
+    fn from(user: AdminUserAuth) -> Self {
+        Self {
+            id: user.id,
+            email: user.email,
+            email_verified: user.email_verified,
+            two_factor_enabled: user.totp_enabled,
+            role: user.role,
+            permissions: user.role.permissions(),
+        }
+    }
 }
 
 async fn me(auth_session: AdminAuthSession) -> AppResult<Json<UserResponse>> {
@@ -168,9 +427,5 @@ async fn me(auth_session: AdminAuthSession) -> AppResult<Json<UserResponse>> {
         .user
         .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
 
-    Ok(Json(UserResponse {
-        id: user.id,
-        email: user.email,
-        email_verified: user.email_verified,
-    }))
+    Ok(Json(user.into()))
 }
@@ -0,0 +1,205 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Admin session tracking
+// Each logged in session gets an admin_sessions row whose id is also kept in the session data. The
+// track_admin_session middleware keeps the row current and logs the session out once its row is gone,
+// so revoking a session is deleting its row (and its record in the session store).
+
+use super::routes::{AdminAuthSession, AdminState};
+use crate::entities::admin_session;
+use crate::errors::{AppError, AppResult};
+use crate::middleware::security::SecurityContext;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get},
+    Router,
+};
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Session data key holding the id of the session's admin_sessions row
+pub const SESSION_TRACKING_KEY: &str = "admin.tracked_session";
+
+/// The session store drops admin sessions after this long without a request
+pub const SESSION_INACTIVITY_DAYS: i64 = 7;
+
+/// Minimum time between last seen updates for one session
+pub const SESSION_TOUCH_SECONDS: i64 = 60;
+
+pub fn session_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/sessions", get(list).delete(revoke_others))
+        .route("/api/admin/sessions/{id}", delete(revoke))
+}
+
+/// Start or refresh tracking of a logged in session, logging it out if it has been revoked
+pub async fn track(
+    auth_session: &mut AdminAuthSession,
+    context: Option<&SecurityContext>,
+) -> anyhow::Result<()> {
+    let Some(user_id) = auth_session.user.as_ref().map(|user| user.id) else {
+        return Ok(());
+    };
+
+    let backend = auth_session.backend.clone();
+    let session_id = auth_session.session.id().map(|id| id.to_string());
+    let ip_address = context.map(|c| c.ip_address.to_string());
+
+    if let Some(tracked) = auth_session
+        .session
+        .get::<Uuid>(SESSION_TRACKING_KEY)
+        .await?
+    {
+        match backend
+            .touch_session(tracked, session_id.clone(), ip_address.clone())
+            .await?
+        {
+            Some(row) if row.admin_user_id == user_id => return Ok(()),
+            // Another admin logged in without logging out first
+            Some(row) => backend.forget_session(row.id).await?,
+            None => {
+                tracing::info!("Revoked session {} used, logging out", tracked);
+                auth_session.logout().await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let row = backend
+        .start_session(
+            user_id,
+            session_id,
+            ip_address,
+            context.and_then(|c| c.user_agent.clone()),
+        )
+        .await?;
+    auth_session
+        .session
+        .insert(SESSION_TRACKING_KEY, row.id)
+        .await?;
+    Ok(())
+}
+
+/// The admin_sessions row of this session, if it's tracked yet
+pub async fn current_session(auth_session: &AdminAuthSession) -> Option<Uuid> {
+    auth_session
+        .session
+        .get::<Uuid>(SESSION_TRACKING_KEY)
+        .await
+        .ok()
+        .flatten()
+}
+
+#[derive(Serialize)]
+struct SessionResponse {
+    id: Uuid,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    created_at: DateTime<FixedOffset>,
+    last_seen_at: DateTime<FixedOffset>,
+    current: bool,
+}
+
+impl SessionResponse {
+    fn new(session: admin_session::Model, current: Option<Uuid>) -> Self {
+        Self {
+            current: current == Some(session.id),
+            id: session.id,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+        }
+    }
+}
+
+async fn list(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<Vec<SessionResponse>>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let sessions = state
+        .auth_backend
+        .list_sessions(user.id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let current = current_session(&auth_session).await;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse::new(session, current))
+            .collect(),
+    ))
+}
+
+#[derive(Serialize)]
+struct RevokedResponse {
+    revoked: usize,
+}
+
+/// Sign out everywhere else
+async fn revoke_others(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<RevokedResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let revoked = state
+        .auth_backend
+        .revoke_sessions(user.id, current_session(&auth_session).await)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(RevokedResponse { revoked }))
+}
+
+async fn revoke(
+    State(state): State<AdminState>,
+    mut auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    state
+        .auth_backend
+        .revoke_session(user.id, id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    if current_session(&auth_session).await == Some(id) {
+        auth_session
+            .logout()
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -15,12 +15,14 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::admin::roles::Permission;
 use crate::errors::AppResult;
-use crate::middleware::AuthenticatedUser;
+use crate::middleware::{require_permission, AuthenticatedUser};
 use crate::settings::SettingsService;
 use axum::{
     extract::State,
     http::StatusCode,
+    middleware::from_fn_with_state,
     response::Json,
     routing::{get, put},
     Router,
@@ -35,8 +37,20 @@ pub struct SettingsState {
 
 pub fn settings_routes() -> Router<SettingsState> {
     Router::new()
-        .route("/api/admin/settings", get(get_all_settings))
-        .route("/api/admin/settings", put(update_setting))
+        .route(
+            "/api/admin/settings",
+            get(get_all_settings).route_layer(from_fn_with_state(
+                Permission::ViewSettings,
+                require_permission,
+            )),
+        )
+        .route(
+            "/api/admin/settings",
+            put(update_setting).route_layer(from_fn_with_state(
+                Permission::ManageSettings,
+                require_permission,
+            )),
+        )
 }
 
 #[derive(Serialize)]
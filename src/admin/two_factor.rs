@@ -0,0 +1,486 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// TOTP two-factor authentication for admin accounts
+// Password login only completes after a second step when 2FA is enabled for the account, or when the
+// require_2fa setting is on and the account still has to enroll. Until then the session only holds a
+// short-lived pending login, the user is not logged in.
+
+use super::routes::{AdminAuthSession, AdminState, UserResponse};
+use super::AdminUserAuth;
+use crate::errors::{AppError, AppResult};
+use anyhow::Result;
+use axum::{
+    extract::State,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use chrono::Utc;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// Setting key (category "system") that makes 2FA mandatory for every admin
+pub const REQUIRE_2FA_SETTING_KEY: &str = "require_2fa";
+
+pub const TOTP_DIGITS: usize = 6;
+pub const TOTP_STEP_SECONDS: u64 = 30;
+
+/// Steps either side of the current one that are accepted, for clock drift
+const TOTP_SKEW_STEPS: i64 = 1;
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// 16 base32 characters, 80 bits of randomness per code
+const RECOVERY_CODE_LENGTH: usize = 16;
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+const PENDING_LOGIN_KEY: &str = "admin.pending_2fa";
+const PENDING_LOGIN_TTL_SECONDS: i64 = 300;
+
+/// Wrong codes allowed before the password has to be entered again
+const MAX_PENDING_ATTEMPTS: u32 = 5;
+
+/// A new random TOTP secret, base32 encoded
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+fn totp(secret: &str, issuer: Option<&str>, account_name: &str) -> Result<TOTP> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| anyhow::anyhow!("Invalid TOTP secret: {:?}", e))?;
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP_SECONDS,
+        bytes,
+        issuer.map(str::to_string),
+        account_name.to_string(),
+    )
+    .map_err(|e| anyhow::anyhow!("Invalid TOTP parameters: {:?}", e))
+}
+
+/// otpauth:// URI for authenticator apps, usually shown as a QR code
+pub fn provisioning_uri(secret: &str, issuer: &str, account_name: &str) -> Result<String> {
+    Ok(totp(secret, Some(issuer), account_name)?.get_url())
+}
+
+/// Check a code against the steps around `now`, returning the matched step
+/// Steps at or before `last_step` are rejected so a code can only be used once
+pub fn verify_totp(
+    secret: &str,
+    code: &str,
+    last_step: Option<i64>,
+    now: u64,
+) -> Result<Option<i64>> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    let totp = totp(secret, None, "")?;
+    let current_step = (now / TOTP_STEP_SECONDS) as i64;
+
+    for step in current_step - TOTP_SKEW_STEPS..=current_step + TOTP_SKEW_STEPS {
+        if step < 0 || last_step.is_some_and(|last| step <= last) {
+            continue;
+        }
+        if totp.check(code, step as u64 * TOTP_STEP_SECONDS) {
+            return Ok(Some(step));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether a submitted code looks like a TOTP code rather than a recovery code
+pub fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+    code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Fresh recovery codes formatted as `xxxx-xxxx-xxxx-xxxx`, only ever shown once
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let chars: Vec<char> = (0..RECOVERY_CODE_LENGTH)
+                .map(|_| *RECOVERY_CODE_ALPHABET.choose(&mut rng).unwrap() as char)
+                .collect();
+            chars
+                .chunks(4)
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect()
+}
+
+/// Recovery codes are random, so a fast hash is enough to keep them unusable if the database leaks
+/// Dashes, spaces and case are ignored when comparing
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+/// Password accepted, waiting for the second factor (or for enrollment)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingLogin {
+    user_id: Uuid,
+    enrollment: bool,
+    expires_at: i64,
+    attempts: u32,
+}
+
+/// Remember a password-verified user until the second step completes
+pub async fn start_pending_login(
+    session: &Session,
+    user_id: Uuid,
+    enrollment: bool,
+) -> AppResult<()> {
+    let pending = PendingLogin {
+        user_id,
+        enrollment,
+        expires_at: Utc::now().timestamp() + PENDING_LOGIN_TTL_SECONDS,
+        attempts: 0,
+    };
+    session
+        .insert(PENDING_LOGIN_KEY, pending)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+async fn pending_login(session: &Session) -> AppResult<Option<PendingLogin>> {
+    let pending: Option<PendingLogin> = session
+        .get(PENDING_LOGIN_KEY)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    match pending {
+        Some(pending) if pending.expires_at > Utc::now().timestamp() => Ok(Some(pending)),
+        Some(_) => {
+            clear_pending_login(session).await?;
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+async fn clear_pending_login(session: &Session) -> AppResult<()> {
+    session
+        .remove::<PendingLogin>(PENDING_LOGIN_KEY)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(())
+}
+
+pub fn two_factor_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/login/2fa", post(login_second_factor))
+        .route("/api/admin/2fa", get(status))
+        .route("/api/admin/2fa/setup", post(setup))
+        .route("/api/admin/2fa/confirm", post(confirm))
+        .route(
+            "/api/admin/2fa/recovery-codes",
+            post(regenerate_recovery_codes),
+        )
+        .route("/api/admin/2fa/disable", post(disable))
+}
+
+#[derive(Deserialize)]
+struct CodeRequest {
+    code: String,
+}
+
+/// Second login step, accepts a TOTP code or an unused recovery code
+async fn login_second_factor(
+    mut auth_session: AdminAuthSession,
+    Json(req): Json<CodeRequest>,
+) -> AppResult<Json<UserResponse>> {
+    let mut pending = pending_login(&auth_session.session)
+        .await?
+        .filter(|pending| !pending.enrollment)
+        .ok_or_else(|| {
+            AppError::AuthError("Login session expired, please log in again".to_string())
+        })?;
+
+    auth_session
+        .backend
+        .ensure_login_allowed(pending.user_id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    let valid = auth_session
+        .backend
+        .verify_second_factor(pending.user_id, &req.code)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    if !valid {
+        // Wrong codes count toward the account lockout like wrong passwords
+        auth_session
+            .backend
+            .record_failed_login(pending.user_id)
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+        pending.attempts += 1;
+        if pending.attempts >= MAX_PENDING_ATTEMPTS {
+            clear_pending_login(&auth_session.session).await?;
+            return Err(AppError::AuthError(
+                "Too many invalid codes, please log in again".to_string(),
+            ));
+        }
+        auth_session
+            .session
+            .insert(PENDING_LOGIN_KEY, pending)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        return Err(AppError::AuthError(
+            "Invalid authentication code".to_string(),
+        ));
+    }
+
+    let admin = auth_session
+        .backend
+        .find_admin(pending.user_id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    clear_pending_login(&auth_session.session).await?;
+    auth_session
+        .backend
+        .clear_failed_logins(admin.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+    let user = AdminUserAuth::from(admin);
+    auth_session
+        .login(&user)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(UserResponse::from(user)))
+}
+
+/// The account being enrolled: the logged in admin, or one whose login is waiting on enrollment
+async fn enrolling_user(auth_session: &AdminAuthSession) -> AppResult<(Uuid, bool)> {
+    if let Some(user) = &auth_session.user {
+        return Ok((user.id, false));
+    }
+
+    match pending_login(&auth_session.session).await? {
+        Some(pending) if pending.enrollment => Ok((pending.user_id, true)),
+        _ => Err(AppError::AuthError("Not authenticated".to_string())),
+    }
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    enabled: bool,
+    required: bool,
+    recovery_codes_remaining: usize,
+}
+
+async fn status(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<StatusResponse>> {
+    let user = auth_session
+        .user
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+    let admin = state
+        .auth_backend
+        .find_admin(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+    let required = state
+        .settings
+        .get_bool(REQUIRE_2FA_SETTING_KEY, Some("system"), None)
+        .await
+        .unwrap_or(false);
+
+    Ok(Json(StatusResponse {
+        enabled: admin.totp_enabled,
+        required,
+        recovery_codes_remaining: admin
+            .recovery_codes
+            .as_deref()
+            .and_then(|codes| serde_json::from_str::<Vec<String>>(codes).ok())
+            .map(|codes| codes.len())
+            .unwrap_or(0),
+    }))
+}
+
+#[derive(Serialize)]
+struct SetupResponse {
+    secret: String,
+    otpauth_uri: String,
+}
+
+/// Start enrollment with a new secret, replacing any unconfirmed one
+async fn setup(auth_session: AdminAuthSession) -> AppResult<Json<SetupResponse>> {
+    let (user_id, _) = enrolling_user(&auth_session).await?;
+    let (secret, otpauth_uri) = auth_session
+        .backend
+        .begin_totp_enrollment(user_id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(SetupResponse {
+        secret,
+        otpauth_uri,
+    }))
+}
+
+#[derive(Serialize)]
+struct RecoveryCodesResponse {
+    recovery_codes: Vec<String>,
+}
+
+/// Finish enrollment with a code from the authenticator app
+/// Completes a pending login that was waiting on enrollment
+async fn confirm(
+    mut auth_session: AdminAuthSession,
+    Json(req): Json<CodeRequest>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    let (user_id, pending) = enrolling_user(&auth_session).await?;
+    let recovery_codes = auth_session
+        .backend
+        .confirm_totp_enrollment(user_id, &req.code)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    if pending {
+        let admin = auth_session
+            .backend
+            .find_admin(user_id)
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+        clear_pending_login(&auth_session.session).await?;
+        auth_session
+            .backend
+            .clear_failed_logins(admin.id)
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+        auth_session
+            .login(&AdminUserAuth::from(admin))
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+    }
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// Replace all recovery codes, requires a current TOTP code
+async fn regenerate_recovery_codes(
+    auth_session: AdminAuthSession,
+    Json(req): Json<CodeRequest>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    if !is_totp_code(&req.code)
+        || !auth_session
+            .backend
+            .verify_second_factor(user.id, &req.code)
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?
+    {
+        return Err(AppError::AuthError(
+            "Invalid authentication code".to_string(),
+        ));
+    }
+
+    let recovery_codes = auth_session
+        .backend
+        .regenerate_recovery_codes(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+#[derive(Deserialize)]
+struct DisableRequest {
+    password: String,
+    code: String,
+}
+
+/// Turn 2FA off, needs the password and a TOTP or recovery code
+/// Not allowed while the require_2fa setting is on
+async fn disable(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Json(req): Json<DisableRequest>,
+) -> AppResult<Json<StatusResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+
+    let required = state
+        .settings
+        .get_bool(REQUIRE_2FA_SETTING_KEY, Some("system"), None)
+        .await
+        .unwrap_or(false);
+    if required {
+        return Err(AppError::AuthError(
+            "Two-factor authentication is required for all admins".to_string(),
+        ));
+    }
+
+    let password_valid = auth_session
+        .backend
+        .verify_admin_password(user.id, &req.password)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+    let code_valid = password_valid
+        && auth_session
+            .backend
+            .verify_second_factor(user.id, &req.code)
+            .await
+            .map_err(|e| AppError::AuthError(e.to_string()))?;
+    if !code_valid {
+        return Err(AppError::AuthError(
+            "Invalid password or authentication code".to_string(),
+        ));
+    }
+
+    auth_session
+        .backend
+        .disable_totp(user.id)
+        .await
+        .map_err(|e| AppError::AuthError(e.to_string()))?;
+
+    Ok(Json(StatusResponse {
+        enabled: false,
+        required,
+        recovery_codes_remaining: 0,
+    }))
+}
@@ -0,0 +1,201 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+// Admin user management
+// Owners can see every admin, change their role, disable or delete them and resend their verification
+// email. The backend refuses anything that would leave no active owner.
+
+use super::auth::ManagementConflict;
+use super::roles::{Permission, Role};
+use super::routes::{ensure_permission, AdminAuthSession, AdminState, MessageResponse};
+use crate::entities::admin_user;
+use crate::errors::{AppError, AppResult};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post, put},
+    Router,
+};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub fn user_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/api/admin/users", get(list))
+        .route("/api/admin/users/{id}", delete(remove))
+        .route("/api/admin/users/{id}/role", put(set_role))
+        .route("/api/admin/users/{id}/disable", post(disable))
+        .route("/api/admin/users/{id}/enable", post(enable))
+        .route(
+            "/api/admin/users/{id}/resend-verification",
+            post(resend_verification),
+        )
+}
+
+#[derive(Serialize)]
+struct AdminUserResponse {
+    id: Uuid,
+    email: String,
+    role: String,
+    email_verified: bool,
+    disabled: bool,
+    two_factor_enabled: bool,
+    locked_until: Option<DateTime<FixedOffset>>,
+    created_at: DateTime<FixedOffset>,
+}
+
+impl From<admin_user::Model> for AdminUserResponse {
+    fn from(admin: admin_user::Model) -> Self {
+        Self {
+            id: admin.id,
+            email: admin.email,
+            role: admin.role,
+            email_verified: admin.email_verified,
+            disabled: admin.disabled,
+            two_factor_enabled: admin.totp_enabled,
+            locked_until: admin.locked_until,
+            created_at: admin.created_at,
+        }
+    }
+}
+
+/// Rule violations are the request's fault, not the session's
+fn management_error(e: anyhow::Error) -> AppError {
+    match e.downcast_ref::<ManagementConflict>() {
+        Some(conflict) => AppError::Conflict(conflict.to_string()),
+        None => AppError::AuthError(e.to_string()),
+    }
+}
+
+fn require_manager(auth_session: &AdminAuthSession) -> AppResult<()> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or_else(|| AppError::AuthError("Not authenticated".to_string()))?;
+    ensure_permission(user, Permission::ManageAdmins)
+}
+
+async fn list(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+) -> AppResult<Json<Vec<AdminUserResponse>>> {
+    require_manager(&auth_session)?;
+
+    let admins = state
+        .auth_backend
+        .list_admins()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(admins.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Deserialize)]
+struct SetRoleRequest {
+    role: Role,
+}
+
+async fn set_role(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetRoleRequest>,
+) -> AppResult<Json<AdminUserResponse>> {
+    require_manager(&auth_session)?;
+
+    let admin = state
+        .auth_backend
+        .set_admin_role(id, req.role)
+        .await
+        .map_err(management_error)?;
+
+    Ok(Json(admin.into()))
+}
+
+async fn disable(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<AdminUserResponse>> {
+    set_disabled(state, auth_session, id, true).await
+}
+
+async fn enable(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<AdminUserResponse>> {
+    set_disabled(state, auth_session, id, false).await
+}
+
+async fn set_disabled(
+    state: AdminState,
+    auth_session: AdminAuthSession,
+    id: Uuid,
+    disabled: bool,
+) -> AppResult<Json<AdminUserResponse>> {
+    require_manager(&auth_session)?;
+
+    let admin = state
+        .auth_backend
+        .set_admin_disabled(id, disabled)
+        .await
+        .map_err(management_error)?;
+
+    Ok(Json(admin.into()))
+}
+
+async fn resend_verification(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<MessageResponse>> {
+    require_manager(&auth_session)?;
+
+    let (admin, token) = state
+        .auth_backend
+        .resend_verification(id)
+        .await
+        .map_err(management_error)?;
+
+    state
+        .email_service
+        .send_verification_email(&admin.email, &token)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(MessageResponse {
+        message: format!("Verification email sent to {}", admin.email),
+    }))
+}
+
+async fn remove(
+    State(state): State<AdminState>,
+    auth_session: AdminAuthSession,
+    Path(id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    require_manager(&auth_session)?;
+
+    state
+        .auth_backend
+        .delete_admin(id)
+        .await
+        .map_err(management_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
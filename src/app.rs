@@ -15,14 +15,20 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::contact::ContactService;
+use crate::content::ContentService;
+use crate::email::EmailService;
+use crate::engagement::EngagementService;
 use crate::entities::{access_code, AccessCode};
 use crate::s3::S3Service;
 use crate::security::SecurityService;
 use crate::settings::SettingsService;
+use crate::templates::TemplateService;
 use anyhow::Result;
 use chrono::Utc;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use std::env;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -30,6 +36,11 @@ pub struct AppState {
     pub security: SecurityService,
     pub settings: SettingsService,
     pub s3: S3Service,
+    pub engagement: EngagementService,
+    pub templates: TemplateService,
+    pub content: ContentService,
+    pub email: Arc<EmailService>,
+    pub contact: ContactService,
 }
 
 impl AppState {
@@ -62,6 +73,11 @@ impl AppState {
         let security = SecurityService::new(db.clone(), Some(security_config.clone()));
         let settings = SettingsService::new(db.clone());
         let s3 = S3Service::new().await?;
+        let engagement = EngagementService::new(db.clone());
+        let templates = TemplateService::from_env(settings.clone())?;
+        let content = ContentService::from_env(s3.clone(), db.clone());
+        let email = Arc::new(EmailService::new().await?);
+        let contact = ContactService::new(db.clone(), settings.clone(), email.clone());
 
         // RUST_LOG=warn recommended for most deployments, info and debug generate lots of logs
         tracing::info!("Database connected and services initialized");
@@ -78,33 +94,46 @@ impl AppState {
             security,
             settings,
             s3,
+            engagement,
+            templates,
+            content,
+            email,
+            contact,
         })
     }
 
     /// Check if code is valid in database and increment usage count
     pub async fn is_valid_code(&self, code: &str) -> Result<bool> {
-        // Check database
-        let db_code = AccessCode::find()
-            .filter(access_code::Column::Code.eq(code))
-            .one(&self.db)
-            .await?;
-
-        if let Some(db_code) = db_code {
-            // Check if expired
-            if let Some(expires_at) = db_code.expires_at {
-                if expires_at.with_timezone(&Utc) < Utc::now() {
-                    return Ok(false); // Expired
-                }
-            }
+        Ok(self.validate_code(code).await?.is_some())
+    }
 
+    /// Look up a valid (existing, unexpired) code and increment its usage count
+    /// Returns the code record so callers can use details such as the recipient name
+    pub async fn validate_code(&self, code: &str) -> Result<Option<access_code::Model>> {
+        if let Some(db_code) = self.find_active_code(code).await? {
             // Increment usage count
             let mut active_code: access_code::ActiveModel = db_code.into();
             active_code.usage_count = Set(active_code.usage_count.unwrap() + 1);
-            active_code.update(&self.db).await?;
+            let updated = active_code.update(&self.db).await?;
 
-            return Ok(true);
+            return Ok(Some(updated));
         }
 
-        Ok(false)
+        Ok(None)
+    }
+
+    /// Look up a valid (existing, unexpired) code without counting it as a use
+    /// Used for follow-up requests such as engagement beacons
+    pub async fn find_active_code(&self, code: &str) -> Result<Option<access_code::Model>> {
+        let db_code = AccessCode::find()
+            .filter(access_code::Column::Code.eq(code))
+            .one(&self.db)
+            .await?;
+
+        Ok(db_code.filter(|db_code| {
+            db_code
+                .expires_at
+                .is_none_or(|expires_at| expires_at.with_timezone(&Utc) >= Utc::now())
+        }))
     }
 }
@@ -0,0 +1,76 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Shared AWS client configuration
+// Lets S3 and SES be pointed at S3-compatible or emulated endpoints (MinIO, localstack)
+// for development and CI without a real AWS account
+
+use aws_config::{BehaviorVersion, Region, SdkConfig};
+use std::env;
+
+/// Per-service endpoint overrides, read from `{PREFIX}_ENDPOINT_URL`, `{PREFIX}_REGION`
+/// and `{PREFIX}_FORCE_PATH_STYLE` (e.g. S3_ENDPOINT_URL)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AwsEndpointConfig {
+    /// Custom endpoint such as http://localhost:9000, None uses the AWS default
+    pub endpoint_url: Option<String>,
+    /// Service region, falling back to AWS_REGION and then the default provider chain
+    pub region: Option<String>,
+    /// Use path-style addressing (http://host/bucket/key), required by most S3-compatible servers
+    pub force_path_style: bool,
+}
+
+impl AwsEndpointConfig {
+    pub fn from_env(prefix: &str) -> Self {
+        Self::from_lookup(prefix, |key| env::var(key).ok())
+    }
+
+    /// Build from an arbitrary variable lookup, used by from_env and tests
+    pub fn from_lookup(prefix: &str, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let non_empty = |key: String| lookup(&key).filter(|value| !value.trim().is_empty());
+
+        let endpoint_url = non_empty(format!("{}_ENDPOINT_URL", prefix));
+        let region =
+            non_empty(format!("{}_REGION", prefix)).or_else(|| non_empty("AWS_REGION".into()));
+        let force_path_style = non_empty(format!("{}_FORCE_PATH_STYLE", prefix))
+            .map(|value| value.trim().eq_ignore_ascii_case("true") || value.trim() == "1")
+            .unwrap_or(false);
+
+        Self {
+            endpoint_url,
+            region,
+            force_path_style,
+        }
+    }
+
+    /// Load shared SDK config with the region and endpoint overrides applied
+    /// Credentials still come from the default provider chain (AWS_ACCESS_KEY_ID etc.)
+    pub async fn load_sdk_config(&self) -> SdkConfig {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+
+        if let Some(region) = &self.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+
+        if let Some(endpoint_url) = &self.endpoint_url {
+            tracing::info!("Using custom AWS endpoint: {}", endpoint_url);
+            loader = loader.endpoint_url(endpoint_url);
+        }
+
+        loader.load().await
+    }
+}
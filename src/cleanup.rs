@@ -0,0 +1,285 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Cleanup of per-code documents left in S3 after a code expires or is deleted
+// Documents live under {code}/, so a prefix is orphaned once no active code uses it
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{DatabaseConnection, EntityTrait};
+use serde::Serialize;
+use std::{collections::HashMap, env};
+
+use crate::content;
+use crate::entities::{access_code, AccessCode};
+use crate::s3::S3Service;
+use crate::settings::SettingsService;
+
+/// System setting enabling the background cleanup job ("true" / "false")
+pub const DOCUMENT_CLEANUP_ENABLED_SETTING_KEY: &str = "document_cleanup_enabled";
+
+/// System setting selecting what happens to orphaned documents ("archive" / "delete")
+pub const DOCUMENT_CLEANUP_MODE_SETTING_KEY: &str = "document_cleanup_mode";
+
+/// System setting for how many days an expired code keeps its documents
+pub const DOCUMENT_CLEANUP_GRACE_DAYS_SETTING_KEY: &str = "document_cleanup_grace_days";
+
+const DEFAULT_GRACE_DAYS: i64 = 7;
+
+/// Archived documents are moved to {prefix}/{code}/..., skipped when scanning for orphans
+const DEFAULT_ARCHIVE_PREFIX: &str = "_archive";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupMode {
+    /// Move documents under the archive prefix (default)
+    Archive,
+    /// Permanently delete documents
+    Delete,
+}
+
+impl CleanupMode {
+    /// Parse a setting value, anything other than "delete" archives so nothing is lost by mistake
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "delete" => CleanupMode::Delete,
+            _ => CleanupMode::Archive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupReason {
+    /// The code exists but expired more than the grace period ago
+    Expired,
+    /// No code exists for the prefix and nothing was uploaded to it within the grace period
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CleanupCandidate {
+    pub code: String,
+    pub reason: CleanupReason,
+    pub expired_at: Option<String>,
+}
+
+/// Work out which prefixes are orphaned given the current codes
+/// Codes that never expire, or expired within the grace period, keep their documents.
+/// Prefixes without a code get the same grace period from their newest upload, so documents
+/// uploaded before their code is created survive, and are kept when their age is unknown.
+/// Reserved prefixes (the archive, post content) are never treated as code folders.
+pub fn plan_cleanup(
+    prefixes: &[String],
+    codes: &[access_code::Model],
+    last_uploaded: &HashMap<String, DateTime<Utc>>,
+    reserved_prefixes: &[String],
+    now: DateTime<Utc>,
+    grace_period: Duration,
+) -> Vec<CleanupCandidate> {
+    prefixes
+        .iter()
+        .filter(|prefix| !prefix.is_empty() && !reserved_prefixes.contains(prefix))
+        .filter_map(|prefix| match codes.iter().find(|c| &c.code == prefix) {
+            None => {
+                let uploaded_at = last_uploaded.get(prefix)?;
+                (*uploaded_at + grace_period < now).then(|| CleanupCandidate {
+                    code: prefix.clone(),
+                    reason: CleanupReason::Deleted,
+                    expired_at: None,
+                })
+            }
+            Some(code) => {
+                let expires_at = code.expires_at?.with_timezone(&Utc);
+                (expires_at + grace_period < now).then(|| CleanupCandidate {
+                    code: prefix.clone(),
+                    reason: CleanupReason::Expired,
+                    expired_at: Some(expires_at.to_rfc3339()),
+                })
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupEntry {
+    pub code: String,
+    pub reason: CleanupReason,
+    pub expired_at: Option<String>,
+    pub objects: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Top-level bucket prefix the archive lives under, list_prefixes only returns that level
+/// so a nested S3_ARCHIVE_PREFIX like archive/docs has to reserve all of archive/
+pub fn reserved_archive_prefix(archive_prefix: &str) -> String {
+    archive_prefix
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub mode: CleanupMode,
+    pub archive_prefix: String,
+    pub grace_days: i64,
+    pub entries: Vec<CleanupEntry>,
+}
+
+#[derive(Clone)]
+pub struct DocumentCleanupService {
+    db: DatabaseConnection,
+    s3: S3Service,
+    settings: SettingsService,
+    archive_prefix: String,
+}
+
+impl DocumentCleanupService {
+    pub fn new(db: DatabaseConnection, s3: S3Service, settings: SettingsService) -> Self {
+        let archive_prefix = env::var("S3_ARCHIVE_PREFIX")
+            .ok()
+            .map(|p| p.trim_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| DEFAULT_ARCHIVE_PREFIX.to_string());
+
+        Self {
+            db,
+            s3,
+            settings,
+            archive_prefix,
+        }
+    }
+
+    /// Whether the background job should run, checked on every tick so it can be toggled live
+    pub async fn is_enabled(&self) -> Result<bool> {
+        self.settings
+            .get_bool(DOCUMENT_CLEANUP_ENABLED_SETTING_KEY, Some("system"), None)
+            .await
+    }
+
+    /// Report what a cleanup run would do without touching any objects
+    pub async fn dry_run(&self) -> Result<CleanupReport> {
+        self.execute(true).await
+    }
+
+    /// Archive or delete all orphaned documents
+    /// Failures are recorded per code so one bad prefix doesn't stop the rest
+    pub async fn run(&self) -> Result<CleanupReport> {
+        self.execute(false).await
+    }
+
+    async fn execute(&self, dry_run: bool) -> Result<CleanupReport> {
+        let mode = self
+            .settings
+            .get(DOCUMENT_CLEANUP_MODE_SETTING_KEY, Some("system"), None)
+            .await?
+            .map(|value| CleanupMode::parse(&value))
+            .unwrap_or(CleanupMode::Archive);
+        let grace_days = self
+            .settings
+            .get(
+                DOCUMENT_CLEANUP_GRACE_DAYS_SETTING_KEY,
+                Some("system"),
+                None,
+            )
+            .await?
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .filter(|days| *days >= 0)
+            .unwrap_or(DEFAULT_GRACE_DAYS);
+
+        let codes = AccessCode::find().all(&self.db).await?;
+        let prefixes = self.s3.list_prefixes().await?;
+        let reserved_prefixes = [
+            reserved_archive_prefix(&self.archive_prefix),
+            content::reserved_storage_prefix(),
+        ];
+
+        // Only prefixes without a code need their age, the rest go by the code's expiry
+        let mut last_uploaded = HashMap::new();
+        for prefix in prefixes.iter().filter(|prefix| {
+            !reserved_prefixes.contains(prefix) && !codes.iter().any(|c| &c.code == *prefix)
+        }) {
+            if let Some(uploaded_at) = self.s3.last_modified(prefix).await? {
+                last_uploaded.insert(prefix.clone(), uploaded_at);
+            }
+        }
+
+        let candidates = plan_cleanup(
+            &prefixes,
+            &codes,
+            &last_uploaded,
+            &reserved_prefixes,
+            Utc::now(),
+            Duration::days(grace_days),
+        );
+
+        let mut entries = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let objects = self.s3.list_files(&candidate.code).await?;
+            let error = if dry_run {
+                None
+            } else {
+                self.remove_objects(&objects, mode)
+                    .await
+                    .err()
+                    .map(|e| e.to_string())
+            };
+
+            match &error {
+                Some(e) => tracing::error!("Document cleanup failed for {}: {}", candidate.code, e),
+                None if !dry_run => tracing::info!(
+                    "Document cleanup {:?} {} objects for code: {}",
+                    mode,
+                    objects.len(),
+                    candidate.code
+                ),
+                None => {}
+            }
+
+            entries.push(CleanupEntry {
+                code: candidate.code,
+                reason: candidate.reason,
+                expired_at: candidate.expired_at,
+                objects,
+                error,
+            });
+        }
+
+        Ok(CleanupReport {
+            dry_run,
+            mode,
+            archive_prefix: self.archive_prefix.clone(),
+            grace_days,
+            entries,
+        })
+    }
+
+    async fn remove_objects(&self, keys: &[String], mode: CleanupMode) -> Result<()> {
+        for key in keys {
+            if mode == CleanupMode::Archive {
+                self.s3
+                    .copy_object(key, &format!("{}/{}", self.archive_prefix, key))
+                    .await?;
+            }
+            self.s3.delete_object(key).await?;
+        }
+        Ok(())
+    }
+}
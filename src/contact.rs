@@ -0,0 +1,229 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Public contact form: messages are stored for the admin inbox and forwarded by email
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use std::env;
+use std::net::IpAddr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::email::EmailService;
+use crate::entities::{contact_message, ContactMessage};
+use crate::security::SecurityService;
+use crate::settings::SettingsService;
+
+/// Address messages are forwarded to (category "system"), messages are only stored when unset
+pub const CONTACT_EMAIL_SETTING_KEY: &str = "contact_email";
+
+/// Action name used for per-IP throttling
+pub const CONTACT_ACTION: &str = "contact";
+
+/// Hidden form field that people leave empty and naive bots fill in
+pub const HONEYPOT_FIELD_NAME: &str = "website";
+
+const DEFAULT_CONTACT_LIMIT_PER_HOUR: u32 = 5;
+
+pub const MAX_NAME_LENGTH: usize = 100;
+pub const MAX_EMAIL_LENGTH: usize = 254;
+pub const MAX_MESSAGE_LENGTH: usize = 5000;
+
+/// A validated contact form submission
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactSubmission {
+    pub name: String,
+    pub email: String,
+    pub message: String,
+}
+
+impl ContactSubmission {
+    /// Trim and validate the form fields, the error is shown to the visitor
+    pub fn parse(name: &str, email: &str, message: &str) -> Result<Self, &'static str> {
+        // Names and addresses end up in the email subject and headers, keep them on one line
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        let email = email.trim();
+        let message = message.trim();
+
+        if name.is_empty() || email.is_empty() || message.is_empty() {
+            return Err("Please fill in your name, email and message.");
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err("That name is too long.");
+        }
+        if email.len() > MAX_EMAIL_LENGTH || !is_plausible_email(email) {
+            return Err("Please enter a valid email address.");
+        }
+        if message.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err("That message is too long.");
+        }
+
+        Ok(Self {
+            name,
+            email: email.to_string(),
+            message: message.to_string(),
+        })
+    }
+}
+
+/// Loose address check, `local@domain.tld` without whitespace or control characters
+pub fn is_plausible_email(email: &str) -> bool {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ',' | ';'))
+}
+
+#[derive(Clone)]
+pub struct ContactService {
+    db: DatabaseConnection,
+    settings: SettingsService,
+    email: Arc<EmailService>,
+    limit_per_hour: u32,
+}
+
+impl ContactService {
+    pub fn new(
+        db: DatabaseConnection,
+        settings: SettingsService,
+        email: Arc<EmailService>,
+    ) -> Self {
+        let limit_per_hour = env::var("CONTACT_LIMIT_PER_HOUR")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_CONTACT_LIMIT_PER_HOUR);
+
+        Self {
+            db,
+            settings,
+            email,
+            limit_per_hour,
+        }
+    }
+
+    /// Count a submission against the IP's hourly allowance
+    pub fn check_limit(&self, security: &SecurityService, ip: IpAddr) -> bool {
+        security.check_action_limit(ip, CONTACT_ACTION, self.limit_per_hour, Duration::hours(1))
+    }
+
+    /// Store a message and forward it to the contact address
+    /// Delivery failures are logged, the message stays in the inbox either way
+    pub async fn submit(
+        &self,
+        submission: &ContactSubmission,
+        ip: Option<IpAddr>,
+        user_agent: Option<String>,
+    ) -> Result<contact_message::Model> {
+        let now = Utc::now();
+        let message = contact_message::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(submission.name.clone()),
+            email: Set(submission.email.clone()),
+            message: Set(submission.message.clone()),
+            ip_address: Set(ip.map(|ip| ip.to_string())),
+            user_agent: Set(user_agent),
+            read: Set(false),
+            delivered: Set(false),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+        }
+        .insert(&self.db)
+        .await?;
+
+        let recipient = self
+            .settings
+            .get(CONTACT_EMAIL_SETTING_KEY, Some("system"), None)
+            .await?
+            .filter(|r| !r.trim().is_empty());
+        let Some(recipient) = recipient else {
+            tracing::info!(
+                "Contact message stored, no {} set",
+                CONTACT_EMAIL_SETTING_KEY
+            );
+            return Ok(message);
+        };
+
+        match self
+            .email
+            .send_contact_message(
+                recipient.trim(),
+                &submission.name,
+                &submission.email,
+                &submission.message,
+            )
+            .await
+        {
+            Ok(()) => {
+                let mut active: contact_message::ActiveModel = message.into();
+                active.delivered = Set(true);
+                active.updated_at = Set(Utc::now().into());
+                Ok(active.update(&self.db).await?)
+            }
+            Err(e) => {
+                tracing::error!("{}", e);
+                Ok(message)
+            }
+        }
+    }
+
+    /// One page of messages, newest first, with the total count and number of pages
+    pub async fn list(
+        &self,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<contact_message::Model>, u64, u64)> {
+        let paginator = ContactMessage::find()
+            .order_by(contact_message::Column::CreatedAt, Order::Desc)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await?;
+        let total_pages = paginator.num_pages().await?;
+        let messages = paginator.fetch_page(page.saturating_sub(1)).await?;
+        Ok((messages, total, total_pages))
+    }
+
+    pub async fn unread_count(&self) -> Result<u64> {
+        Ok(ContactMessage::find()
+            .filter(contact_message::Column::Read.eq(false))
+            .count(&self.db)
+            .await?)
+    }
+
+    /// Mark a message read or unread, returns false if it doesn't exist
+    pub async fn set_read(&self, id: Uuid, read: bool) -> Result<bool> {
+        let Some(message) = ContactMessage::find_by_id(id).one(&self.db).await? else {
+            return Ok(false);
+        };
+
+        let mut active: contact_message::ActiveModel = message.into();
+        active.read = Set(read);
+        active.updated_at = Set(Utc::now().into());
+        active.update(&self.db).await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,202 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Atom and RSS feeds of published posts
+// Links are absolute, built from SITE_URL, so feed readers can resolve them outside the site.
+
+use super::html::SITE_NAME;
+use super::Post;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Newest posts included in a feed
+pub const FEED_ENTRY_LIMIT: usize = 20;
+
+pub const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+pub const RSS_CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+
+/// Site URL without a trailing slash, empty when SITE_URL is unset
+fn base_url(site_url: Option<&str>) -> &str {
+    site_url.unwrap_or_default().trim_end_matches('/')
+}
+
+fn post_url(base: &str, post: &Post) -> String {
+    format!("{}/blog/{}", base, post.slug)
+}
+
+/// Feed level updated time, the newest entry or the epoch for an empty feed
+fn feed_updated(posts: &[&Post]) -> DateTime<Utc> {
+    posts
+        .iter()
+        .filter_map(|post| post.updated_at())
+        .max()
+        .unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+/// Atom 1.0 feed, `posts` should already be filtered to published posts and sorted newest first
+pub fn render_atom(posts: &[Post], site_url: Option<&str>) -> String {
+    let base = base_url(site_url);
+    let posts: Vec<&Post> = posts.iter().take(FEED_ENTRY_LIMIT).collect();
+    let updated = feed_updated(&posts);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(xml, "  <title>{}</title>", xml_escape(SITE_NAME));
+    let _ = writeln!(xml, "  <id>{}/blog</id>", xml_escape(base));
+    let _ = writeln!(
+        xml,
+        "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}/feed.xml\"/>",
+        xml_escape(base)
+    );
+    let _ = writeln!(
+        xml,
+        "  <link rel=\"alternate\" type=\"text/html\" href=\"{}/blog\"/>",
+        xml_escape(base)
+    );
+    let _ = writeln!(xml, "  <updated>{}</updated>", rfc3339(updated));
+    let _ = writeln!(
+        xml,
+        "  <author><name>{}</name></author>",
+        xml_escape(SITE_NAME)
+    );
+
+    for post in posts {
+        let url = xml_escape(&post_url(base, post));
+        let entry_updated = post.updated_at().unwrap_or(updated);
+
+        xml.push_str("  <entry>\n");
+        let _ = writeln!(xml, "    <title>{}</title>", xml_escape(&post.title));
+        let _ = writeln!(xml, "    <id>{}</id>", url);
+        let _ = writeln!(
+            xml,
+            "    <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>",
+            url
+        );
+        if let Some(published) = post.date.and_then(|d| d.and_hms_opt(0, 0, 0)) {
+            let _ = writeln!(
+                xml,
+                "    <published>{}</published>",
+                rfc3339(published.and_utc())
+            );
+        }
+        let _ = writeln!(xml, "    <updated>{}</updated>", rfc3339(entry_updated));
+        for tag in &post.tags {
+            let _ = writeln!(xml, "    <category term=\"{}\"/>", xml_escape(tag));
+        }
+        if let Some(summary) = &post.summary {
+            let _ = writeln!(xml, "    <summary>{}</summary>", xml_escape(summary));
+        }
+        let _ = writeln!(
+            xml,
+            "    <content type=\"html\">{}</content>",
+            xml_escape(&post.body_html)
+        );
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// RSS 2.0 feed, `posts` should already be filtered to published posts and sorted newest first
+pub fn render_rss(posts: &[Post], site_url: Option<&str>) -> String {
+    let base = base_url(site_url);
+    let posts: Vec<&Post> = posts.iter().take(FEED_ENTRY_LIMIT).collect();
+    let updated = feed_updated(&posts);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str("  <channel>\n");
+    let _ = writeln!(xml, "    <title>{}</title>", xml_escape(SITE_NAME));
+    let _ = writeln!(xml, "    <link>{}/blog</link>", xml_escape(base));
+    let _ = writeln!(
+        xml,
+        "    <description>Posts from {}</description>",
+        xml_escape(SITE_NAME)
+    );
+    let _ = writeln!(
+        xml,
+        "    <atom:link href=\"{}/rss.xml\" rel=\"self\" type=\"application/rss+xml\"/>",
+        xml_escape(base)
+    );
+    let _ = writeln!(
+        xml,
+        "    <lastBuildDate>{}</lastBuildDate>",
+        updated.to_rfc2822()
+    );
+
+    for post in posts {
+        let url = xml_escape(&post_url(base, post));
+
+        xml.push_str("    <item>\n");
+        let _ = writeln!(xml, "      <title>{}</title>", xml_escape(&post.title));
+        let _ = writeln!(xml, "      <link>{}</link>", url);
+        let _ = writeln!(xml, "      <guid isPermaLink=\"true\">{}</guid>", url);
+        if let Some(published) = post.updated_at() {
+            let _ = writeln!(xml, "      <pubDate>{}</pubDate>", published.to_rfc2822());
+        }
+        for tag in &post.tags {
+            let _ = writeln!(xml, "      <category>{}</category>", xml_escape(tag));
+        }
+        let _ = writeln!(
+            xml,
+            "      <description>{}</description>",
+            xml_escape(&post.body_html)
+        );
+        xml.push_str("    </item>\n");
+    }
+
+    xml.push_str("  </channel>\n");
+    xml.push_str("</rss>\n");
+    xml
+}
+
+/// Strong ETag for a rendered feed body
+pub fn etag(body: &str) -> String {
+    let digest = Sha256::digest(body.as_bytes());
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// Whether an If-None-Match header value matches the current ETag
+/// Handles `*`, comma separated lists and weak validators, which compare equal for GET
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+fn rfc3339(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,61 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::Post;
+use anyhow::Result;
+use minijinja::{context, Environment};
+
+const BASE_TEMPLATE: &str = include_str!("templates/base.html");
+const INDEX_TEMPLATE: &str = include_str!("templates/index.html");
+const POST_TEMPLATE: &str = include_str!("templates/post.html");
+
+/// Page title suffix for content pages
+pub(super) const SITE_NAME: &str = "Grant DeFayette";
+
+fn environment() -> Result<Environment<'static>> {
+    let mut env = Environment::new();
+    env.add_template("base.html", BASE_TEMPLATE)
+        .map_err(|e| anyhow::anyhow!("Invalid content base template: {}", e))?;
+    env.add_template("index.html", INDEX_TEMPLATE)
+        .map_err(|e| anyhow::anyhow!("Invalid content index template: {}", e))?;
+    env.add_template("post.html", POST_TEMPLATE)
+        .map_err(|e| anyhow::anyhow!("Invalid content post template: {}", e))?;
+    Ok(env)
+}
+
+/// Render the post list, `tag` is set on tag pages
+pub fn render_index(posts: &[Post], tag: Option<&str>) -> Result<String> {
+    environment()?
+        .get_template("index.html")?
+        .render(context! {
+            site_name => SITE_NAME,
+            posts => posts,
+            tag => tag,
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to render post index: {}", e))
+}
+
+/// Render a single post, the Markdown body is already HTML
+pub fn render_post(post: &Post) -> Result<String> {
+    environment()?
+        .get_template("post.html")?
+        .render(context! {
+            site_name => SITE_NAME,
+            post => post,
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to render post: {}", e))
+}
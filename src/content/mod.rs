@@ -0,0 +1,456 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Public Markdown posts (blog / project pages)
+// Posts are .md files with a front matter block, read from a local directory or the S3 bucket.
+// Visibility comes from the draft flag unless an admin has published or unpublished the post.
+
+pub mod feed;
+pub mod html;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use pulldown_cmark::{html as markdown_html, Options, Parser};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::entities::{post_publication, PostPublication};
+use crate::s3::S3Service;
+
+const DEFAULT_CONTENT_DIR: &str = "content/posts";
+const DEFAULT_CONTENT_S3_PREFIX: &str = "_content/posts";
+const DEFAULT_CACHE_SECONDS: u64 = 60;
+
+/// Where post files are read from
+#[derive(Debug, Clone)]
+pub enum ContentSource {
+    /// Local directory of .md files
+    Directory(PathBuf),
+    /// Objects under a prefix in the documents bucket
+    Storage { prefix: String },
+}
+
+impl ContentSource {
+    /// CONTENT_SOURCE=s3 reads from CONTENT_S3_PREFIX, otherwise CONTENT_DIR is used
+    pub fn from_env() -> Self {
+        match env::var("CONTENT_SOURCE").as_deref() {
+            Ok("s3") => ContentSource::Storage {
+                prefix: storage_prefix(),
+            },
+            _ => ContentSource::Directory(PathBuf::from(
+                env::var("CONTENT_DIR").unwrap_or_else(|_| DEFAULT_CONTENT_DIR.to_string()),
+            )),
+        }
+    }
+}
+
+fn storage_prefix() -> String {
+    env::var("CONTENT_S3_PREFIX")
+        .ok()
+        .map(|p| p.trim_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| DEFAULT_CONTENT_S3_PREFIX.to_string())
+}
+
+/// Top-level bucket prefix holding posts, which is not an access code's document folder
+pub fn reserved_storage_prefix() -> String {
+    storage_prefix()
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Front matter fields, all optional
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub date: Option<NaiveDate>,
+    pub updated: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub draft: bool,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Post {
+    pub slug: String,
+    pub title: String,
+    pub date: Option<NaiveDate>,
+    pub updated: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub draft: bool,
+    pub summary: Option<String>,
+    pub body_html: String,
+}
+
+impl Post {
+    /// When the post last changed: the `updated` front matter, else midnight UTC on its date
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated.or_else(|| {
+            self.date
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })
+    }
+}
+
+/// Split a leading `---` delimited front matter block from the Markdown body
+/// Supports `key: value` lines, quoted values and `[a, b]` or comma separated tag lists
+pub fn parse_front_matter(source: &str) -> (FrontMatter, &str) {
+    let mut front_matter = FrontMatter::default();
+
+    let Some(rest) = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))
+    else {
+        return (front_matter, source);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (front_matter, source);
+    };
+
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+    for line in rest[..end].lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = unquote(value.trim());
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "title" => front_matter.title = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "date" => front_matter.date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok(),
+            "updated" => front_matter.updated = parse_timestamp(value),
+            "draft" => front_matter.draft = value.eq_ignore_ascii_case("true"),
+            "summary" | "description" => {
+                front_matter.summary = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "tags" => {
+                front_matter.tags = value
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(',')
+                    .filter_map(|tag| normalize_tag(unquote(tag.trim())))
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+
+    (front_matter, body)
+}
+
+/// Accepts an RFC 3339 timestamp or a plain date, taken as midnight UTC
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Tags are lower case words joined by hyphens so they can be used in URLs
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag: String = tag
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-");
+    let valid = !tag.is_empty()
+        && tag.len() <= 64
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(tag)
+}
+
+/// Slugs come from file names, restricted to URL safe characters
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= 128
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Parse a Markdown post, the title defaults to the slug when front matter has none
+pub fn parse_post(slug: &str, source: &str) -> Post {
+    let (front_matter, body) = parse_front_matter(source);
+
+    let parser = Parser::new_ext(
+        body,
+        Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS,
+    );
+    let mut body_html = String::with_capacity(body.len() * 3 / 2);
+    markdown_html::push_html(&mut body_html, parser);
+
+    Post {
+        slug: slug.to_string(),
+        title: front_matter.title.unwrap_or_else(|| slug.to_string()),
+        date: front_matter.date,
+        updated: front_matter.updated,
+        tags: front_matter.tags,
+        draft: front_matter.draft,
+        summary: front_matter.summary,
+        body_html,
+    }
+}
+
+/// Newest first, undated posts last, then by title
+fn sort_posts(posts: &mut [Post]) {
+    posts.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.title.cmp(&b.title)));
+}
+
+/// Admin view of a post and its publication state
+#[derive(Debug, Clone, Serialize)]
+pub struct PostStatus {
+    pub slug: String,
+    pub title: String,
+    pub date: Option<NaiveDate>,
+    pub tags: Vec<String>,
+    pub draft: bool,
+    pub published: bool,
+    /// Set when an admin published or unpublished the post, overriding the draft flag
+    pub published_override: Option<bool>,
+}
+
+type PostCache = Option<(Instant, Arc<Vec<Post>>)>;
+
+#[derive(Clone)]
+pub struct ContentService {
+    source: ContentSource,
+    s3: S3Service,
+    db: DatabaseConnection,
+    cache: Arc<RwLock<PostCache>>,
+    cache_ttl: Duration,
+}
+
+impl ContentService {
+    pub fn new(source: ContentSource, s3: S3Service, db: DatabaseConnection) -> Self {
+        let cache_seconds = env::var("CONTENT_CACHE_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_CACHE_SECONDS);
+
+        Self {
+            source,
+            s3,
+            db,
+            cache: Arc::new(RwLock::new(None)),
+            cache_ttl: Duration::from_secs(cache_seconds),
+        }
+    }
+
+    pub fn from_env(s3: S3Service, db: DatabaseConnection) -> Self {
+        Self::new(ContentSource::from_env(), s3, db)
+    }
+
+    /// Override how long parsed posts are cached, zero re-reads on every request
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// All posts including drafts, sorted newest first
+    pub async fn all_posts(&self) -> Result<Arc<Vec<Post>>> {
+        if let Ok(cache) = self.cache.read() {
+            if let Some((loaded_at, posts)) = cache.as_ref() {
+                if loaded_at.elapsed() < self.cache_ttl {
+                    return Ok(posts.clone());
+                }
+            }
+        }
+
+        let mut posts = self.load_posts().await?;
+        sort_posts(&mut posts);
+        let posts = Arc::new(posts);
+
+        if let Ok(mut cache) = self.cache.write() {
+            *cache = Some((Instant::now(), posts.clone()));
+        }
+        Ok(posts)
+    }
+
+    /// Posts visible to the public, optionally limited to a tag
+    pub async fn published_posts(&self, tag: Option<&str>) -> Result<Vec<Post>> {
+        let overrides = self.publication_overrides().await?;
+        Ok(self
+            .all_posts()
+            .await?
+            .iter()
+            .filter(|post| is_published(post, &overrides))
+            .filter(|post| tag.is_none_or(|tag| post.tags.iter().any(|t| t == tag)))
+            .cloned()
+            .collect())
+    }
+
+    /// A single public post
+    pub async fn published_post(&self, slug: &str) -> Result<Option<Post>> {
+        let overrides = self.publication_overrides().await?;
+        Ok(self
+            .all_posts()
+            .await?
+            .iter()
+            .find(|post| post.slug == slug && is_published(post, &overrides))
+            .cloned())
+    }
+
+    /// Every post with its publication state, for the admin
+    pub async fn post_statuses(&self) -> Result<Vec<PostStatus>> {
+        let overrides = self.publication_overrides().await?;
+        Ok(self
+            .all_posts()
+            .await?
+            .iter()
+            .map(|post| PostStatus {
+                slug: post.slug.clone(),
+                title: post.title.clone(),
+                date: post.date,
+                tags: post.tags.clone(),
+                draft: post.draft,
+                published: is_published(post, &overrides),
+                published_override: overrides.get(&post.slug).copied(),
+            })
+            .collect())
+    }
+
+    /// Publish or unpublish a post, returns false if no post has the slug
+    pub async fn set_published(
+        &self,
+        slug: &str,
+        published: bool,
+        updated_by: Uuid,
+    ) -> Result<bool> {
+        if !self.all_posts().await?.iter().any(|post| post.slug == slug) {
+            return Ok(false);
+        }
+
+        let existing = PostPublication::find()
+            .filter(post_publication::Column::Slug.eq(slug))
+            .one(&self.db)
+            .await?;
+
+        match existing {
+            Some(publication) => {
+                let mut active: post_publication::ActiveModel = publication.into();
+                active.published = Set(published);
+                active.updated_by = Set(Some(updated_by));
+                active.updated_at = Set(Utc::now().into());
+                active.update(&self.db).await?;
+            }
+            None => {
+                let publication = post_publication::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    slug: Set(slug.to_string()),
+                    published: Set(published),
+                    updated_by: Set(Some(updated_by)),
+                    updated_at: Set(Utc::now().into()),
+                };
+                publication.insert(&self.db).await?;
+            }
+        }
+
+        tracing::info!(
+            "Post {} {}",
+            slug,
+            if published {
+                "published"
+            } else {
+                "unpublished"
+            }
+        );
+        Ok(true)
+    }
+
+    async fn publication_overrides(&self) -> Result<HashMap<String, bool>> {
+        Ok(PostPublication::find()
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|p| (p.slug, p.published))
+            .collect())
+    }
+
+    async fn load_posts(&self) -> Result<Vec<Post>> {
+        let mut posts = Vec::new();
+
+        match &self.source {
+            ContentSource::Directory(dir) => {
+                let mut entries = match tokio::fs::read_dir(dir).await {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        tracing::debug!("Content directory {} not found", dir.display());
+                        return Ok(posts);
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+
+                while let Some(entry) = entries.next_entry().await? {
+                    let file_name = entry.file_name().to_string_lossy().to_string();
+                    let Some(slug) = markdown_slug(&file_name) else {
+                        continue;
+                    };
+                    let source = tokio::fs::read_to_string(entry.path()).await?;
+                    posts.push(parse_post(slug, &source));
+                }
+            }
+            ContentSource::Storage { prefix } => {
+                for file_name in self.s3.list_filenames(prefix).await? {
+                    let Some(slug) = markdown_slug(&file_name) else {
+                        continue;
+                    };
+                    let bytes = self.s3.get_file(prefix, &file_name).await?;
+                    posts.push(parse_post(slug, &String::from_utf8_lossy(&bytes)));
+                }
+            }
+        }
+
+        Ok(posts)
+    }
+}
+
+fn markdown_slug(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(".md")
+        .filter(|slug| is_valid_slug(slug))
+}
+
+fn is_published(post: &Post, overrides: &HashMap<String, bool>) -> bool {
+    overrides.get(&post.slug).copied().unwrap_or(!post.draft)
+}
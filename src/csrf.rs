@@ -0,0 +1,88 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Double-submit cookie CSRF protection for public forms
+// The token is set as a cookie and embedded in the form, a POST is accepted only if both match.
+// A cross-site attacker can't read the cookie, so they can't forge the form field.
+
+use axum::http::{header, HeaderMap};
+use rand::Rng;
+use std::env;
+
+pub const CSRF_COOKIE_NAME: &str = "ps_csrf";
+
+/// Hidden form field carrying the token
+pub const CSRF_FIELD_NAME: &str = "csrf_token";
+
+/// Generate a new random token
+pub fn generate_token() -> String {
+    let token_bytes: [u8; 32] = rand::rng().random();
+    hex::encode(token_bytes)
+}
+
+/// Token from the request cookie, if present and well formed
+pub fn token_from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == CSRF_COOKIE_NAME)
+        .map(|(_, value)| value.to_string())
+        .filter(|token| is_well_formed(token))
+}
+
+/// Reuse the existing cookie token so forms open in several tabs all stay valid
+pub fn token_for_form(headers: &HeaderMap) -> String {
+    token_from_headers(headers).unwrap_or_else(generate_token)
+}
+
+/// Check a submitted form token against the cookie token
+pub fn verify(headers: &HeaderMap, submitted: &str) -> bool {
+    match token_from_headers(headers) {
+        Some(cookie_token) => constant_time_eq(cookie_token.as_bytes(), submitted.as_bytes()),
+        None => false,
+    }
+}
+
+/// Set-Cookie value for a token, scoped to the form handler
+/// Secure is added when the site is served over https (SITE_URL)
+pub fn cookie_header(token: &str, path: &str) -> String {
+    let secure = env::var("SITE_URL")
+        .map(|url| url.starts_with("https://"))
+        .unwrap_or(false);
+
+    format!(
+        "{}={}; Path={}; HttpOnly; SameSite=Strict{}",
+        CSRF_COOKIE_NAME,
+        token,
+        path,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+fn is_well_formed(token: &str) -> bool {
+    token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
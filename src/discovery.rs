@@ -0,0 +1,204 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Files for crawlers and security researchers: robots.txt, sitemap.xml and security.txt
+// Code-gated and admin pages are kept out of search engines, only public pages are listed.
+
+use crate::content::{self, feed::xml_escape, Post};
+use crate::settings::SettingsService;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Contact for security.txt (category "system"), e.g. `security@example.com` or a URL.
+/// Several contacts can be separated by commas, bare email addresses get a `mailto:` prefix
+pub const SECURITY_CONTACT_SETTING_KEY: &str = "security_txt_contact";
+
+/// Expiry for security.txt (category "system") as a date or RFC 3339 timestamp
+pub const SECURITY_EXPIRES_SETTING_KEY: &str = "security_txt_expires";
+
+/// Paths crawlers are asked to stay out of
+const DISALLOWED_PATHS: &[&str] = &["/access", "/resume", "/admin", "/api/"];
+
+fn base_url(site_url: Option<&str>) -> &str {
+    site_url.unwrap_or_default().trim_end_matches('/')
+}
+
+pub fn render_robots(site_url: Option<&str>) -> String {
+    let mut robots = String::from("User-agent: *\n");
+    for path in DISALLOWED_PATHS {
+        let _ = writeln!(robots, "Disallow: {}", path);
+    }
+
+    let base = base_url(site_url);
+    if !base.is_empty() {
+        let _ = writeln!(robots, "\nSitemap: {}/sitemap.xml", base);
+    }
+    robots
+}
+
+/// Sitemap of the landing page, blog index, published posts and their tag pages
+/// `posts` should already be filtered to published posts
+pub fn render_sitemap(site_url: Option<&str>, posts: &[Post]) -> String {
+    let base = base_url(site_url);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+
+    let newest = posts.iter().filter_map(Post::updated_at).max();
+    push_url(&mut xml, &format!("{}/", base), None);
+    push_url(&mut xml, &format!("{}/blog", base), newest);
+
+    for post in posts {
+        push_url(
+            &mut xml,
+            &format!("{}/blog/{}", base, post.slug),
+            post.updated_at(),
+        );
+    }
+
+    let tags: BTreeSet<&str> = posts
+        .iter()
+        .flat_map(|post| post.tags.iter().map(String::as_str))
+        .collect();
+    for tag in tags {
+        let updated = posts
+            .iter()
+            .filter(|post| post.tags.iter().any(|t| t == tag))
+            .filter_map(Post::updated_at)
+            .max();
+        push_url(
+            &mut xml,
+            &format!("{}/blog/tags/{}", base, encode_path_segment(tag)),
+            updated,
+        );
+    }
+
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn push_url(xml: &mut String, loc: &str, last_modified: Option<DateTime<Utc>>) {
+    xml.push_str("  <url>\n");
+    let _ = writeln!(xml, "    <loc>{}</loc>", xml_escape(loc));
+    if let Some(last_modified) = last_modified {
+        let _ = writeln!(
+            xml,
+            "    <lastmod>{}</lastmod>",
+            last_modified.format("%Y-%m-%d")
+        );
+    }
+    xml.push_str("  </url>\n");
+}
+
+/// Tags are already restricted to URL safe words, only non-ASCII letters need encoding
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// RFC 9116 security.txt contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityTxt {
+    pub contacts: Vec<String>,
+    pub expires: DateTime<Utc>,
+    pub canonical: Option<String>,
+}
+
+impl SecurityTxt {
+    /// Both a contact and a valid expiry are required, otherwise there is no security.txt
+    pub fn parse(contact: &str, expires: &str, site_url: Option<&str>) -> Option<Self> {
+        let contacts: Vec<String> = contact
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                if c.contains(':') {
+                    c.to_string()
+                } else {
+                    format!("mailto:{}", c)
+                }
+            })
+            .collect();
+        if contacts.is_empty() {
+            return None;
+        }
+
+        let expires = content::parse_timestamp(expires.trim())?;
+        let base = base_url(site_url);
+        let canonical = (!base.is_empty()).then(|| format!("{}/.well-known/security.txt", base));
+
+        Some(Self {
+            contacts,
+            expires,
+            canonical,
+        })
+    }
+
+    /// Read the contact and expiry from system settings
+    pub async fn load(settings: &SettingsService, site_url: Option<&str>) -> Result<Option<Self>> {
+        let contact = settings
+            .get(SECURITY_CONTACT_SETTING_KEY, Some("system"), None)
+            .await?;
+        let expires = settings
+            .get(SECURITY_EXPIRES_SETTING_KEY, Some("system"), None)
+            .await?;
+
+        let (Some(contact), Some(expires)) = (contact, expires) else {
+            return Ok(None);
+        };
+
+        let security_txt = Self::parse(&contact, &expires, site_url);
+        match &security_txt {
+            None => tracing::warn!(
+                "security.txt settings are invalid, {} must be a date or RFC 3339 timestamp",
+                SECURITY_EXPIRES_SETTING_KEY
+            ),
+            Some(txt) if txt.expires < Utc::now() => {
+                tracing::warn!("security.txt expired on {}", txt.expires)
+            }
+            Some(_) => {}
+        }
+        Ok(security_txt)
+    }
+
+    pub fn render(&self) -> String {
+        let mut txt = String::new();
+        for contact in &self.contacts {
+            let _ = writeln!(txt, "Contact: {}", contact);
+        }
+        let _ = writeln!(
+            txt,
+            "Expires: {}",
+            self.expires
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        );
+        if let Some(canonical) = &self.canonical {
+            let _ = writeln!(txt, "Canonical: {}", canonical);
+        }
+        txt
+    }
+}
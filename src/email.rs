@@ -15,11 +15,13 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::aws::AwsEndpointConfig;
 use anyhow::Result;
 use aws_sdk_sesv2::{
     types::{Body, Content, Destination, EmailContent, Message},
     Client as SesClient,
 };
+use chrono::{DateTime, Utc};
 use std::env;
 
 #[derive(Clone)]
@@ -31,14 +33,9 @@ pub struct EmailService {
 
 impl EmailService {
     pub async fn new() -> Result<Self> {
-        let mut config_loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
-
-        // Override region if AWS_REGION is set in environment
-        if let Ok(region) = env::var("AWS_REGION") {
-            config_loader = config_loader.region(aws_sdk_sesv2::config::Region::new(region));
-        }
-
-        let config = config_loader.load().await;
+        // SES_REGION / SES_ENDPOINT_URL override AWS_REGION and the default endpoint,
+        // e.g. to send through localstack in development
+        let config = AwsEndpointConfig::from_env("SES").load_sdk_config().await;
         let client = SesClient::new(&config);
 
         let from_email = env::var("AWS_SES_FROM_EMAIL")
@@ -115,28 +112,235 @@ If you didn't request this verification email, you can safely ignore it.
             verification_url
         );
 
+        self.send(to_email, None, subject, Some(html_body), text_body)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send verification email: {}", e))?;
+
+        tracing::info!("Verification email sent to {}", to_email);
+
+        Ok(())
+    }
+
+    pub async fn send_password_reset_email(&self, to_email: &str, reset_token: &str) -> Result<()> {
+        let reset_url = format!(
+            "{}/admin/reset-password?token={}",
+            self.site_url, reset_token
+        );
+
+        let subject = "Reset Your Admin Password";
+        let html_body = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <title>Reset Your Password</title>
+</head>
+<body style="font-family: Arial, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <div style="background-color: #f4f4f4; border-radius: 5px; padding: 20px; margin-bottom: 20px;">
+        <h1 style="color: #2c3e50; margin-top: 0;">Password Reset</h1>
+        <p>A password reset was requested for your Cave Bat Software admin account.</p>
+    </div>
+
+    <div style="background-color: white; border: 1px solid #ddd; border-radius: 5px; padding: 20px; margin-bottom: 20px;">
+        <p>Click the button below to choose a new password:</p>
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{}"
+               style="background-color: #3498db; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; display: inline-block; font-weight: bold;">
+                Reset Password
+            </a>
+        </div>
+        <p style="color: #666; font-size: 14px;">Or copy and paste this link into your browser:</p>
+        <p style="word-break: break-all; color: #3498db; font-size: 14px;">{}</p>
+    </div>
+
+    <div style="color: #666; font-size: 12px; text-align: center;">
+        <p>This link will expire in 1 hour and can only be used once. Resetting signs out all existing sessions.</p>
+        <p>If you didn't request a password reset, you can safely ignore this email.</p>
+    </div>
+</body>
+</html>
+"#,
+            reset_url, reset_url
+        );
+
+        let text_body = format!(
+            r#"
+Password Reset
+
+A password reset was requested for your Cave Bat Software admin account.
+
+Reset Link: {}
+
+This link will expire in 1 hour and can only be used once. Resetting signs out all existing sessions.
+
+If you didn't request a password reset, you can safely ignore this email.
+"#,
+            reset_url
+        );
+
+        self.send(to_email, None, subject, Some(html_body), text_body)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send password reset email: {}", e))?;
+
+        tracing::info!("Password reset email sent to {}", to_email);
+
+        Ok(())
+    }
+
+    pub async fn send_invite_email(
+        &self,
+        to_email: &str,
+        invited_by: &str,
+        invite_token: &str,
+    ) -> Result<()> {
+        let invite_url = format!("{}/admin/register?invite={}", self.site_url, invite_token);
+
+        let subject = "You're Invited to the Cave Bat Software Admin";
+        let html_body = format!(
+            r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <title>Admin Invitation</title>
+</head>
+<body style="font-family: Arial, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <div style="background-color: #f4f4f4; border-radius: 5px; padding: 20px; margin-bottom: 20px;">
+        <h1 style="color: #2c3e50; margin-top: 0;">You're Invited</h1>
+        <p>{} has invited you to become an admin of Cave Bat Software.</p>
+    </div>
+
+    <div style="background-color: white; border: 1px solid #ddd; border-radius: 5px; padding: 20px; margin-bottom: 20px;">
+        <p>Click the button below to create your account:</p>
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{}"
+               style="background-color: #3498db; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; display: inline-block; font-weight: bold;">
+                Accept Invitation
+            </a>
+        </div>
+        <p style="color: #666; font-size: 14px;">Or copy and paste this link into your browser:</p>
+        <p style="word-break: break-all; color: #3498db; font-size: 14px;">{}</p>
+    </div>
+
+    <div style="color: #666; font-size: 12px; text-align: center;">
+        <p>This invitation will expire in 7 days and can only be used once.</p>
+        <p>If you weren't expecting it, you can safely ignore this email.</p>
+    </div>
+</body>
+</html>
+"#,
+            invited_by, invite_url, invite_url
+        );
+
+        let text_body = format!(
+            r#"
+You're Invited
+
+{} has invited you to become an admin of Cave Bat Software.
+
+Accept Invitation: {}
+
+This invitation will expire in 7 days and can only be used once.
+
+If you weren't expecting it, you can safely ignore this email.
+"#,
+            invited_by, invite_url
+        );
+
+        self.send(to_email, None, subject, Some(html_body), text_body)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send invite email: {}", e))?;
+
+        tracing::info!("Invite email sent to {}", to_email);
+
+        Ok(())
+    }
+
+    pub async fn send_account_locked_email(
+        &self,
+        to_email: &str,
+        locked_until: DateTime<Utc>,
+    ) -> Result<()> {
+        let reset_url = format!("{}/admin/forgot-password", self.site_url);
+        let until = locked_until.format("%Y-%m-%d %H:%M UTC");
+
+        let subject = "Admin Account Temporarily Locked";
+        let text_body = format!(
+            r#"
+Your Cave Bat Software admin account has been temporarily locked after several failed login attempts.
+
+You can try again after {}. Further failed attempts lock the account for longer.
+
+If this wasn't you, someone may be guessing your password. Resetting it also lifts the lock:
+{}
+"#,
+            until, reset_url
+        );
+
+        self.send(to_email, None, subject, None, text_body)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send account locked email: {}", e))?;
+
+        tracing::info!("Account locked email sent to {}", to_email);
+
+        Ok(())
+    }
+
+    /// Forward a contact form message to the site owner
+    /// Sent as plain text only so nothing the visitor typed is rendered as HTML,
+    /// replies go straight to the visitor
+    pub async fn send_contact_message(
+        &self,
+        to_email: &str,
+        name: &str,
+        reply_to: &str,
+        message: &str,
+    ) -> Result<()> {
+        let subject = format!("Contact form: {}", name);
+        let text_body = format!(
+            "New message from the contact form on {}\n\nName: {}\nEmail: {}\n\n{}\n",
+            self.site_url, name, reply_to, message
+        );
+
+        self.send(to_email, Some(reply_to), &subject, None, text_body)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send contact message: {}", e))?;
+
+        tracing::info!("Contact message forwarded to {}", to_email);
+
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        to_email: &str,
+        reply_to: Option<&str>,
+        subject: &str,
+        html_body: Option<String>,
+        text_body: String,
+    ) -> Result<()> {
         let destination = Destination::builder().to_addresses(to_email).build();
 
         let subject_content = Content::builder().data(subject).charset("UTF-8").build()?;
 
-        let html_content = Content::builder()
-            .data(html_body)
-            .charset("UTF-8")
-            .build()?;
-
         let text_content = Content::builder()
             .data(text_body)
             .charset("UTF-8")
             .build()?;
 
-        let body = Body::builder()
-            .html(html_content)
-            .text(text_content)
-            .build();
+        let mut body = Body::builder().text(text_content);
+        if let Some(html_body) = html_body {
+            let html_content = Content::builder()
+                .data(html_body)
+                .charset("UTF-8")
+                .build()?;
+            body = body.html(html_content);
+        }
 
         let message = Message::builder()
             .subject(subject_content)
-            .body(body)
+            .body(body.build())
             .build();
 
         let email_content = EmailContent::builder().simple(message).build();
@@ -145,12 +349,10 @@ If you didn't request this verification email, you can safely ignore it.
             .send_email()
             .from_email_address(&self.from_email)
             .destination(destination)
+            .set_reply_to_addresses(reply_to.map(|r| vec![r.to_string()]))
             .content(email_content)
             .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to send verification email: {}", e))?;
-
-        tracing::info!("Verification email sent to {}", to_email);
+            .await?;
 
         Ok(())
     }
@@ -0,0 +1,307 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Opt-in reading engagement tracking
+// A small script injected into served resume pages reports visible time and scroll depth
+// back to /access/{code}/beacon so the admin can see whether a page was actually read
+
+use anyhow::Result;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::{engagement_event, EngagementEvent};
+
+/// Setting key enabling beacons, either globally (category "system")
+/// or per access code (category "access_code", entity_id = code id)
+pub const ENGAGEMENT_TRACKING_SETTING_KEY: &str = "engagement_tracking";
+
+/// Beacons are tiny JSON documents, anything larger is rejected unparsed
+pub const MAX_BEACON_BYTES: usize = 1024;
+
+/// Longest time on page accepted from a single view (12 hours)
+const MAX_TIME_ON_PAGE_MS: i64 = 12 * 60 * 60 * 1000;
+
+/// Scroll depth (percent) counted as reading the whole page
+const READ_THROUGH_SCROLL_DEPTH: i32 = 90;
+
+/// Number of individual views returned with the analytics summary
+const RECENT_VIEWS_LIMIT: usize = 20;
+
+/// User agent fragments of crawlers, link previewers and headless browsers
+const BOT_USER_AGENT_MARKERS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "preview",
+    "headless",
+    "lighthouse",
+    "phantomjs",
+    "facebookexternalhit",
+    "embedly",
+    "curl/",
+    "wget/",
+    "python-requests",
+    "go-http-client",
+];
+
+/// Reports visible time on page and the deepest scroll position once per page hide
+/// The beacon URL is relative to the page, so /access and /resume both work
+const BEACON_SCRIPT: &str = r#"<script>
+(function () {
+  if (!navigator.sendBeacon) return;
+  var endpoint = location.pathname.replace(/\/+$/, "") + "/beacon";
+  var viewId = window.crypto && crypto.randomUUID
+    ? crypto.randomUUID()
+    : Date.now().toString(36) + Math.random().toString(36).slice(2);
+  var visibleMs = 0;
+  var visibleSince = document.visibilityState === "visible" ? Date.now() : null;
+  var maxDepth = 0;
+  var lastSent = -1;
+  function trackDepth() {
+    var doc = document.documentElement;
+    var scrollable = doc.scrollHeight - window.innerHeight;
+    var depth = scrollable <= 0 ? 100 : Math.round((window.scrollY / scrollable) * 100);
+    maxDepth = Math.max(maxDepth, Math.min(100, depth));
+  }
+  function send() {
+    var elapsed = visibleMs + (visibleSince ? Date.now() - visibleSince : 0);
+    if (elapsed === lastSent) return;
+    lastSent = elapsed;
+    navigator.sendBeacon(endpoint, JSON.stringify({
+      view_id: viewId,
+      time_on_page_ms: elapsed,
+      scroll_depth: maxDepth
+    }));
+  }
+  document.addEventListener("visibilitychange", function () {
+    if (document.visibilityState === "hidden") {
+      if (visibleSince) {
+        visibleMs += Date.now() - visibleSince;
+        visibleSince = null;
+      }
+      send();
+    } else {
+      visibleSince = Date.now();
+    }
+  });
+  window.addEventListener("pagehide", send);
+  window.addEventListener("scroll", trackDepth, { passive: true });
+  trackDepth();
+})();
+</script>
+"#;
+
+/// Insert the beacon script before the closing body tag, or append it if there is none
+pub fn inject_beacon_script(html: &str) -> String {
+    let insert_at = html
+        .to_ascii_lowercase()
+        .rfind("</body>")
+        .unwrap_or(html.len());
+
+    let mut output = String::with_capacity(html.len() + BEACON_SCRIPT.len());
+    output.push_str(&html[..insert_at]);
+    output.push_str(BEACON_SCRIPT);
+    output.push_str(&html[insert_at..]);
+    output
+}
+
+/// Crawlers and link previewers can execute scripts too, their beacons are dropped
+/// Requests without a user agent are treated as automated
+pub fn is_bot(user_agent: Option<&str>) -> bool {
+    let Some(user_agent) = user_agent.filter(|ua| !ua.trim().is_empty()) else {
+        return true;
+    };
+
+    let user_agent = user_agent.to_ascii_lowercase();
+    BOT_USER_AGENT_MARKERS
+        .iter()
+        .any(|marker| user_agent.contains(marker))
+}
+
+/// Beacon body posted by the injected script
+#[derive(Debug, Clone, Deserialize)]
+pub struct BeaconPayload {
+    pub view_id: String,
+    pub time_on_page_ms: i64,
+    pub scroll_depth: i32,
+}
+
+impl BeaconPayload {
+    /// Parse a beacon body, rejecting oversized bodies and malformed view ids
+    /// Numbers are clamped to sane ranges since the client is untrusted
+    pub fn parse(body: &[u8]) -> Option<Self> {
+        if body.len() > MAX_BEACON_BYTES {
+            return None;
+        }
+
+        let payload: BeaconPayload = serde_json::from_slice(body).ok()?;
+        let view_id_valid = !payload.view_id.is_empty()
+            && payload.view_id.len() <= 64
+            && payload
+                .view_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !view_id_valid {
+            return None;
+        }
+
+        Some(Self {
+            view_id: payload.view_id,
+            time_on_page_ms: payload.time_on_page_ms.clamp(0, MAX_TIME_ON_PAGE_MS),
+            scroll_depth: payload.scroll_depth.clamp(0, 100),
+        })
+    }
+}
+
+/// Engagement totals for a single access code
+#[derive(Debug, Clone, Serialize)]
+pub struct EngagementSummary {
+    pub tracked_views: usize,
+    pub average_time_on_page_seconds: f64,
+    pub average_scroll_depth: f64,
+    pub read_through_views: usize,
+    pub last_engaged_at: Option<String>,
+    pub recent_views: Vec<EngagementView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngagementView {
+    pub time_on_page_seconds: f64,
+    pub scroll_depth: i32,
+    pub user_agent: Option<String>,
+    pub viewed_at: String,
+}
+
+impl From<engagement_event::Model> for EngagementView {
+    fn from(model: engagement_event::Model) -> Self {
+        Self {
+            time_on_page_seconds: model.time_on_page_ms as f64 / 1000.0,
+            scroll_depth: model.scroll_depth,
+            user_agent: model.user_agent,
+            viewed_at: model.created_at.with_timezone(&Utc).to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EngagementService {
+    db: DatabaseConnection,
+}
+
+impl EngagementService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record a beacon for a page view
+    /// Repeat beacons from the same view (tab hidden then shown again) keep the highest values
+    pub async fn record_beacon(
+        &self,
+        access_code_id: Uuid,
+        payload: &BeaconPayload,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<()> {
+        let now = Utc::now();
+
+        let existing = EngagementEvent::find()
+            .filter(engagement_event::Column::ViewId.eq(&payload.view_id))
+            .one(&self.db)
+            .await?;
+
+        match existing {
+            Some(event) if event.access_code_id == access_code_id => {
+                let time_on_page_ms = event.time_on_page_ms.max(payload.time_on_page_ms);
+                let scroll_depth = event.scroll_depth.max(payload.scroll_depth);
+
+                let mut active: engagement_event::ActiveModel = event.into();
+                active.time_on_page_ms = Set(time_on_page_ms);
+                active.scroll_depth = Set(scroll_depth);
+                active.updated_at = Set(now.into());
+                active.update(&self.db).await?;
+            }
+            Some(_) => {
+                tracing::warn!(
+                    "Ignoring beacon reusing a view id from another access code: {}",
+                    payload.view_id
+                );
+            }
+            None => {
+                let event = engagement_event::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    access_code_id: Set(access_code_id),
+                    view_id: Set(payload.view_id.clone()),
+                    time_on_page_ms: Set(payload.time_on_page_ms),
+                    scroll_depth: Set(payload.scroll_depth),
+                    ip_address: Set(ip_address),
+                    user_agent: Set(user_agent),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                };
+                event.insert(&self.db).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Summarize engagement for an access code, most recent views first
+    pub async fn summary(&self, access_code_id: Uuid) -> Result<EngagementSummary> {
+        let events = EngagementEvent::find()
+            .filter(engagement_event::Column::AccessCodeId.eq(access_code_id))
+            .order_by_desc(engagement_event::Column::UpdatedAt)
+            .all(&self.db)
+            .await?;
+
+        let tracked_views = events.len();
+        let (average_time_on_page_seconds, average_scroll_depth) = if tracked_views == 0 {
+            (0.0, 0.0)
+        } else {
+            let total_ms: i64 = events.iter().map(|e| e.time_on_page_ms).sum();
+            let total_depth: i64 = events.iter().map(|e| e.scroll_depth as i64).sum();
+            (
+                total_ms as f64 / 1000.0 / tracked_views as f64,
+                total_depth as f64 / tracked_views as f64,
+            )
+        };
+        let read_through_views = events
+            .iter()
+            .filter(|e| e.scroll_depth >= READ_THROUGH_SCROLL_DEPTH)
+            .count();
+        let last_engaged_at = events
+            .first()
+            .map(|e| e.updated_at.with_timezone(&Utc).to_rfc3339());
+
+        Ok(EngagementSummary {
+            tracked_views,
+            average_time_on_page_seconds,
+            average_scroll_depth,
+            read_through_views,
+            last_engaged_at,
+            recent_views: events
+                .into_iter()
+                .take(RECENT_VIEWS_LIMIT)
+                .map(Into::into)
+                .collect(),
+        })
+    }
+}
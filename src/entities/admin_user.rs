@@ -29,6 +29,18 @@ pub struct Model {
     pub email_verified: bool,
     pub verification_token: Option<String>,
     pub verification_token_expires_at: Option<DateTimeWithTimeZone>,
+    pub totp_secret: Option<String>, // Base32, set during enrollment before totp_enabled
+    pub totp_enabled: bool,
+    pub totp_last_step: Option<i64>, // Last accepted time step, codes can't be replayed
+    #[sea_orm(column_type = "Text", nullable)]
+    pub recovery_codes: Option<String>, // JSON array of SHA-256 hashes of unused codes
+    pub password_reset_token_hash: Option<String>, // SHA-256 of the emailed token, cleared once used
+    pub password_reset_expires_at: Option<DateTimeWithTimeZone>,
+    pub failed_login_attempts: i32, // Consecutive failures, reset by a successful login
+    pub last_failed_login_at: Option<DateTimeWithTimeZone>,
+    pub locked_until: Option<DateTimeWithTimeZone>,
+    pub role: String,   // owner, editor or viewer, see admin::roles
+    pub disabled: bool, // Can't log in and existing sessions end, set by an owner
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
@@ -17,10 +17,24 @@
 
 pub mod access_code;
 pub mod access_log;
+pub mod admin_api_token;
+pub mod admin_invite;
+pub mod admin_passkey;
+pub mod admin_session;
 pub mod admin_user;
+pub mod contact_message;
+pub mod engagement_event;
+pub mod post_publication;
 pub mod setting;
 
 pub use access_code::Entity as AccessCode;
 pub use access_log::Entity as AccessLog;
+pub use admin_api_token::Entity as AdminApiToken;
+pub use admin_invite::Entity as AdminInvite;
+pub use admin_passkey::Entity as AdminPasskey;
+pub use admin_session::Entity as AdminSession;
 pub use admin_user::Entity as AdminUser;
+pub use contact_message::Entity as ContactMessage;
+pub use engagement_event::Entity as EngagementEvent;
+pub use post_publication::Entity as PostPublication;
 pub use setting::Entity as Setting;
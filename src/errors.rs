@@ -39,8 +39,17 @@ pub enum AppError {
     #[error("Authentication error: {0}")]
     AuthError(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Configuration error: {0}")]
     Configuration(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
 }
 
 #[derive(Serialize)]
@@ -70,6 +79,14 @@ impl IntoResponse for AppError {
                 tracing::warn!("Authentication error: {}", msg);
                 (StatusCode::UNAUTHORIZED, msg)
             }
+            AppError::Forbidden(msg) => {
+                tracing::warn!("Forbidden: {}", msg);
+                (StatusCode::FORBIDDEN, msg)
+            }
+            AppError::Conflict(msg) => {
+                tracing::warn!("Conflict: {}", msg);
+                (StatusCode::CONFLICT, msg)
+            }
             AppError::Configuration(msg) => {
                 tracing::error!("Configuration error: {}", msg);
                 (
@@ -77,6 +94,13 @@ impl IntoResponse for AppError {
                     "Server configuration error".to_string(),
                 )
             }
+            AppError::Internal(msg) => {
+                tracing::error!("Internal error: {}", msg);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error".to_string(),
+                )
+            }
         };
 
         (
@@ -20,15 +20,26 @@
 
 pub mod admin;
 pub mod app;
+pub mod aws;
+pub mod cleanup;
+pub mod contact;
+pub mod content;
+pub mod csrf;
 pub mod database;
+pub mod discovery;
 pub mod email;
+pub mod engagement;
 pub mod entities;
 pub mod errors;
+pub mod locale;
 pub mod middleware;
 pub mod migration;
+pub mod resume;
 pub mod s3;
 pub mod security;
 pub mod settings;
+pub mod templates;
+pub mod watermark;
 
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,165 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Locale negotiation for the landing page and per-code documents
+// Localized variants sit next to the base file with the language before the extension,
+// e.g. landing.de.html or {code}/index.fr-ca.html. The unsuffixed file is in the default locale.
+
+use std::cmp::Ordering;
+
+/// System setting naming the language of unsuffixed files and the fallback locale
+pub const DEFAULT_LOCALE_SETTING_KEY: &str = "default_locale";
+
+pub const FALLBACK_DEFAULT_LOCALE: &str = "en";
+
+/// Maximum number of Accept-Language entries considered
+const MAX_ACCEPT_LANGUAGE_ENTRIES: usize = 16;
+
+/// Normalize a language tag to lower case with hyphens, rejecting anything that isn't tag shaped
+/// so it can safely be used in file names
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-").to_ascii_lowercase();
+    let valid = !tag.is_empty()
+        && tag.len() <= 35
+        && tag.split('-').all(|part| {
+            !part.is_empty() && part.len() <= 8 && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    valid.then_some(tag)
+}
+
+/// Parse an Accept-Language header into tags ordered by preference
+/// Wildcards and q=0 entries are dropped, equal weights keep header order
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut entries: Vec<(String, f32)> = header
+        .split(',')
+        .take(MAX_ACCEPT_LANGUAGE_ENTRIES)
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = normalize_tag(parts.next()?)?;
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    entries.into_iter().map(|(tag, _)| tag).collect()
+}
+
+fn primary_subtag(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+/// Locales requested for a single request, most preferred first
+#[derive(Debug, Clone)]
+pub struct LocalePreference {
+    requested: Vec<String>,
+    default_locale: String,
+}
+
+impl LocalePreference {
+    /// Build from the ?lang= override, the Accept-Language header and the default_locale setting
+    pub fn new(
+        query_lang: Option<&str>,
+        accept_language: Option<&str>,
+        default_locale: Option<&str>,
+    ) -> Self {
+        let mut requested: Vec<String> = query_lang.and_then(normalize_tag).into_iter().collect();
+        requested.extend(
+            accept_language
+                .map(parse_accept_language)
+                .unwrap_or_default(),
+        );
+
+        Self {
+            requested,
+            default_locale: default_locale
+                .and_then(normalize_tag)
+                .unwrap_or_else(|| FALLBACK_DEFAULT_LOCALE.to_string()),
+        }
+    }
+
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Pick the best available variant locale, None means the unsuffixed default file
+    /// Exact matches win, then a shared primary language (de-at -> de, en -> en-gb)
+    pub fn choose(&self, available: &[String]) -> Option<String> {
+        let candidates = self
+            .requested
+            .iter()
+            .chain(std::iter::once(&self.default_locale));
+
+        for candidate in candidates {
+            if let Some(exact) = available.iter().find(|a| *a == candidate) {
+                return Some(exact.clone());
+            }
+            if primary_subtag(candidate) == primary_subtag(&self.default_locale) {
+                return None;
+            }
+            if let Some(partial) = available
+                .iter()
+                .find(|a| primary_subtag(a) == primary_subtag(candidate))
+            {
+                return Some(partial.clone());
+            }
+        }
+
+        None
+    }
+
+    /// Choose between a base file ({stem}.{ext}) and its variants in a list of file names
+    /// Returns the file to serve and its Content-Language
+    pub fn select_file(&self, files: &[String], stem: &str, ext: &str) -> Option<(String, String)> {
+        let base = format!("{}.{}", stem, ext);
+        let has_base = files.contains(&base);
+        let mut available = variant_locales(files, stem, ext);
+        available.sort();
+
+        match self.choose(&available) {
+            Some(locale) => Some((variant_filename(stem, ext, &locale), locale)),
+            None if has_base => Some((base, self.default_locale.clone())),
+            // No default file, serve whichever variant exists
+            None => available
+                .into_iter()
+                .next()
+                .map(|locale| (variant_filename(stem, ext, &locale), locale)),
+        }
+    }
+}
+
+/// File name of a locale variant, e.g. ("index", "html", "de") -> index.de.html
+pub fn variant_filename(stem: &str, ext: &str, locale: &str) -> String {
+    format!("{}.{}.{}", stem, locale, ext)
+}
+
+/// Locales of the {stem}.{locale}.{ext} variants among a list of file names
+pub fn variant_locales(files: &[String], stem: &str, ext: &str) -> Vec<String> {
+    files
+        .iter()
+        .filter_map(|file| {
+            file.strip_prefix(stem)?
+                .strip_prefix('.')?
+                .strip_suffix(ext)?
+                .strip_suffix('.')
+        })
+        .filter_map(|locale| normalize_tag(locale).filter(|tag| tag == locale))
+        .collect()
+}
@@ -16,115 +16,773 @@
  */
 
 use axum::{
-    extract::Path,
-    http::{header, StatusCode},
+    body::Bytes,
+    extract::{Extension, Form, Path, Query},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     middleware::{from_fn, from_fn_with_state},
-    response::{Html, IntoResponse},
-    routing::get,
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
     Router,
 };
 use axum_login::AuthManagerLayerBuilder;
-use std::{env, sync::Arc};
+use minijinja::context;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::Arc;
 use time::Duration as TimeDuration;
 use tower::ServiceBuilder;
 use tower_http::{services::ServeDir, trace::TraceLayer};
 use tower_sessions::{Expiry, SessionManagerLayer};
 use tower_sessions_sqlx_store::PostgresStore;
 
-mod admin;
-mod app;
-mod database;
-mod email;
-mod entities;
-mod errors;
-mod middleware;
-mod migration;
-mod s3;
-mod security;
-mod settings;
-
-use self::middleware::{
-    access_log_middleware, rate_limit_middleware, require_admin_auth, security_middleware,
+use personal_site::admin::access_codes::ACCESS_CODE_SETTINGS_CATEGORY;
+use personal_site::cleanup::DocumentCleanupService;
+use personal_site::contact::ContactSubmission;
+use personal_site::content::feed;
+use personal_site::discovery::{self, SecurityTxt};
+use personal_site::engagement::{self, BeaconPayload};
+use personal_site::locale::{self, LocalePreference};
+use personal_site::middleware::{
+    access_log::LoggedAccessCode, access_log_middleware, rate_limit_middleware, require_admin_auth,
+    security::SecurityContext, security_middleware, track_admin_session,
 };
-use app::AppState;
-use errors::{AppError, AppResult};
+use personal_site::s3::{DownloadMode, DOWNLOAD_MODE_SETTING_KEY};
+use personal_site::templates::{ADMIN_SHELL_TEMPLATE, LANDING_TEMPLATE};
+use personal_site::watermark::{self, Watermark, WatermarkMode};
+use personal_site::{
+    admin,
+    app::AppState,
+    contact, content, csrf, database,
+    errors::{AppError, AppResult},
+    resume::{self, RESUME_JSON_FILENAME},
+};
+
+/// Download name used for uploaded Resume.pdf files
+const DEFAULT_PDF_FILENAME: &str = "Grant_DeFayette_Resume.pdf";
 
-#[cfg(test)]
-mod tests;
+#[derive(Deserialize)]
+struct LocaleQuery {
+    lang: Option<String>,
+}
 
 async fn serve_access(
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(code): Path<String>,
-) -> AppResult<Html<String>> {
-    if !state.is_valid_code(&code).await.unwrap_or(false) {
+    Query(query): Query<LocaleQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let Some(access_code) = state.validate_code(&code).await.unwrap_or(None) else {
         return Err(AppError::InvalidAccess);
-    }
+    };
 
     tracing::info!("Valid access code used: {}", code);
 
-    let html_bytes =
-        state.s3.get_file(&code, "index.html").await.map_err(|e| {
+    let preference = locale_preference(&state, &headers, query.lang.as_deref()).await?;
+    let files = list_code_documents(&state, &code).await;
+
+    // Codes with a resume.json get the rendered template, others the uploaded index.html
+    let (html_content, content_language) = if let Some((filename, lang)) =
+        select_document(&state, &code, &files, &preference, RESUME_JSON_FILENAME).await
+    {
+        let resume = resume::load_resume(&state.s3, &code, &filename)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let html = resume::html::render_html(&resume, &code, &lang, query.lang.as_deref())
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        (html, lang)
+    } else {
+        let (filename, lang) = select_document(&state, &code, &files, &preference, "index.html")
+            .await
+            .unwrap_or_else(|| {
+                (
+                    "index.html".to_string(),
+                    preference.default_locale().to_string(),
+                )
+            });
+
+        let html_bytes = state.s3.get_file(&code, &filename).await.map_err(|e| {
             AppError::FileSystem(std::io::Error::new(std::io::ErrorKind::NotFound, e))
         })?;
 
-    let html_content = String::from_utf8(html_bytes).map_err(|e| {
-        AppError::FileSystem(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
-    })?;
+        let html = String::from_utf8(html_bytes).map_err(|e| {
+            AppError::FileSystem(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        })?;
+        (html, lang)
+    };
+
+    let tracking_enabled = state
+        .settings
+        .get_with_fallback(
+            engagement::ENGAGEMENT_TRACKING_SETTING_KEY,
+            ACCESS_CODE_SETTINGS_CATEGORY,
+            access_code.id,
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .is_some_and(|value| value == "true");
+
+    let html_content = if tracking_enabled {
+        engagement::inject_beacon_script(&html_content)
+    } else {
+        html_content
+    };
+
+    Ok(localized(Html(html_content), &content_language))
+}
+
+/// Locale preference for a request, with the default from the default_locale setting
+async fn locale_preference(
+    state: &AppState,
+    headers: &HeaderMap,
+    query_lang: Option<&str>,
+) -> AppResult<LocalePreference> {
+    let default_locale = state
+        .settings
+        .get(locale::DEFAULT_LOCALE_SETTING_KEY, Some("system"), None)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
 
-    Ok(Html(html_content))
+    Ok(LocalePreference::new(
+        query_lang,
+        accept_language,
+        default_locale.as_deref(),
+    ))
 }
 
-async fn download_access(
+/// File names in a code's S3 prefix, None if the bucket can't be listed (no s3:ListBucket)
+async fn list_code_documents(state: &AppState, code: &str) -> Option<Vec<String>> {
+    match state.s3.list_filenames(code).await {
+        Ok(files) => Some(files),
+        Err(e) => {
+            tracing::warn!(
+                "Could not list documents for {}, locale variants unavailable: {}",
+                code,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Pick the best locale variant of a document, e.g. index.de.html for index.html
+/// Without a listing only the unsuffixed file is considered
+async fn select_document(
+    state: &AppState,
+    code: &str,
+    files: &Option<Vec<String>>,
+    preference: &LocalePreference,
+    filename: &str,
+) -> Option<(String, String)> {
+    let (stem, ext) = filename.rsplit_once('.')?;
+    match files {
+        Some(files) => preference.select_file(files, stem, ext),
+        None => state.s3.file_exists(code, filename).await.then(|| {
+            (
+                filename.to_string(),
+                preference.default_locale().to_string(),
+            )
+        }),
+    }
+}
+
+/// Mark a response as localized so caches keep one copy per language
+fn localized(response: impl IntoResponse, content_language: &str) -> Response {
+    let mut response = response.into_response();
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(content_language) {
+        headers.insert(header::CONTENT_LANGUAGE, value);
+    }
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Language"));
+    response
+}
+
+/// Receive reading engagement beacons from the script injected by serve_access
+/// Ignored beacons (tracking off, bots) still get 204 so the script has nothing to react to
+async fn engagement_beacon(
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(code): Path<String>,
-) -> AppResult<impl IntoResponse> {
-    if !state.is_valid_code(&code).await.unwrap_or(false) {
+    Extension(security_context): Extension<SecurityContext>,
+    body: Bytes,
+) -> AppResult<StatusCode> {
+    let Some(access_code) = state
+        .find_active_code(&code)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+    else {
         return Err(AppError::InvalidAccess);
+    };
+
+    let tracking_enabled = state
+        .settings
+        .get_with_fallback(
+            engagement::ENGAGEMENT_TRACKING_SETTING_KEY,
+            ACCESS_CODE_SETTINGS_CATEGORY,
+            access_code.id,
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .is_some_and(|value| value == "true");
+
+    if !tracking_enabled || engagement::is_bot(security_context.user_agent.as_deref()) {
+        return Ok(StatusCode::NO_CONTENT);
     }
 
+    let Some(payload) = BeaconPayload::parse(&body) else {
+        return Ok(StatusCode::BAD_REQUEST);
+    };
+
+    state
+        .engagement
+        .record_beacon(
+            access_code.id,
+            &payload,
+            Some(security_context.ip_address.to_string()),
+            security_context.user_agent,
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn download_access(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(code): Path<String>,
+    Query(query): Query<LocaleQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let Some(access_code) = state.validate_code(&code).await.unwrap_or(None) else {
+        return Err(AppError::InvalidAccess);
+    };
+
     tracing::info!("Valid access code used for download: {}", code);
 
-    let pdf_content =
-        state.s3.get_file(&code, "Resume.pdf").await.map_err(|e| {
-            AppError::FileSystem(std::io::Error::new(std::io::ErrorKind::NotFound, e))
-        })?;
+    let preference = locale_preference(&state, &headers, query.lang.as_deref()).await?;
+    let files = list_code_documents(&state, &code).await;
+
+    let resume =
+        match select_document(&state, &code, &files, &preference, RESUME_JSON_FILENAME).await {
+            Some((filename, lang)) => {
+                let resume = resume::load_resume(&state.s3, &code, &filename)
+                    .await
+                    .map_err(|e| AppError::Internal(e.to_string()))?;
+                Some((resume, lang))
+            }
+            None => None,
+        };
+    let (pdf_file, pdf_lang) = select_document(&state, &code, &files, &preference, "Resume.pdf")
+        .await
+        .unwrap_or_else(|| {
+            (
+                "Resume.pdf".to_string(),
+                preference.default_locale().to_string(),
+            )
+        });
+
+    let watermark_mode = state
+        .settings
+        .get_with_fallback(
+            watermark::WATERMARK_SETTING_KEY,
+            ACCESS_CODE_SETTINGS_CATEGORY,
+            access_code.id,
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map(|value| WatermarkMode::parse(&value))
+        .unwrap_or(WatermarkMode::Off);
+
+    let download_mode = state
+        .settings
+        .get_with_fallback(
+            DOWNLOAD_MODE_SETTING_KEY,
+            ACCESS_CODE_SETTINGS_CATEGORY,
+            access_code.id,
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map(|value| DownloadMode::parse(&value))
+        .unwrap_or(DownloadMode::Proxy);
+
+    // Generated and watermarked PDFs only exist in memory, so those are always proxied
+    if download_mode == DownloadMode::PresignedRedirect {
+        if resume.is_none() && !watermark_mode.is_enabled() {
+            let url = state
+                .s3
+                .presigned_get_url(&code, &pdf_file, &content_disposition(DEFAULT_PDF_FILENAME))
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            // 302 with no-store so the short-lived URL is never reused from a cache
+            let response = (
+                StatusCode::FOUND,
+                [
+                    (header::LOCATION, url),
+                    (header::CACHE_CONTROL, "no-store".to_string()),
+                ],
+            );
+            return Ok(localized(response, &pdf_lang));
+        }
+
+        tracing::debug!(
+            "Presigned downloads unavailable for generated or watermarked PDFs, proxying: {}",
+            code
+        );
+    }
+
+    // Generate the PDF from resume.json when present so it matches the HTML view
+    let (mut pdf_content, filename, content_language) = match resume {
+        Some((resume, lang)) => {
+            let pdf =
+                resume::pdf::render_pdf(&resume).map_err(|e| AppError::Internal(e.to_string()))?;
+            (pdf, resume.pdf_filename(), lang)
+        }
+        None => {
+            let pdf = state.s3.get_file(&code, &pdf_file).await.map_err(|e| {
+                AppError::FileSystem(std::io::Error::new(std::io::ErrorKind::NotFound, e))
+            })?;
+            (pdf, DEFAULT_PDF_FILENAME.to_string(), pdf_lang)
+        }
+    };
+
+    if watermark_mode.is_enabled() {
+        let stamp = Watermark::new(&access_code.name, chrono::Utc::now());
+        pdf_content = watermark::watermark_pdf(&pdf_content, &stamp, watermark_mode)
+            .map_err(|e| AppError::Internal(format!("Failed to watermark PDF: {}", e)))?;
+        tracing::info!("Watermarked download for access code: {}", code);
+    }
 
     let response = (
         StatusCode::OK,
         [
-            (header::CONTENT_TYPE, "application/pdf"),
-            (
-                header::CONTENT_DISPOSITION,
-                "attachment; filename=\"Grant_DeFayette_Resume.pdf\"",
-            ),
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition(&filename)),
         ],
         pdf_content,
     );
 
-    Ok(response)
+    Ok(localized(response, &content_language))
+}
+
+fn content_disposition(filename: &str) -> String {
+    format!("attachment; filename=\"{}\"", filename)
 }
 
 async fn health_check() -> &'static str {
     "OK"
 }
 
-async fn serve_landing() -> AppResult<Html<String>> {
-    let html_content = tokio::fs::read_to_string("landing.html")
+async fn serve_landing(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<LocaleQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    render_landing(
+        &state,
+        &headers,
+        query.lang.as_deref(),
+        StatusCode::OK,
+        LandingNotice::default(),
+    )
+    .await
+}
+
+/// Form feedback shown when the landing page is re-rendered after a POST
+#[derive(Default)]
+struct LandingNotice<'a> {
+    access_error: Option<&'a str>,
+    contact_error: Option<&'a str>,
+    contact_sent: bool,
+    /// Submitted contact fields, kept so a rejected message doesn't have to be retyped
+    contact: Option<&'a ContactForm>,
+}
+
+#[derive(Deserialize)]
+struct AccessForm {
+    code: String,
+    csrf_token: String,
+}
+
+/// Access code entry form on the landing page
+/// Valid codes redirect to the document, anything else re-renders the landing page with a generic error
+async fn submit_access(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<LocaleQuery>,
+    headers: HeaderMap,
+    Form(form): Form<AccessForm>,
+) -> AppResult<Response> {
+    let lang = query.lang.as_deref().and_then(locale::normalize_tag);
+    if !csrf::verify(&headers, &form.csrf_token) {
+        tracing::warn!("Access form submitted with missing or mismatched CSRF token");
+        return render_landing(
+            &state,
+            &headers,
+            lang.as_deref(),
+            StatusCode::FORBIDDEN,
+            LandingNotice {
+                access_error: Some("Your session expired, please try again."),
+                ..Default::default()
+            },
+        )
+        .await;
+    }
+
+    let code = form.code.trim();
+    // Usage is counted when the redirect target is served, so only check the code here
+    let access_code = if code.is_empty() {
+        None
+    } else {
+        state
+            .find_active_code(code)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+    };
+
+    let mut response = match access_code {
+        Some(access_code) => {
+            tracing::info!("Valid access code submitted via form: {}", code);
+            let mut location = format!("/resume/{}", encode_path_segment(&access_code.code));
+            if let Some(lang) = &lang {
+                location.push_str(&format!("?lang={}", lang));
+            }
+            (StatusCode::SEE_OTHER, [(header::LOCATION, location)]).into_response()
+        }
+        None => {
+            render_landing(
+                &state,
+                &headers,
+                lang.as_deref(),
+                StatusCode::NOT_FOUND,
+                LandingNotice {
+                    access_error: Some("That access code is not valid."),
+                    ..Default::default()
+                },
+            )
+            .await?
+        }
+    };
+
+    // Log the submitted code like the URL based route would
+    response
+        .extensions_mut()
+        .insert(LoggedAccessCode(code.to_string()));
+    Ok(response)
+}
+
+#[derive(Deserialize, Serialize)]
+struct ContactForm {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default, rename = "website", skip_serializing)]
+    honeypot: String,
+    #[serde(default, skip_serializing)]
+    csrf_token: String,
+}
+
+/// Contact form on the landing page
+/// Messages are stored for the admin inbox and forwarded to the contact_email setting
+async fn submit_contact(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<LocaleQuery>,
+    Extension(security_context): Extension<SecurityContext>,
+    headers: HeaderMap,
+    Form(form): Form<ContactForm>,
+) -> AppResult<Response> {
+    let lang = query.lang.as_deref().and_then(locale::normalize_tag);
+    let rejected = |status, error| {
+        render_landing(
+            &state,
+            &headers,
+            lang.as_deref(),
+            status,
+            LandingNotice {
+                contact_error: Some(error),
+                contact: Some(&form),
+                ..Default::default()
+            },
+        )
+    };
+
+    if !csrf::verify(&headers, &form.csrf_token) {
+        tracing::warn!("Contact form submitted with missing or mismatched CSRF token");
+        return rejected(
+            StatusCode::FORBIDDEN,
+            "Your session expired, please try again.",
+        )
+        .await;
+    }
+
+    let sent = LandingNotice {
+        contact_sent: true,
+        ..Default::default()
+    };
+
+    // Bots get the same response as people so they don't learn to skip the field
+    if !form.honeypot.is_empty() {
+        tracing::info!(
+            "Dropped contact form submission with honeypot filled from {}",
+            security_context.ip_address
+        );
+        return render_landing(&state, &headers, lang.as_deref(), StatusCode::OK, sent).await;
+    }
+
+    let submission = match ContactSubmission::parse(&form.name, &form.email, &form.message) {
+        Ok(submission) => submission,
+        Err(error) => return rejected(StatusCode::UNPROCESSABLE_ENTITY, error).await,
+    };
+
+    if !state
+        .contact
+        .check_limit(&state.security, security_context.ip_address)
+    {
+        return rejected(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many messages, please try again later.",
+        )
+        .await;
+    }
+
+    state
+        .contact
+        .submit(
+            &submission,
+            Some(security_context.ip_address),
+            security_context.user_agent.clone(),
+        )
         .await
-        .map_err(AppError::FileSystem)?;
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    render_landing(&state, &headers, lang.as_deref(), StatusCode::OK, sent).await
+}
+
+async fn render_landing(
+    state: &AppState,
+    headers: &HeaderMap,
+    query_lang: Option<&str>,
+    status: StatusCode,
+    notice: LandingNotice<'_>,
+) -> AppResult<Response> {
+    let csrf_token = csrf::token_for_form(headers);
+
+    let preference = locale_preference(state, headers, query_lang).await?;
+    let chosen = preference.choose(&state.templates.locales(LANDING_TEMPLATE));
+    let template_name = match &chosen {
+        Some(lang) => locale::variant_filename("landing", "html", lang),
+        None => LANDING_TEMPLATE.to_string(),
+    };
+    let content_language = chosen.unwrap_or_else(|| preference.default_locale().to_string());
+
+    let html_content = state
+        .templates
+        .render_with(
+            &template_name,
+            context! {
+                csrf_token => csrf_token,
+                csrf_field => csrf::CSRF_FIELD_NAME,
+                access_error => notice.access_error,
+                contact_error => notice.contact_error,
+                contact_sent => notice.contact_sent,
+                contact => notice.contact,
+                honeypot_field => contact::HONEYPOT_FIELD_NAME,
+                lang => content_language,
+                // Explicit ?lang= is kept on the form so the choice survives the POST
+                lang_override => query_lang.and_then(locale::normalize_tag),
+            },
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| {
+            AppError::FileSystem(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "landing.html not found",
+            ))
+        })?;
+
+    // The page embeds a per-visitor token, so it must not be cached
+    let response = (
+        status,
+        [
+            (
+                header::SET_COOKIE,
+                // Site wide so both the access and contact forms can verify it
+                csrf::cookie_header(&csrf_token, "/"),
+            ),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        Html(html_content),
+    );
 
-    let site_domain = env::var("SITE_DOMAIN").map_err(|_| {
-        AppError::Configuration("SITE_DOMAIN environment variable is required".to_string())
-    })?;
-    let site_url = env::var("SITE_URL").map_err(|_| {
-        AppError::Configuration("SITE_URL environment variable is required".to_string())
-    })?;
+    Ok(localized(response, &content_language))
+}
 
-    let templated_content = html_content
-        .replace("{{SITE_DOMAIN}}", &site_domain)
-        .replace("{{SITE_URL}}", &site_url);
+/// Percent-encode a single URL path segment
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
 
-    Ok(Html(templated_content))
+/// Blog index listing every published post
+async fn serve_blog_index(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> AppResult<Html<String>> {
+    let posts = state
+        .content
+        .published_posts(None)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let html =
+        content::html::render_index(&posts, None).map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Html(html))
+}
+
+/// Published posts carrying a tag, unknown or empty tags are a 404
+async fn serve_blog_tag(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(tag): Path<String>,
+) -> AppResult<Html<String>> {
+    let tag = content::normalize_tag(&tag).ok_or(AppError::InvalidAccess)?;
+    let posts = state
+        .content
+        .published_posts(Some(&tag))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if posts.is_empty() {
+        return Err(AppError::InvalidAccess);
+    }
+    let html = content::html::render_index(&posts, Some(&tag))
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Html(html))
+}
+
+async fn serve_blog_post(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Html<String>> {
+    if !content::is_valid_slug(&slug) {
+        return Err(AppError::InvalidAccess);
+    }
+    let post = state
+        .content
+        .published_post(&slug)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or(AppError::InvalidAccess)?;
+    let html = content::html::render_post(&post).map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Html(html))
+}
+
+/// Atom feed of published posts
+async fn serve_atom_feed(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let posts = state
+        .content
+        .published_posts(None)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let body = feed::render_atom(&posts, state.templates.site().url.as_deref());
+    Ok(feed_response(&headers, feed::ATOM_CONTENT_TYPE, body))
+}
+
+/// RSS 2.0 feed of published posts
+async fn serve_rss_feed(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let posts = state
+        .content
+        .published_posts(None)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let body = feed::render_rss(&posts, state.templates.site().url.as_deref());
+    Ok(feed_response(&headers, feed::RSS_CONTENT_TYPE, body))
+}
+
+/// Feeds are revalidated on every poll, an unchanged feed is a bodiless 304
+fn feed_response(headers: &HeaderMap, content_type: &'static str, body: String) -> Response {
+    let etag = feed::etag(&body);
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| feed::etag_matches(value, &etag));
+
+    let cache_headers = [
+        (header::ETAG, etag),
+        (header::CACHE_CONTROL, "no-cache".to_string()),
+    ];
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, content_type)],
+        body,
+    )
+        .into_response()
+}
+
+async fn serve_robots(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> impl IntoResponse {
+    let body = discovery::render_robots(state.templates.site().url.as_deref());
+    (
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        body,
+    )
+}
+
+/// Sitemap of public pages only, code-gated and admin pages are never listed
+async fn serve_sitemap(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> AppResult<impl IntoResponse> {
+    let posts = state
+        .content
+        .published_posts(None)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let body = discovery::render_sitemap(state.templates.site().url.as_deref(), &posts);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        body,
+    ))
+}
+
+/// security.txt from the security_txt_contact and security_txt_expires settings, 404 until both are set
+async fn serve_security_txt(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> AppResult<impl IntoResponse> {
+    let security_txt = SecurityTxt::load(&state.settings, state.templates.site().url.as_deref())
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or(AppError::InvalidAccess)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        security_txt.render(),
+    ))
 }
 
 async fn handle_404() -> (StatusCode, &'static str) {
@@ -146,10 +804,20 @@ async fn serve_favicon() -> AppResult<impl IntoResponse> {
     Ok(response)
 }
 
-async fn serve_admin_spa() -> AppResult<impl IntoResponse> {
-    let html_content = tokio::fs::read_to_string("admin-assets/index.html")
+async fn serve_admin_spa(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> AppResult<impl IntoResponse> {
+    let html_content = state
+        .templates
+        .render(ADMIN_SHELL_TEMPLATE)
         .await
-        .map_err(AppError::FileSystem)?;
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| {
+            AppError::FileSystem(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "admin-assets/index.html not found, run make admin-build",
+            ))
+        })?;
 
     let response = (
         StatusCode::OK,
@@ -193,36 +861,44 @@ async fn main() -> anyhow::Result<()> {
     let session_store = PostgresStore::new(session_pool);
     session_store.migrate().await?;
 
-    let session_layer = SessionManagerLayer::new(session_store)
-        .with_expiry(Expiry::OnInactivity(TimeDuration::days(7)));
+    let session_layer = SessionManagerLayer::new(session_store.clone()).with_expiry(
+        Expiry::OnInactivity(TimeDuration::days(admin::sessions::SESSION_INACTIVITY_DAYS)),
+    );
 
     // Setup admin auth backend
-    let admin_backend = admin::AdminAuthBackend::new(state.db.clone());
+    let mut admin_backend = admin::AdminAuthBackend::new(state.db.clone())
+        .with_email_service(state.email.clone())
+        .with_session_store(Arc::new(session_store));
+    if let Some(oidc_config) = admin::oidc::OidcConfig::from_env() {
+        tracing::info!("Single sign-on enabled with {}", oidc_config.issuer_url);
+        admin_backend = admin_backend.with_oidc(admin::oidc::OidcProvider::new(oidc_config)?);
+    }
     let auth_layer =
         AuthManagerLayerBuilder::new(admin_backend.clone(), session_layer.clone()).build();
 
-    // Setup email service
-    let email_service = Arc::new(email::EmailService::new().await?);
-
     // Create admin state
     let admin_state = admin::routes::AdminState {
         auth_backend: admin_backend.clone(),
-        email_service: email_service.clone(),
+        email_service: state.email.clone(),
         settings: state.settings.clone(),
     };
 
     // Build admin routes
     let admin_routes = admin::routes::admin_api_routes()
         .with_state(admin_state)
+        .layer(from_fn(track_admin_session))
         .layer(auth_layer.clone());
 
     // Build access code management routes
     let access_code_state = admin::access_codes::AccessCodeState {
         db: state.db.clone(),
+        settings: state.settings.clone(),
+        engagement: state.engagement.clone(),
     };
     let access_code_routes = admin::access_codes::access_code_routes()
         .with_state(access_code_state)
         .layer(from_fn(require_admin_auth))
+        .layer(from_fn(track_admin_session))
         .layer(auth_layer.clone());
 
     // Build access log management routes
@@ -232,6 +908,39 @@ async fn main() -> anyhow::Result<()> {
     let access_log_routes = admin::access_logs::access_log_routes()
         .with_state(access_log_state)
         .layer(from_fn(require_admin_auth))
+        .layer(from_fn(track_admin_session))
+        .layer(auth_layer.clone());
+
+    // Build document cleanup routes
+    let document_cleanup =
+        DocumentCleanupService::new(state.db.clone(), state.s3.clone(), state.settings.clone());
+    let document_state = admin::documents::DocumentState {
+        cleanup: document_cleanup.clone(),
+    };
+    let document_routes = admin::documents::document_routes()
+        .with_state(document_state)
+        .layer(from_fn(require_admin_auth))
+        .layer(from_fn(track_admin_session))
+        .layer(auth_layer.clone());
+
+    // Build blog post publishing routes
+    let post_state = admin::posts::PostState {
+        content: state.content.clone(),
+    };
+    let post_routes = admin::posts::post_routes()
+        .with_state(post_state)
+        .layer(from_fn(require_admin_auth))
+        .layer(from_fn(track_admin_session))
+        .layer(auth_layer.clone());
+
+    // Build contact message inbox routes
+    let message_state = admin::messages::MessageState {
+        contact: state.contact.clone(),
+    };
+    let message_routes = admin::messages::message_routes()
+        .with_state(message_state)
+        .layer(from_fn(require_admin_auth))
+        .layer(from_fn(track_admin_session))
         .layer(auth_layer.clone());
 
     // Build settings management routes
@@ -241,19 +950,31 @@ async fn main() -> anyhow::Result<()> {
     let settings_routes = admin::settings::settings_routes()
         .with_state(settings_state)
         .layer(from_fn(require_admin_auth))
+        .layer(from_fn(track_admin_session))
         .layer(auth_layer);
 
     // Build the application with routes and middleware stack
     let app = Router::new()
         .route("/", get(serve_landing))
         .route("/favicon.ico", get(serve_favicon))
-        .route("/access", get(serve_landing))
+        .route("/access", get(serve_landing).post(submit_access))
+        .route("/contact", post(submit_contact))
         .route("/access/{code}", get(serve_access))
         .route("/access/{code}/download", get(download_access))
+        .route("/access/{code}/beacon", post(engagement_beacon))
         // Alias routes for resume
         .route("/resume/{code}", get(serve_access))
         .route("/resume/{code}/download", get(download_access))
+        .route("/resume/{code}/beacon", post(engagement_beacon))
         .route("/health", get(health_check))
+        .route("/blog", get(serve_blog_index))
+        .route("/blog/tags/{tag}", get(serve_blog_tag))
+        .route("/blog/{slug}", get(serve_blog_post))
+        .route("/feed.xml", get(serve_atom_feed))
+        .route("/rss.xml", get(serve_rss_feed))
+        .route("/robots.txt", get(serve_robots))
+        .route("/sitemap.xml", get(serve_sitemap))
+        .route("/.well-known/security.txt", get(serve_security_txt))
         .nest_service("/admin/assets", ServeDir::new("./admin-assets/assets"))
         .route("/admin", get(serve_admin_spa))
         .route("/admin/{*path}", get(serve_admin_spa))
@@ -261,6 +982,9 @@ async fn main() -> anyhow::Result<()> {
         .merge(admin_routes)
         .merge(access_code_routes)
         .merge(access_log_routes)
+        .merge(document_routes)
+        .merge(post_routes)
+        .merge(message_routes)
         .merge(settings_routes)
         .fallback(handle_404)
         .with_state(state.clone())
@@ -276,6 +1000,9 @@ async fn main() -> anyhow::Result<()> {
                 .layer(TraceLayer::new_for_http()),
         );
 
+    // Recompile landing page and admin shell templates when they change on disk
+    state.templates.spawn_hot_reload();
+
     // Start cleanup task for old entries
     // Runs every 5 minutes to prevent memory leaks in rate_limit_cache
     let cleanup_state = state.clone();
@@ -289,6 +1016,40 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
+    // Start document cleanup task for expired and deleted codes
+    // Runs hourly, the document_cleanup_enabled setting is checked on every run
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // Every hour
+        loop {
+            interval.tick().await;
+            match document_cleanup.is_enabled().await {
+                Ok(true) => {
+                    if let Err(e) = document_cleanup.run().await {
+                        tracing::error!("Failed to clean up documents: {}", e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => tracing::error!("Failed to read document cleanup setting: {}", e),
+            }
+        }
+    });
+
+    // Start purge of admin accounts that never verified their email
+    // Runs hourly, accounts are kept for UNVERIFIED_ADMIN_PURGE_DAYS
+    let unverified_retention = admin::auth::unverified_admin_retention();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // Every hour
+        loop {
+            interval.tick().await;
+            if let Err(e) = admin_backend
+                .purge_unverified_admins(unverified_retention)
+                .await
+            {
+                tracing::error!("Failed to purge unverified admins: {}", e);
+            }
+        }
+    });
+
     // Determine the bind address
     let port = env::var("PORT")
         .unwrap_or_else(|_| "3000".to_string())
@@ -23,6 +23,11 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
+/// Access code submitted in a request body (e.g. the landing page form)
+/// Handlers attach this to the response so the attempt is logged as if the code were in the path
+#[derive(Debug, Clone)]
+pub struct LoggedAccessCode(pub String);
+
 /// Access logging middleware that logs requests after they complete
 /// Uses the SecurityContext and response status to determine success/failure
 pub async fn access_log_middleware(
@@ -50,12 +55,18 @@ pub async fn access_log_middleware(
     let response = next.run(request).await;
 
     // Determine if the request was successful based on status code
+    // Redirects count as success, e.g. presigned download redirects
     let status = response.status();
-    let success = status.is_success();
+    let success = status.is_success() || status.is_redirection();
 
     // Determine action type based on path for filtering
     let action_type = determine_action_type(&path);
 
+    let logged_path = match response.extensions().get::<LoggedAccessCode>() {
+        Some(LoggedAccessCode(code)) => format!("{}/{}", path, code),
+        None => path,
+    };
+
     // Only log if logging is enabled and meets criteria
     if should_log(&action_type, success, &state) {
         // Use special action prefix for admin-authenticated requests
@@ -71,7 +82,7 @@ pub async fn access_log_middleware(
             .log_access_attempt(
                 Some(security_context.ip_address),
                 security_context.user_agent,
-                &format!("{}:{}", method, path),
+                &format!("{}:{}", method, logged_path),
                 &action,
                 success,
             )
@@ -86,14 +97,14 @@ pub async fn access_log_middleware(
 
 /// Determine the action type based on the request path for filtering purposes
 fn determine_action_type(path: &str) -> String {
-    if path.starts_with("/assets/") {
-        "asset".to_string()
-    } else if path.starts_with("/admin/assets") {
+    if path.starts_with("/assets/") || path.starts_with("/admin/assets") {
         "asset".to_string()
     } else if path == "/health" {
         "health".to_string()
     } else if path == "/favicon.ico" {
         "favicon".to_string()
+    } else if path.ends_with("/beacon") {
+        "beacon".to_string()
     } else {
         "request".to_string()
     }
@@ -105,7 +116,8 @@ fn should_log(action: &str, success: bool, state: &AppState) -> bool {
     // - health checks (monitoring pings)
     // - favicon requests (browser automatic requests)
     // - asset requests (CSS, JS, images - these are just page dependencies)
-    if matches!(action, "health" | "favicon" | "asset") {
+    // - engagement beacons (recorded separately, one per page hide)
+    if matches!(action, "health" | "favicon" | "asset" | "beacon") {
         return false;
     }
 
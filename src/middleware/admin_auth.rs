@@ -15,11 +15,17 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::admin::api_tokens::TokenScopes;
+use crate::admin::roles::Permission;
+use crate::admin::routes::ensure_permission;
+use crate::admin::sessions;
 use crate::admin::{AdminAuthBackend, AdminUserAuth};
+use crate::errors::AppError;
+use crate::middleware::security::SecurityContext;
 use axum::{
     body::Body,
-    extract::Request,
-    http::StatusCode,
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -38,6 +44,23 @@ pub async fn require_admin_auth(
     );
     tracing::debug!("Auth session user present: {}", auth_session.user.is_some());
 
+    // Scripts authenticate with an API token instead of a session
+    if let Some(token) = bearer_token(request.headers()) {
+        return match auth_session.backend.authenticate_api_token(&token).await {
+            Ok(Some((user, scopes))) => {
+                tracing::debug!("API token authenticated: {}", user.email);
+                request.extensions_mut().insert(user);
+                request.extensions_mut().insert(scopes);
+                next.run(request).await
+            }
+            Ok(None) => {
+                tracing::warn!("Invalid or expired API token");
+                (StatusCode::UNAUTHORIZED, "Invalid API token").into_response()
+            }
+            Err(e) => AppError::Internal(e.to_string()).into_response(),
+        };
+    }
+
     if let Some(user) = auth_session.user {
         tracing::debug!("User authenticated: {}", user.email);
         request.extensions_mut().insert(user);
@@ -50,6 +73,62 @@ pub async fn require_admin_auth(
     }
 }
 
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim().to_string())
+}
+
+/// Keeps the session list current and logs out sessions that have been revoked. Runs inside the auth
+/// layer and before `require_admin_auth`, on every admin router.
+pub async fn track_admin_session(mut request: Request<Body>, next: Next) -> Response {
+    let context = request.extensions().get::<SecurityContext>().cloned();
+
+    if let Some(auth_session) = request.extensions_mut().get_mut::<AdminAuthSession>() {
+        if let Err(e) = sessions::track(auth_session, context.as_ref()).await {
+            tracing::error!("Failed to track admin session: {}", e);
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Checks the role of the admin that `require_admin_auth` let through, which must run first.
+/// Routes declare the permission they need:
+/// ```ignore
+/// Router::new()
+///     .route("/api/admin/things", delete(clear_things))
+///     .route_layer(from_fn_with_state(Permission::ClearThings, require_permission))
+/// ```
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(user) = request.extensions().get::<AdminUserAuth>() else {
+        tracing::warn!("Permission check without an authenticated user");
+        return (StatusCode::UNAUTHORIZED, "Not authenticated").into_response();
+    };
+
+    if let Err(e) = ensure_permission(user, permission) {
+        return e.into_response();
+    }
+
+    if let Some(scopes) = request.extensions().get::<TokenScopes>() {
+        if !scopes.allows(permission) {
+            return AppError::Forbidden(format!(
+                "This token lacks the {} scope",
+                permission.scope().unwrap_or("required")
+            ))
+            .into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
 /// Extension type for accessing authenticated admin user in handlers
 /// Usage in handlers:
 /// ```ignore
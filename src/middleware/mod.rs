@@ -24,6 +24,8 @@ pub mod rate_limit;
 pub mod security;
 
 pub use access_log::access_log_middleware;
-pub use admin_auth::{require_admin_auth, AuthenticatedUser};
+pub use admin_auth::{
+    require_admin_auth, require_permission, track_admin_session, AuthenticatedUser,
+};
 pub use rate_limit::rate_limit_middleware;
 pub use security::security_middleware;
@@ -53,7 +53,7 @@ pub async fn security_middleware(
     let user_agent = headers
         .get("user-agent")
         .and_then(|h| h.to_str().ok())
-        .map(|s| sanitize_user_agent(s));
+        .map(sanitize_user_agent);
 
     // Log all headers for debugging
     tracing::debug!(
@@ -0,0 +1,84 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EngagementEvents::Table)
+                    .if_not_exists()
+                    .col(uuid(EngagementEvents::Id).primary_key())
+                    .col(uuid(EngagementEvents::AccessCodeId))
+                    .col(string_uniq(EngagementEvents::ViewId))
+                    .col(big_integer(EngagementEvents::TimeOnPageMs))
+                    .col(integer(EngagementEvents::ScrollDepth))
+                    .col(string_null(EngagementEvents::IpAddress))
+                    .col(string_null(EngagementEvents::UserAgent))
+                    .col(
+                        timestamp_with_time_zone(EngagementEvents::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(EngagementEvents::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on access_code_id for per code analytics
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_engagement_events_access_code_id")
+                    .table(EngagementEvents::Table)
+                    .col(EngagementEvents::AccessCodeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EngagementEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EngagementEvents {
+    Table,
+    Id,
+    AccessCodeId,
+    ViewId,
+    TimeOnPageMs,
+    ScrollDepth,
+    IpAddress,
+    UserAgent,
+    CreatedAt,
+    UpdatedAt,
+}
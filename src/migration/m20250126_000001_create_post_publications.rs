@@ -0,0 +1,59 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostPublications::Table)
+                    .if_not_exists()
+                    .col(uuid(PostPublications::Id).primary_key())
+                    .col(string_uniq(PostPublications::Slug))
+                    .col(boolean(PostPublications::Published))
+                    .col(uuid_null(PostPublications::UpdatedBy))
+                    .col(
+                        timestamp_with_time_zone(PostPublications::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostPublications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PostPublications {
+    Table,
+    Id,
+    Slug,
+    Published,
+    UpdatedBy,
+    UpdatedAt,
+}
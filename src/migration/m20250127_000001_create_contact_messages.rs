@@ -0,0 +1,86 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ContactMessages::Table)
+                    .if_not_exists()
+                    .col(uuid(ContactMessages::Id).primary_key())
+                    .col(string(ContactMessages::Name))
+                    .col(string(ContactMessages::Email))
+                    .col(text(ContactMessages::Message))
+                    .col(string_null(ContactMessages::IpAddress))
+                    .col(string_null(ContactMessages::UserAgent))
+                    .col(boolean(ContactMessages::Read).default(false))
+                    .col(boolean(ContactMessages::Delivered).default(false))
+                    .col(
+                        timestamp_with_time_zone(ContactMessages::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(ContactMessages::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on created_at for the newest first inbox listing
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_contact_messages_created_at")
+                    .table(ContactMessages::Table)
+                    .col(ContactMessages::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ContactMessages::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContactMessages {
+    Table,
+    Id,
+    Name,
+    Email,
+    Message,
+    IpAddress,
+    UserAgent,
+    Read,
+    Delivered,
+    CreatedAt,
+    UpdatedAt,
+}
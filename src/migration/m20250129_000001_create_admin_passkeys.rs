@@ -0,0 +1,94 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdminPasskeys::Table)
+                    .if_not_exists()
+                    .col(uuid(AdminPasskeys::Id).primary_key())
+                    .col(uuid(AdminPasskeys::AdminUserId))
+                    .col(string(AdminPasskeys::Name))
+                    .col(string_uniq(AdminPasskeys::CredentialId))
+                    .col(text(AdminPasskeys::Passkey))
+                    .col(timestamp_with_time_zone_null(AdminPasskeys::LastUsedAt))
+                    .col(
+                        timestamp_with_time_zone(AdminPasskeys::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(AdminPasskeys::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_admin_passkeys_admin_user_id")
+                            .from(AdminPasskeys::Table, AdminPasskeys::AdminUserId)
+                            .to(AdminUsers::Table, AdminUsers::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on admin_user_id for listing an admin's passkeys
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_admin_passkeys_admin_user_id")
+                    .table(AdminPasskeys::Table)
+                    .col(AdminPasskeys::AdminUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AdminPasskeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdminPasskeys {
+    Table,
+    Id,
+    AdminUserId,
+    Name,
+    CredentialId,
+    Passkey,
+    LastUsedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AdminUsers {
+    Table,
+    Id,
+}
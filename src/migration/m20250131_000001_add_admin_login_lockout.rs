@@ -0,0 +1,74 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One column per statement, SQLite can't add several in a single ALTER TABLE
+        let columns = [
+            integer(AdminUsers::FailedLoginAttempts)
+                .default(0)
+                .to_owned(),
+            timestamp_with_time_zone_null(AdminUsers::LastFailedLoginAt),
+            timestamp_with_time_zone_null(AdminUsers::LockedUntil),
+        ];
+
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(AdminUsers::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            AdminUsers::FailedLoginAttempts,
+            AdminUsers::LastFailedLoginAt,
+            AdminUsers::LockedUntil,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(AdminUsers::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdminUsers {
+    Table,
+    FailedLoginAttempts,
+    LastFailedLoginAt,
+    LockedUntil,
+}
@@ -0,0 +1,92 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdminSessions::Table)
+                    .if_not_exists()
+                    .col(uuid(AdminSessions::Id).primary_key())
+                    .col(uuid(AdminSessions::AdminUserId))
+                    .col(string_null(AdminSessions::SessionId))
+                    .col(string_null(AdminSessions::IpAddress))
+                    .col(text_null(AdminSessions::UserAgent))
+                    .col(
+                        timestamp_with_time_zone(AdminSessions::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(AdminSessions::LastSeenAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_admin_sessions_admin_user")
+                            .from(AdminSessions::Table, AdminSessions::AdminUserId)
+                            .to(AdminUsers::Table, AdminUsers::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on admin_user_id for listing an admin's sessions
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_admin_sessions_admin_user_id")
+                    .table(AdminSessions::Table)
+                    .col(AdminSessions::AdminUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AdminSessions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdminSessions {
+    Table,
+    Id,
+    AdminUserId,
+    SessionId,
+    IpAddress,
+    UserAgent,
+    CreatedAt,
+    LastSeenAt,
+}
+
+#[derive(DeriveIden)]
+enum AdminUsers {
+    Table,
+    Id,
+}
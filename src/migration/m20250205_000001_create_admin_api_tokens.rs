@@ -0,0 +1,93 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdminApiTokens::Table)
+                    .if_not_exists()
+                    .col(uuid(AdminApiTokens::Id).primary_key())
+                    .col(uuid(AdminApiTokens::AdminUserId))
+                    .col(string(AdminApiTokens::Name))
+                    .col(string_len(AdminApiTokens::TokenPrefix, 16))
+                    .col(string_uniq(AdminApiTokens::TokenHash))
+                    .col(text(AdminApiTokens::Scopes))
+                    .col(timestamp_with_time_zone(AdminApiTokens::ExpiresAt))
+                    .col(timestamp_with_time_zone_null(AdminApiTokens::LastUsedAt))
+                    .col(
+                        timestamp_with_time_zone(AdminApiTokens::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_admin_api_tokens_admin_user")
+                            .from(AdminApiTokens::Table, AdminApiTokens::AdminUserId)
+                            .to(AdminUsers::Table, AdminUsers::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on admin_user_id for listing an admin's tokens
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_admin_api_tokens_admin_user_id")
+                    .table(AdminApiTokens::Table)
+                    .col(AdminApiTokens::AdminUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AdminApiTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdminApiTokens {
+    Table,
+    Id,
+    AdminUserId,
+    Name,
+    TokenPrefix,
+    TokenHash,
+    Scopes,
+    ExpiresAt,
+    LastUsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AdminUsers {
+    Table,
+    Id,
+}
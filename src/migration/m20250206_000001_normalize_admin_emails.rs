@@ -0,0 +1,67 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Admin emails are compared lowercased from now on. Rows that would clash with another
+        // account once lowercased are left alone for the owner to sort out.
+        let other = Alias::new("other");
+        let clash = Query::select()
+            .expr(Expr::val(1))
+            .from_as(AdminUsers::Table, other.clone())
+            .and_where(
+                Expr::expr(Func::lower(Expr::col((other.clone(), AdminUsers::Email)))).eq(
+                    Func::lower(Expr::col((AdminUsers::Table, AdminUsers::Email))),
+                ),
+            )
+            .and_where(
+                Expr::col((other, AdminUsers::Id))
+                    .ne(Expr::col((AdminUsers::Table, AdminUsers::Id))),
+            )
+            .to_owned();
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(AdminUsers::Table)
+                    .value(AdminUsers::Email, Func::lower(Expr::col(AdminUsers::Email)))
+                    .and_where(
+                        Expr::col(AdminUsers::Email).ne(Func::lower(Expr::col(AdminUsers::Email))),
+                    )
+                    .and_where(Expr::exists(clash).not())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // The original casing isn't kept, and lowercased addresses still work
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdminUsers {
+    Table,
+    Id,
+    Email,
+}
@@ -23,6 +23,19 @@ mod m20250121_000001_create_admin_users;
 mod m20250122_000001_create_access_codes;
 mod m20250123_000001_add_usage_count;
 mod m20250124_000001_create_settings;
+mod m20250125_000001_create_engagement_events;
+mod m20250126_000001_create_post_publications;
+mod m20250127_000001_create_contact_messages;
+mod m20250128_000001_add_admin_two_factor;
+mod m20250129_000001_create_admin_passkeys;
+mod m20250130_000001_add_admin_password_reset;
+mod m20250131_000001_add_admin_login_lockout;
+mod m20250201_000001_create_admin_invites;
+mod m20250202_000001_add_admin_roles;
+mod m20250203_000001_add_admin_disabled;
+mod m20250204_000001_create_admin_sessions;
+mod m20250205_000001_create_admin_api_tokens;
+mod m20250206_000001_normalize_admin_emails;
 
 pub struct Migrator;
 
@@ -35,6 +48,19 @@ impl MigratorTrait for Migrator {
             Box::new(m20250122_000001_create_access_codes::Migration),
             Box::new(m20250123_000001_add_usage_count::Migration),
             Box::new(m20250124_000001_create_settings::Migration),
+            Box::new(m20250125_000001_create_engagement_events::Migration),
+            Box::new(m20250126_000001_create_post_publications::Migration),
+            Box::new(m20250127_000001_create_contact_messages::Migration),
+            Box::new(m20250128_000001_add_admin_two_factor::Migration),
+            Box::new(m20250129_000001_create_admin_passkeys::Migration),
+            Box::new(m20250130_000001_add_admin_password_reset::Migration),
+            Box::new(m20250131_000001_add_admin_login_lockout::Migration),
+            Box::new(m20250201_000001_create_admin_invites::Migration),
+            Box::new(m20250202_000001_add_admin_roles::Migration),
+            Box::new(m20250203_000001_add_admin_disabled::Migration),
+            Box::new(m20250204_000001_create_admin_sessions::Migration),
+            Box::new(m20250205_000001_create_admin_api_tokens::Migration),
+            Box::new(m20250206_000001_normalize_admin_emails::Migration),
         ]
     }
 }
@@ -0,0 +1,61 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::{format_date, format_date_range, Resume};
+use anyhow::Result;
+use minijinja::{context, Environment};
+
+const RESUME_TEMPLATE: &str = include_str!("templates/resume.html");
+
+/// Render the resume through the bundled HTML template
+/// `code` is used to build the relative download link for the generated PDF,
+/// `lang` is the page language and `lang_override` an explicit ?lang= carried over to the download
+pub fn render_html(
+    resume: &Resume,
+    code: &str,
+    lang: &str,
+    lang_override: Option<&str>,
+) -> Result<String> {
+    let mut env = Environment::new();
+    env.add_function(
+        "date_range",
+        |start: Option<String>, end: Option<String>| {
+            format_date_range(start.as_deref(), end.as_deref())
+        },
+    );
+    env.add_filter("resume_date", |date: String| format_date(&date));
+    env.add_template("resume.html", RESUME_TEMPLATE)
+        .map_err(|e| anyhow::anyhow!("Invalid resume template: {}", e))?;
+
+    let template = env.get_template("resume.html")?;
+    let location = resume
+        .basics
+        .location
+        .as_ref()
+        .map(|l| l.display())
+        .unwrap_or_default();
+
+    template
+        .render(context! {
+            resume => resume,
+            location => location,
+            code => code,
+            lang => lang,
+            lang_override => lang_override,
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to render resume: {}", e))
+}
@@ -0,0 +1,254 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// JSON Resume (https://jsonresume.org/schema) support
+// A single resume.json per access code drives both the HTML view and the PDF download
+// so the two can never drift apart
+
+pub mod html;
+pub mod pdf;
+
+use crate::s3::S3Service;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// File name looked up in each access code's S3 prefix
+pub const RESUME_JSON_FILENAME: &str = "resume.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Resume {
+    pub basics: Basics,
+    pub work: Vec<Work>,
+    pub volunteer: Vec<Volunteer>,
+    pub education: Vec<Education>,
+    pub awards: Vec<Award>,
+    pub certificates: Vec<Certificate>,
+    pub publications: Vec<Publication>,
+    pub skills: Vec<Skill>,
+    pub languages: Vec<Language>,
+    pub interests: Vec<Interest>,
+    pub projects: Vec<Project>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Basics {
+    pub name: String,
+    pub label: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub url: Option<String>,
+    pub summary: Option<String>,
+    pub location: Option<Location>,
+    pub profiles: Vec<Profile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Location {
+    pub address: Option<String>,
+    pub postal_code: Option<String>,
+    pub city: Option<String>,
+    pub country_code: Option<String>,
+    pub region: Option<String>,
+}
+
+impl Location {
+    /// Short "City, Region, Country" form used in headers
+    pub fn display(&self) -> String {
+        [&self.city, &self.region, &self.country_code]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.trim().is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Profile {
+    pub network: String,
+    pub username: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Work {
+    pub name: String,
+    pub position: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub summary: Option<String>,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Volunteer {
+    pub organization: String,
+    pub position: Option<String>,
+    pub url: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub summary: Option<String>,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Education {
+    pub institution: String,
+    pub url: Option<String>,
+    pub area: Option<String>,
+    pub study_type: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub score: Option<String>,
+    pub courses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Award {
+    pub title: String,
+    pub date: Option<String>,
+    pub awarder: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Certificate {
+    pub name: String,
+    pub date: Option<String>,
+    pub issuer: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Publication {
+    pub name: String,
+    pub publisher: Option<String>,
+    pub release_date: Option<String>,
+    pub url: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Skill {
+    pub name: String,
+    pub level: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Language {
+    pub language: String,
+    pub fluency: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Interest {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Project {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub highlights: Vec<String>,
+    pub keywords: Vec<String>,
+}
+
+impl Resume {
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| anyhow::anyhow!("Invalid resume.json: {}", e))
+    }
+
+    /// Download file name derived from the resume owner's name
+    pub fn pdf_filename(&self) -> String {
+        let name: String = self
+            .basics
+            .name
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("_")
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .collect();
+
+        if name.is_empty() {
+            "Resume.pdf".to_string()
+        } else {
+            format!("{}_Resume.pdf", name)
+        }
+    }
+}
+
+/// Load a JSON resume uploaded for an access code
+/// `filename` is resume.json or a locale variant such as resume.de.json
+pub async fn load_resume(s3: &S3Service, code: &str, filename: &str) -> Result<Resume> {
+    let bytes = s3.get_file(code, filename).await?;
+    Resume::from_json(&bytes)
+}
+
+/// Format a JSON Resume date ("2020", "2020-04" or "2020-04-15") as "Apr 2020"
+pub fn format_date(date: &str) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut parts = date.trim().split('-');
+    let year = parts.next().unwrap_or_default();
+    let month = parts
+        .next()
+        .and_then(|m| m.parse::<usize>().ok())
+        .filter(|m| (1..=12).contains(m));
+
+    match month {
+        Some(m) => format!("{} {}", MONTHS[m - 1], year),
+        None => year.to_string(),
+    }
+}
+
+/// Format a start/end pair, treating a missing end date as ongoing
+pub fn format_date_range(start: Option<&str>, end: Option<&str>) -> String {
+    let start = start.filter(|s| !s.trim().is_empty()).map(format_date);
+    let end = end.filter(|s| !s.trim().is_empty()).map(format_date);
+
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{} - {}", start, end),
+        (Some(start), None) => format!("{} - Present", start),
+        (None, Some(end)) => end,
+        (None, None) => String::new(),
+    }
+}
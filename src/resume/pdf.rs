@@ -0,0 +1,540 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Minimal PDF layout for JSON resumes
+// Uses the standard Helvetica fonts so nothing needs to be embedded, with word wrapping
+// based on the published Helvetica glyph widths
+
+use super::{format_date, format_date_range, Resume};
+use anyhow::Result;
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Document, Object, ObjectId, Stream,
+};
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+const BULLET_INDENT: f32 = 12.0;
+
+const REGULAR_FONT: &str = "F1";
+const BOLD_FONT: &str = "F2";
+
+/// Helvetica advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Clone, Copy)]
+struct TextStyle {
+    bold: bool,
+    size: f32,
+    gray: f32,
+}
+
+impl TextStyle {
+    const fn regular(size: f32) -> Self {
+        Self {
+            bold: false,
+            size,
+            gray: 0.13,
+        }
+    }
+
+    const fn bold(size: f32) -> Self {
+        Self {
+            bold: true,
+            size,
+            gray: 0.0,
+        }
+    }
+
+    const fn muted(size: f32) -> Self {
+        Self {
+            bold: false,
+            size,
+            gray: 0.4,
+        }
+    }
+
+    fn line_height(&self) -> f32 {
+        self.size * 1.35
+    }
+
+    fn text_width(&self, text: &str) -> f32 {
+        let widths = if self.bold {
+            &HELVETICA_BOLD_WIDTHS
+        } else {
+            &HELVETICA_WIDTHS
+        };
+
+        text.chars()
+            .map(|c| match c as u32 {
+                code @ 32..=126 => widths[(code - 32) as usize] as f32,
+                _ => 556.0,
+            })
+            .sum::<f32>()
+            * self.size
+            / 1000.0
+    }
+}
+
+/// Accumulates positioned text into pages, starting a new page when the cursor runs out of room
+struct Layout {
+    pages: Vec<Vec<Operation>>,
+    cursor_y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            cursor_y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.cursor_y - height < MARGIN {
+            self.pages.push(Vec::new());
+            self.cursor_y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        self.cursor_y -= height;
+    }
+
+    fn draw_text(&mut self, x: f32, text: &str, style: TextStyle) {
+        let font = if style.bold { BOLD_FONT } else { REGULAR_FONT };
+        let ops = self.pages.last_mut().expect("layout always has a page");
+        ops.extend([
+            Operation::new("BT", vec![]),
+            Operation::new(
+                "Tf",
+                vec![Object::Name(font.as_bytes().to_vec()), style.size.into()],
+            ),
+            Operation::new("g", vec![style.gray.into()]),
+            Operation::new("Td", vec![x.into(), self.cursor_y.into()]),
+            Operation::new("Tj", vec![Object::string_literal(encode_win_ansi(text))]),
+            Operation::new("ET", vec![]),
+        ]);
+    }
+
+    /// Write a single line of text, with optional right aligned text on the same baseline
+    fn line(&mut self, text: &str, style: TextStyle, right: Option<(&str, TextStyle)>) {
+        self.ensure_space(style.line_height());
+        self.cursor_y -= style.size;
+        self.draw_text(MARGIN, text, style);
+        if let Some((right_text, right_style)) = right.filter(|(t, _)| !t.is_empty()) {
+            let x = PAGE_WIDTH - MARGIN - right_style.text_width(right_text);
+            self.draw_text(x, right_text, right_style);
+        }
+        self.cursor_y -= style.line_height() - style.size;
+    }
+
+    /// Write word wrapped text starting at the given indent
+    fn paragraph(&mut self, text: &str, style: TextStyle, indent: f32) {
+        let max_width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+        for line in wrap_text(text, style, max_width) {
+            self.ensure_space(style.line_height());
+            self.cursor_y -= style.size;
+            self.draw_text(MARGIN + indent, &line, style);
+            self.cursor_y -= style.line_height() - style.size;
+        }
+    }
+
+    fn bullet(&mut self, text: &str, style: TextStyle) {
+        self.ensure_space(style.line_height());
+        let top = self.cursor_y;
+        self.cursor_y -= style.size;
+        self.draw_text(MARGIN + 2.0, "\u{2022}", style);
+        self.cursor_y = top;
+        self.paragraph(text, style, BULLET_INDENT);
+    }
+
+    fn section(&mut self, title: &str) {
+        let style = TextStyle::bold(12.0);
+        // Keep headings with at least a couple of lines of their content
+        self.ensure_space(style.line_height() * 3.0);
+        self.space(8.0);
+        self.line(&title.to_uppercase(), style, None);
+        let ops = self.pages.last_mut().expect("layout always has a page");
+        let rule_y = self.cursor_y + 2.0;
+        ops.extend([
+            Operation::new("G", vec![0.75.into()]),
+            Operation::new("w", vec![0.5.into()]),
+            Operation::new("m", vec![MARGIN.into(), rule_y.into()]),
+            Operation::new("l", vec![(PAGE_WIDTH - MARGIN).into(), rule_y.into()]),
+            Operation::new("S", vec![]),
+        ]);
+        self.space(4.0);
+    }
+}
+
+/// Generate a PDF rendition of the resume
+pub fn render_pdf(resume: &Resume) -> Result<Vec<u8>> {
+    let layout = layout_resume(resume);
+    write_document(resume, layout)
+}
+
+fn layout_resume(resume: &Resume) -> Layout {
+    let body = TextStyle::regular(10.0);
+    let title = TextStyle::bold(11.0);
+    let dates = TextStyle::muted(9.5);
+
+    let mut layout = Layout::new();
+    let basics = &resume.basics;
+
+    layout.line(&basics.name, TextStyle::bold(22.0), None);
+    if let Some(label) = &basics.label {
+        layout.line(label, TextStyle::muted(13.0), None);
+    }
+
+    let mut contact: Vec<String> = [&basics.email, &basics.phone, &basics.url]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    if let Some(location) = basics.location.as_ref().map(|l| l.display()) {
+        if !location.is_empty() {
+            contact.push(location);
+        }
+    }
+    contact.extend(
+        basics
+            .profiles
+            .iter()
+            .map(|p| p.url.clone().unwrap_or_else(|| p.network.clone())),
+    );
+    if !contact.is_empty() {
+        layout.paragraph(&contact.join("  |  "), TextStyle::muted(9.5), 0.0);
+    }
+
+    if let Some(summary) = &basics.summary {
+        layout.section("Summary");
+        layout.paragraph(summary, body, 0.0);
+    }
+
+    if !resume.work.is_empty() {
+        layout.section("Experience");
+        for job in &resume.work {
+            let heading = match &job.position {
+                Some(position) => format!("{} - {}", position, job.name),
+                None => job.name.clone(),
+            };
+            let range = format_date_range(job.start_date.as_deref(), job.end_date.as_deref());
+            layout.line(&heading, title, Some((&range, dates)));
+            if let Some(summary) = &job.summary {
+                layout.paragraph(summary, body, 0.0);
+            }
+            for highlight in &job.highlights {
+                layout.bullet(highlight, body);
+            }
+            layout.space(6.0);
+        }
+    }
+
+    if !resume.projects.is_empty() {
+        layout.section("Projects");
+        for project in &resume.projects {
+            let range =
+                format_date_range(project.start_date.as_deref(), project.end_date.as_deref());
+            layout.line(&project.name, title, Some((&range, dates)));
+            if let Some(description) = &project.description {
+                layout.paragraph(description, body, 0.0);
+            }
+            for highlight in &project.highlights {
+                layout.bullet(highlight, body);
+            }
+            if !project.keywords.is_empty() {
+                layout.paragraph(&project.keywords.join(", "), TextStyle::muted(9.5), 0.0);
+            }
+            layout.space(6.0);
+        }
+    }
+
+    if !resume.volunteer.is_empty() {
+        layout.section("Volunteer");
+        for item in &resume.volunteer {
+            let heading = match &item.position {
+                Some(position) => format!("{} - {}", position, item.organization),
+                None => item.organization.clone(),
+            };
+            let range = format_date_range(item.start_date.as_deref(), item.end_date.as_deref());
+            layout.line(&heading, title, Some((&range, dates)));
+            if let Some(summary) = &item.summary {
+                layout.paragraph(summary, body, 0.0);
+            }
+            for highlight in &item.highlights {
+                layout.bullet(highlight, body);
+            }
+            layout.space(6.0);
+        }
+    }
+
+    if !resume.education.is_empty() {
+        layout.section("Education");
+        for school in &resume.education {
+            let range = format_date_range(school.start_date.as_deref(), school.end_date.as_deref());
+            layout.line(&school.institution, title, Some((&range, dates)));
+            let degree: Vec<&str> = [&school.study_type, &school.area]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            if !degree.is_empty() {
+                let mut text = degree.join(", ");
+                if let Some(score) = &school.score {
+                    text.push_str(&format!(" ({})", score));
+                }
+                layout.paragraph(&text, body, 0.0);
+            }
+            if !school.courses.is_empty() {
+                layout.paragraph(&school.courses.join(", "), TextStyle::muted(9.5), 0.0);
+            }
+            layout.space(6.0);
+        }
+    }
+
+    if !resume.skills.is_empty() {
+        layout.section("Skills");
+        for skill in &resume.skills {
+            let mut text = skill.name.clone();
+            if let Some(level) = &skill.level {
+                text.push_str(&format!(" ({})", level));
+            }
+            if !skill.keywords.is_empty() {
+                text.push_str(&format!(": {}", skill.keywords.join(", ")));
+            }
+            layout.paragraph(&text, body, 0.0);
+        }
+    }
+
+    if !resume.certificates.is_empty() {
+        layout.section("Certificates");
+        for cert in &resume.certificates {
+            let heading = match &cert.issuer {
+                Some(issuer) => format!("{} - {}", cert.name, issuer),
+                None => cert.name.clone(),
+            };
+            let date = cert.date.as_deref().map(format_date).unwrap_or_default();
+            layout.line(&heading, body, Some((&date, dates)));
+        }
+    }
+
+    if !resume.awards.is_empty() {
+        layout.section("Awards");
+        for award in &resume.awards {
+            let heading = match &award.awarder {
+                Some(awarder) => format!("{} - {}", award.title, awarder),
+                None => award.title.clone(),
+            };
+            let date = award.date.as_deref().map(format_date).unwrap_or_default();
+            layout.line(&heading, title, Some((&date, dates)));
+            if let Some(summary) = &award.summary {
+                layout.paragraph(summary, body, 0.0);
+            }
+        }
+    }
+
+    if !resume.publications.is_empty() {
+        layout.section("Publications");
+        for publication in &resume.publications {
+            let heading = match &publication.publisher {
+                Some(publisher) => format!("{} - {}", publication.name, publisher),
+                None => publication.name.clone(),
+            };
+            let date = publication
+                .release_date
+                .as_deref()
+                .map(format_date)
+                .unwrap_or_default();
+            layout.line(&heading, title, Some((&date, dates)));
+            if let Some(summary) = &publication.summary {
+                layout.paragraph(summary, body, 0.0);
+            }
+        }
+    }
+
+    if !resume.languages.is_empty() {
+        layout.section("Languages");
+        let languages: Vec<String> = resume
+            .languages
+            .iter()
+            .map(|l| match &l.fluency {
+                Some(fluency) => format!("{} ({})", l.language, fluency),
+                None => l.language.clone(),
+            })
+            .collect();
+        layout.paragraph(&languages.join(", "), body, 0.0);
+    }
+
+    if !resume.interests.is_empty() {
+        layout.section("Interests");
+        for interest in &resume.interests {
+            let text = if interest.keywords.is_empty() {
+                interest.name.clone()
+            } else {
+                format!("{}: {}", interest.name, interest.keywords.join(", "))
+            };
+            layout.paragraph(&text, body, 0.0);
+        }
+    }
+
+    layout
+}
+
+fn write_document(resume: &Resume, layout: Layout) -> Result<Vec<u8>> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+
+    let regular_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let bold_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica-Bold",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! {
+            REGULAR_FONT => regular_id,
+            BOLD_FONT => bold_id,
+        },
+    });
+
+    let mut kids: Vec<Object> = Vec::with_capacity(layout.pages.len());
+    for operations in layout.pages {
+        let content = Content { operations }
+            .encode()
+            .map_err(|e| anyhow::anyhow!("Failed to encode resume page: {}", e))?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id: ObjectId = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    let page_count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+        }),
+    );
+
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    let info_id = doc.add_object(dictionary! {
+        "Title" => Object::string_literal(encode_win_ansi(&format!("{} - Resume", resume.basics.name))),
+        "Author" => Object::string_literal(encode_win_ansi(&resume.basics.name)),
+        "Producer" => Object::string_literal("personal-site"),
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc.compress();
+
+    let mut output = Vec::new();
+    doc.save_to(&mut output)
+        .map_err(|e| anyhow::anyhow!("Failed to write resume PDF: {}", e))?;
+    Ok(output)
+}
+
+/// Greedy word wrap using the font metrics, hard breaking words longer than a line
+fn wrap_text(text: &str, style: TextStyle, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for source_line in text.lines() {
+        let mut current = String::new();
+        for word in source_line.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+
+            if style.text_width(&candidate) <= max_width {
+                current = candidate;
+                continue;
+            }
+
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+
+            // Break words that can't fit on a line by themselves (long URLs)
+            let mut chunk = String::new();
+            for c in word.chars() {
+                chunk.push(c);
+                if style.text_width(&chunk) > max_width {
+                    chunk.pop();
+                    lines.push(std::mem::take(&mut chunk));
+                    chunk.push(c);
+                }
+            }
+            current = chunk;
+        }
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Encode text for the standard fonts' WinAnsiEncoding
+/// Latin-1 maps directly; common typographic punctuation maps to its WinAnsi slot
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20AC}' => 0x80,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            c if (c as u32) < 0x20 => b' ',
+            c if (c as u32) <= 0xFF && !(0x7F..0xA0).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
@@ -15,29 +15,78 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::aws::AwsEndpointConfig;
 use anyhow::Result;
-use aws_sdk_s3::Client;
-use std::env;
+use aws_sdk_s3::{presigning::PresigningConfig, Client};
+use chrono::{DateTime, Utc};
+use std::{env, time::Duration};
+
+/// Setting key selecting how downloads are delivered, either globally (category "system")
+/// or per access code (category "access_code", entity_id = code id)
+pub const DOWNLOAD_MODE_SETTING_KEY: &str = "download_mode";
+
+/// Default lifetime of presigned download URLs
+const DEFAULT_PRESIGNED_URL_TTL_SECONDS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadMode {
+    /// Stream the file through the app (default)
+    Proxy,
+    /// Redirect to a short-lived presigned S3 GET URL
+    PresignedRedirect,
+}
+
+impl DownloadMode {
+    /// Parse a setting value, treating anything unrecognized as Proxy
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "presigned" | "redirect" => DownloadMode::PresignedRedirect,
+            _ => DownloadMode::Proxy,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct S3Service {
     client: Client,
     bucket_name: String,
+    presigned_url_ttl: Duration,
 }
 
 impl S3Service {
     pub async fn new() -> Result<Self> {
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .load()
-            .await;
-        let client = Client::new(&config);
+        let endpoint_config = AwsEndpointConfig::from_env("S3");
         let bucket_name =
             env::var("S3_BUCKET_NAME").unwrap_or_else(|_| "personal-site-resumes".to_string());
 
-        Ok(Self {
+        Ok(Self::from_client(
+            Self::build_client(&endpoint_config).await,
+            bucket_name,
+        ))
+    }
+
+    /// Build an S3 client honoring custom endpoint, region and path-style settings
+    pub async fn build_client(endpoint_config: &AwsEndpointConfig) -> Client {
+        let sdk_config = endpoint_config.load_sdk_config().await;
+        let s3_config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(endpoint_config.force_path_style)
+            .build();
+
+        Client::from_conf(s3_config)
+    }
+
+    /// Build the service around an existing client, e.g. one pointed at a local S3-compatible endpoint
+    pub fn from_client(client: Client, bucket_name: String) -> Self {
+        let presigned_url_ttl = env::var("S3_PRESIGNED_URL_TTL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_PRESIGNED_URL_TTL_SECONDS);
+
+        Self {
             client,
             bucket_name,
-        })
+            presigned_url_ttl: Duration::from_secs(presigned_url_ttl),
+        }
     }
 
     /// Fetch a file from S3 at path: {code}/{filename}
@@ -66,16 +115,185 @@ impl S3Service {
     pub async fn file_exists(&self, code: &str, filename: &str) -> bool {
         let key = format!("{}/{}", code, filename);
 
-        match self
-            .client
+        self.client
             .head_object()
             .bucket(&self.bucket_name)
             .key(&key)
             .send()
             .await
-        {
-            Ok(_) => true,
-            Err(_) => false,
+            .is_ok()
+    }
+
+    /// Create a short-lived presigned GET URL for {code}/{filename}
+    /// The content disposition is signed into the URL so S3 serves the file as a named download
+    pub async fn presigned_get_url(
+        &self,
+        code: &str,
+        filename: &str,
+        content_disposition: &str,
+    ) -> Result<String> {
+        let key = format!("{}/{}", code, filename);
+
+        let presigning_config = PresigningConfig::expires_in(self.presigned_url_ttl)
+            .map_err(|e| anyhow::anyhow!("Invalid presigned URL lifetime: {}", e))?;
+
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .response_content_disposition(content_disposition)
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to presign S3 request: {}", e))?;
+
+        tracing::info!(
+            "Presigned S3 download: bucket={}, key={}, expires_in={:?}",
+            self.bucket_name,
+            key,
+            self.presigned_url_ttl
+        );
+
+        Ok(request.uri().to_string())
+    }
+
+    /// List top-level prefixes in the bucket without the trailing slash, i.e. the per-code folders
+    pub async fn list_prefixes(&self) -> Result<Vec<String>> {
+        let mut prefixes = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket_name)
+            .delimiter("/")
+            .into_paginator()
+            .send();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| anyhow::anyhow!("Failed to list S3 prefixes: {}", e))?;
+            prefixes.extend(
+                page.common_prefixes()
+                    .iter()
+                    .filter_map(|p| p.prefix())
+                    .map(|p| p.trim_end_matches('/').to_string()),
+            );
         }
+
+        Ok(prefixes)
+    }
+
+    /// List every object key under {code}/
+    pub async fn list_files(&self, code: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket_name)
+            .prefix(format!("{}/", code))
+            .into_paginator()
+            .send();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| anyhow::anyhow!("Failed to list S3 objects: {}", e))?;
+            keys.extend(
+                page.contents()
+                    .iter()
+                    .filter_map(|object| object.key())
+                    .map(str::to_string),
+            );
+        }
+
+        Ok(keys)
+    }
+
+    /// When the newest object under {code}/ was written, None if there are no objects
+    pub async fn last_modified(&self, code: &str) -> Result<Option<DateTime<Utc>>> {
+        let mut newest = None;
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket_name)
+            .prefix(format!("{}/", code))
+            .into_paginator()
+            .send();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| anyhow::anyhow!("Failed to list S3 objects: {}", e))?;
+            let modified = page
+                .contents()
+                .iter()
+                .filter_map(|object| object.last_modified())
+                .filter_map(|at| DateTime::from_timestamp(at.secs(), at.subsec_nanos()));
+            newest = newest.into_iter().chain(modified).max();
+        }
+
+        Ok(newest)
+    }
+
+    /// List file names under {code}/ without the prefix, e.g. ["index.html", "Resume.pdf"]
+    pub async fn list_filenames(&self, code: &str) -> Result<Vec<String>> {
+        let prefix = format!("{}/", code);
+        Ok(self
+            .list_files(code)
+            .await?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&prefix).map(str::to_string))
+            .filter(|name| !name.is_empty() && !name.contains('/'))
+            .collect())
     }
+
+    /// Copy an object within the bucket
+    pub async fn copy_object(&self, source_key: &str, destination_key: &str) -> Result<()> {
+        tracing::info!(
+            "Copying S3 object: bucket={}, from={}, to={}",
+            self.bucket_name,
+            source_key,
+            destination_key
+        );
+
+        self.client
+            .copy_object()
+            .bucket(&self.bucket_name)
+            .copy_source(format!(
+                "{}/{}",
+                self.bucket_name,
+                encode_copy_source_key(source_key)
+            ))
+            .key(destination_key)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to copy S3 object {}: {}", source_key, e))?;
+
+        Ok(())
+    }
+
+    /// Delete an object by its full key
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        tracing::info!(
+            "Deleting S3 object: bucket={}, key={}",
+            self.bucket_name,
+            key
+        );
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to delete S3 object {}: {}", key, e))?;
+
+        Ok(())
+    }
+}
+
+/// Percent-encode a key for the CopySource header, keeping the path separators
+fn encode_copy_source_key(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
 }
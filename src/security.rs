@@ -62,6 +62,8 @@ impl Default for SecurityConfig {
 #[derive(Debug, Clone)]
 pub struct SecurityService {
     rate_limit_cache: Arc<DashMap<String, RateLimitEntry>>,
+    /// Per-IP counters for individual actions (e.g. contact form posts), keyed "action:ip"
+    action_limit_cache: Arc<DashMap<String, RateLimitEntry>>,
     pub config: SecurityConfig,
     db: DatabaseConnection,
 }
@@ -70,6 +72,7 @@ impl SecurityService {
     pub fn new(db: DatabaseConnection, config: Option<SecurityConfig>) -> Self {
         Self {
             rate_limit_cache: Arc::new(DashMap::new()),
+            action_limit_cache: Arc::new(DashMap::new()),
             config: config.unwrap_or_default(),
             db,
         }
@@ -133,6 +136,49 @@ impl SecurityService {
         Ok((!newly_blocked, newly_blocked))
     }
 
+    /// Throttle a specific action per IP, separate from the general request rate limit
+    /// Allows `limit` attempts per `window`, returns false once the limit is used up
+    pub fn check_action_limit(
+        &self,
+        ip: IpAddr,
+        action: &str,
+        limit: u32,
+        window: Duration,
+    ) -> bool {
+        let now = Utc::now();
+        let mut allowed = true;
+
+        self.action_limit_cache
+            .entry(format!("{}:{}", action, ip))
+            .and_modify(|entry| {
+                if now.signed_duration_since(entry.first_attempt) > window {
+                    entry.count = 0;
+                    entry.first_attempt = now;
+                }
+                entry.last_attempt = now;
+
+                if entry.count >= limit {
+                    allowed = false;
+                } else {
+                    entry.count += 1;
+                }
+            })
+            .or_insert_with(|| {
+                allowed = limit > 0;
+                RateLimitEntry {
+                    count: 1,
+                    first_attempt: now,
+                    last_attempt: now,
+                    blocked_until: None,
+                }
+            });
+
+        if !allowed {
+            tracing::info!("IP {} exceeded the {} limit", ip, action);
+        }
+        allowed
+    }
+
     pub async fn log_access_attempt(
         &self,
         ip: Option<IpAddr>,
@@ -249,6 +295,10 @@ impl SecurityService {
             );
         }
 
+        // Action limits use windows of up to a day
+        self.action_limit_cache
+            .retain(|_, entry| now.signed_duration_since(entry.last_attempt) < Duration::days(1));
+
         Ok(())
     }
 }
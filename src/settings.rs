@@ -17,18 +17,57 @@
 
 use anyhow::Result;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::entities::{setting, Setting};
 
+/// How long the system settings snapshot is reused, writes through this service refresh it
+/// right away and the expiry picks up changes made elsewhere
+const SYSTEM_SETTINGS_CACHE_SECONDS: u64 = 30;
+
+type SystemSettings = Arc<BTreeMap<String, String>>;
+
 #[derive(Debug, Clone)]
 pub struct SettingsService {
     db: DatabaseConnection,
+    system_cache: Arc<RwLock<Option<(Instant, SystemSettings)>>>,
 }
 
 impl SettingsService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            system_cache: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Site-wide settings (category "system", no entity) by key, as given to page templates
+    pub async fn system_settings(&self) -> Result<SystemSettings> {
+        if let Ok(cache) = self.system_cache.read() {
+            if let Some((loaded_at, settings)) = cache.as_ref() {
+                if loaded_at.elapsed() < Duration::from_secs(SYSTEM_SETTINGS_CACHE_SECONDS) {
+                    return Ok(settings.clone());
+                }
+            }
+        }
+
+        let settings: SystemSettings = Arc::new(
+            Setting::find()
+                .filter(setting::Column::Category.eq("system"))
+                .filter(setting::Column::EntityId.is_null())
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(|s| (s.key, s.value))
+                .collect(),
+        );
+        if let Ok(mut cache) = self.system_cache.write() {
+            *cache = Some((Instant::now(), settings.clone()));
+        }
+        Ok(settings)
     }
 
     /// Get a setting value by key, category, and optional entity_id
@@ -67,6 +106,35 @@ impl SettingsService {
         Ok(value.map(|v| v == "true").unwrap_or(false))
     }
 
+    /// Get a setting scoped to an entity, falling back to the global "system" value
+    /// e.g. a per access code override of a site-wide default
+    pub async fn get_with_fallback(
+        &self,
+        key: &str,
+        category: &str,
+        entity_id: Uuid,
+    ) -> Result<Option<String>> {
+        if let Some(value) = self.get(key, Some(category), Some(entity_id)).await? {
+            return Ok(Some(value));
+        }
+
+        self.get(key, Some("system"), None).await
+    }
+
+    /// Get all settings scoped to a single entity
+    pub async fn get_for_entity(
+        &self,
+        category: &str,
+        entity_id: Uuid,
+    ) -> Result<Vec<setting::Model>> {
+        let settings = Setting::find()
+            .filter(setting::Column::Category.eq(category))
+            .filter(setting::Column::EntityId.eq(entity_id))
+            .all(&self.db)
+            .await?;
+        Ok(settings)
+    }
+
     /// Set a setting value, creating it if it doesn't exist
     pub async fn set(
         &self,
@@ -112,6 +180,9 @@ impl SettingsService {
             new_setting.insert(&self.db).await?;
         }
 
+        if let Ok(mut cache) = self.system_cache.write() {
+            *cache = None;
+        }
         Ok(())
     }
 
@@ -0,0 +1,362 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Server-rendered page templates (landing page, admin SPA shell)
+// Templates are compiled once at startup and recompiled when their files change on disk
+
+use anyhow::Result;
+use minijinja::{context, Environment, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use std::{env, fs};
+
+use crate::locale;
+use crate::settings::SettingsService;
+
+pub const LANDING_TEMPLATE: &str = "landing.html";
+pub const ADMIN_SHELL_TEMPLATE: &str = "admin.html";
+
+/// Default polling interval for template changes
+const DEFAULT_RELOAD_INTERVAL_SECONDS: u64 = 2;
+
+/// A template file and whether the app can run without it
+/// The admin shell is produced by the frontend build and may not exist in development
+#[derive(Debug, Clone)]
+pub struct TemplateSource {
+    pub name: String,
+    pub path: PathBuf,
+    pub required: bool,
+    /// Also load locale variants next to the file, e.g. landing.de.html for landing.html
+    pub localized: bool,
+    /// Serve the file as is when it isn't a valid template instead of leaving it out
+    pub raw_fallback: bool,
+}
+
+impl TemplateSource {
+    pub fn new(name: &str, path: impl Into<PathBuf>, required: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.into(),
+            required,
+            localized: false,
+            raw_fallback: false,
+        }
+    }
+
+    pub fn localized(mut self) -> Self {
+        self.localized = true;
+        self
+    }
+
+    /// For bundled files like the admin shell, whose scripts can look like template syntax
+    pub fn raw_fallback(mut self) -> Self {
+        self.raw_fallback = true;
+        self
+    }
+
+    /// This source plus any locale variants currently on disk
+    fn expand(&self) -> Vec<TemplateSource> {
+        let mut sources = vec![self.clone()];
+        if !self.localized {
+            return sources;
+        }
+
+        let Some((stem, ext)) = self.name.rsplit_once('.') else {
+            return sources;
+        };
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let Some(file_stem) = self
+            .path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .and_then(|f| f.rsplit_once('.'))
+            .map(|(file_stem, _)| file_stem.to_string())
+        else {
+            return sources;
+        };
+
+        let files: Vec<String> = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut locales = locale::variant_locales(&files, &file_stem, ext);
+        locales.sort();
+        sources.extend(locales.into_iter().map(|locale| TemplateSource {
+            name: locale::variant_filename(stem, ext, &locale),
+            path: dir.join(locale::variant_filename(&file_stem, ext, &locale)),
+            required: false,
+            localized: false,
+            raw_fallback: false,
+        }));
+        sources
+    }
+}
+
+/// Values available to every template alongside `settings`
+#[derive(Debug, Clone, Default)]
+pub struct SiteConfig {
+    pub domain: Option<String>,
+    pub url: Option<String>,
+}
+
+impl SiteConfig {
+    pub fn from_env() -> Self {
+        let site = Self {
+            domain: env::var("SITE_DOMAIN").ok(),
+            url: env::var("SITE_URL").ok(),
+        };
+        if site.domain.is_none() || site.url.is_none() {
+            tracing::warn!(
+                "SITE_DOMAIN and SITE_URL should be set, templates will render them empty"
+            );
+        }
+        site
+    }
+}
+
+struct CompiledTemplates {
+    env: Environment<'static>,
+    /// Optional files that aren't valid templates, e.g. a frontend build containing `{{`,
+    /// served as they are
+    raw: BTreeMap<String, String>,
+    modified: BTreeMap<String, Option<SystemTime>>,
+}
+
+#[derive(Clone)]
+pub struct TemplateService {
+    sources: Arc<Vec<TemplateSource>>,
+    compiled: Arc<RwLock<CompiledTemplates>>,
+    site: SiteConfig,
+    settings: SettingsService,
+}
+
+impl TemplateService {
+    /// Compile all templates, failing if a required one is missing or invalid
+    pub fn new(
+        sources: Vec<TemplateSource>,
+        site: SiteConfig,
+        settings: SettingsService,
+    ) -> Result<Self> {
+        let compiled = compile(&sources)?;
+        tracing::info!(
+            "Compiled {} page templates",
+            compiled.modified.values().filter(|m| m.is_some()).count()
+        );
+
+        Ok(Self {
+            sources: Arc::new(sources),
+            compiled: Arc::new(RwLock::new(compiled)),
+            site,
+            settings,
+        })
+    }
+
+    /// Landing page and admin shell from their standard locations
+    pub fn from_env(settings: SettingsService) -> Result<Self> {
+        Self::new(
+            vec![
+                TemplateSource::new(LANDING_TEMPLATE, "landing.html", true).localized(),
+                TemplateSource::new(ADMIN_SHELL_TEMPLATE, "admin-assets/index.html", false)
+                    .raw_fallback(),
+            ],
+            SiteConfig::from_env(),
+            settings,
+        )
+    }
+
+    /// Render a template with the site config and system settings in context
+    /// Returns None if the template file doesn't exist
+    pub async fn render(&self, name: &str) -> Result<Option<String>> {
+        self.render_with(name, context! {}).await
+    }
+
+    /// Render with additional page-specific values, e.g. form errors
+    pub async fn render_with(&self, name: &str, extra: Value) -> Result<Option<String>> {
+        let settings = self.settings.system_settings().await?;
+
+        // Site values come from deployment config and are inserted verbatim like the original
+        // placeholders, settings are admin-editable so they stay auto-escaped
+        let domain = Value::from_safe_string(self.site.domain.clone().unwrap_or_default());
+        let url = Value::from_safe_string(self.site.url.clone().unwrap_or_default());
+        let ctx = context! {
+            // Upper case names are kept for the original {{SITE_DOMAIN}} placeholders
+            SITE_DOMAIN => domain.clone(),
+            SITE_URL => url.clone(),
+            site => context! { domain => domain, url => url },
+            settings => Value::from_serialize(&*settings),
+            ..extra
+        };
+
+        let compiled = self
+            .compiled
+            .read()
+            .map_err(|_| anyhow::anyhow!("Template lock poisoned"))?;
+        if let Some(html) = compiled.raw.get(name) {
+            return Ok(Some(html.clone()));
+        }
+        let template = match compiled.env.get_template(name) {
+            Ok(template) => template,
+            Err(e) if e.kind() == minijinja::ErrorKind::TemplateNotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let html = template
+            .render(ctx)
+            .map_err(|e| anyhow::anyhow!("Failed to render {}: {}", name, e))?;
+        Ok(Some(html))
+    }
+
+    pub fn site(&self) -> &SiteConfig {
+        &self.site
+    }
+
+    /// Locales with a variant of the given template, e.g. ["de", "fr"] for landing.html
+    pub fn locales(&self, name: &str) -> Vec<String> {
+        let Some((stem, ext)) = name.rsplit_once('.') else {
+            return Vec::new();
+        };
+        let Ok(compiled) = self.compiled.read() else {
+            return Vec::new();
+        };
+
+        let loaded: Vec<String> = compiled
+            .modified
+            .keys()
+            .filter(|name| compiled.env.get_template(name).is_ok())
+            .cloned()
+            .collect();
+        locale::variant_locales(&loaded, stem, ext)
+    }
+
+    /// Recompile templates if any file was added, removed or modified since the last compile
+    /// A broken edit keeps the previous version live and logs the error
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        let current: BTreeMap<String, Option<SystemTime>> = self
+            .sources
+            .iter()
+            .flat_map(TemplateSource::expand)
+            .map(|source| (source.name.clone(), modified_time(&source.path)))
+            .collect();
+
+        let changed = {
+            let compiled = self
+                .compiled
+                .read()
+                .map_err(|_| anyhow::anyhow!("Template lock poisoned"))?;
+            compiled.modified != current
+        };
+        if !changed {
+            return Ok(false);
+        }
+
+        let recompiled = compile(&self.sources)?;
+        *self
+            .compiled
+            .write()
+            .map_err(|_| anyhow::anyhow!("Template lock poisoned"))? = recompiled;
+
+        tracing::info!("Page templates changed on disk, reloaded");
+        Ok(true)
+    }
+
+    /// Poll template files for changes in the background
+    /// TEMPLATE_RELOAD_INTERVAL_SECONDS controls the interval, 0 disables reloading
+    pub fn spawn_hot_reload(&self) {
+        let interval_seconds = env::var("TEMPLATE_RELOAD_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_RELOAD_INTERVAL_SECONDS);
+        if interval_seconds == 0 {
+            tracing::info!("Template hot reload disabled");
+            return;
+        }
+
+        let templates = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_seconds));
+            loop {
+                interval.tick().await;
+                if let Err(e) = templates.reload_if_changed() {
+                    tracing::error!(
+                        "Failed to reload templates, keeping previous version: {}",
+                        e
+                    );
+                }
+            }
+        });
+    }
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn compile(sources: &[TemplateSource]) -> Result<CompiledTemplates> {
+    let mut env = Environment::new();
+    let mut raw = BTreeMap::new();
+    let mut modified = BTreeMap::new();
+
+    for source in sources.iter().flat_map(TemplateSource::expand) {
+        // Read the timestamp first so a write during the read triggers another reload
+        let mtime = modified_time(&source.path);
+        match fs::read_to_string(&source.path) {
+            Ok(content) => {
+                match env.add_template_owned(source.name.clone(), content.clone()) {
+                    Ok(()) => {}
+                    Err(e) if source.required => {
+                        anyhow::bail!("Invalid template {}: {}", source.name, e);
+                    }
+                    Err(e) if source.raw_fallback => {
+                        tracing::warn!(
+                            "{} is not a valid template, serving it unrendered: {}",
+                            source.path.display(),
+                            e
+                        );
+                        raw.insert(source.name.clone(), content);
+                    }
+                    // A broken locale variant is left out so the default page is served instead
+                    Err(e) => {
+                        tracing::error!(
+                            "{} is not a valid template, skipping it: {}",
+                            source.path.display(),
+                            e
+                        );
+                    }
+                }
+                modified.insert(source.name.clone(), mtime);
+            }
+            Err(e) if source.required => {
+                anyhow::bail!("Failed to read template {}: {}", source.path.display(), e);
+            }
+            Err(_) => {
+                modified.insert(source.name.clone(), None);
+            }
+        }
+    }
+
+    Ok(CompiledTemplates { env, raw, modified })
+}
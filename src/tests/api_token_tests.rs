@@ -0,0 +1,202 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::access_logs::{access_log_routes, AccessLogState};
+use crate::admin::api_tokens::{API_TOKEN_PREFIX, MAX_API_TOKEN_DAYS};
+use crate::admin::roles::{Permission, Role};
+use crate::admin::AdminAuthBackend;
+use crate::entities::{admin_api_token, admin_user, AdminApiToken};
+use crate::middleware::require_admin_auth;
+use crate::tests::{create_verified_admin, test_backend};
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    middleware::from_fn,
+    Router,
+};
+use axum_login::AuthManagerLayerBuilder;
+use chrono::{Duration, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serial_test::serial;
+use tower::ServiceExt;
+use tower_sessions::{MemoryStore, SessionManagerLayer};
+
+const PASSWORD: &str = "amber falcon quiet harbor";
+
+async fn setup() -> (AdminAuthBackend, DatabaseConnection, admin_user::Model) {
+    let (backend, db) = test_backend().await;
+    let owner = create_verified_admin(&backend, "owner@example.com", PASSWORD).await;
+    (backend, db, owner)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_token_creation_is_validated() {
+    let (backend, _db, owner) = setup().await;
+
+    assert!(backend
+        .create_api_token(owner.id, "ci", &["codes:delete"], 30)
+        .await
+        .is_err());
+    assert!(backend
+        .create_api_token::<&str>(owner.id, "ci", &[], 30)
+        .await
+        .is_err());
+    assert!(backend
+        .create_api_token(owner.id, "  ", &["codes:read"], 30)
+        .await
+        .is_err());
+    assert!(backend
+        .create_api_token(owner.id, "ci", &["codes:read"], 0)
+        .await
+        .is_err());
+    assert!(backend
+        .create_api_token(owner.id, "ci", &["codes:read"], MAX_API_TOKEN_DAYS + 1)
+        .await
+        .is_err());
+
+    // Tokens can't do more than their admin
+    let viewer = create_verified_admin(&backend, "viewer@example.com", PASSWORD).await;
+    assert_eq!(viewer.role, Role::Viewer.as_str());
+    assert!(backend
+        .create_api_token(viewer.id, "ci", &["codes:write"], 30)
+        .await
+        .is_err());
+
+    let (token, secret) = backend
+        .create_api_token(
+            owner.id,
+            " ci ",
+            &["codes:write", "logs:read", "codes:write"],
+            30,
+        )
+        .await
+        .unwrap();
+    assert_eq!(token.name, "ci");
+    assert_eq!(token.scopes, "codes:write logs:read");
+    assert!(secret.starts_with(API_TOKEN_PREFIX));
+    assert!(secret.starts_with(&token.token_prefix));
+    assert_ne!(token.token_hash, secret, "only the token hash is stored");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_token_authentication() {
+    let (backend, db, owner) = setup().await;
+    let (token, secret) = backend
+        .create_api_token(owner.id, "ci", &["codes:write"], 30)
+        .await
+        .unwrap();
+
+    let (user, scopes) = backend
+        .authenticate_api_token(&secret)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.id, owner.id);
+    assert!(scopes.allows(Permission::ManageAccessCodes));
+    assert!(!scopes.allows(Permission::ViewAccessCodes));
+    let used = AdminApiToken::find_by_id(token.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(used.last_used_at.is_some());
+
+    assert!(backend
+        .authenticate_api_token(&secret[API_TOKEN_PREFIX.len()..])
+        .await
+        .unwrap()
+        .is_none());
+
+    // Disabled admins' tokens stop working with them
+    let other = create_verified_admin(&backend, "other@example.com", PASSWORD).await;
+    backend.set_admin_role(other.id, Role::Owner).await.unwrap();
+    backend.set_admin_disabled(owner.id, true).await.unwrap();
+    assert!(backend
+        .authenticate_api_token(&secret)
+        .await
+        .unwrap()
+        .is_none());
+    backend.set_admin_disabled(owner.id, false).await.unwrap();
+
+    let mut expired: admin_api_token::ActiveModel = used.into();
+    expired.expires_at = Set((Utc::now() - Duration::minutes(1)).into());
+    expired.update(&db).await.unwrap();
+    assert!(backend
+        .authenticate_api_token(&secret)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(backend.revoke_api_token(other.id, token.id).await.is_err());
+    backend.revoke_api_token(owner.id, token.id).await.unwrap();
+    assert!(backend.list_api_tokens(owner.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_bearer_tokens_are_limited_to_their_scopes() {
+    let (backend, db, owner) = setup().await;
+    let (_, read_only) = backend
+        .create_api_token(owner.id, "reader", &["logs:read"], 30)
+        .await
+        .unwrap();
+    let (_, clearer) = backend
+        .create_api_token(owner.id, "cleaner", &["logs:write"], 30)
+        .await
+        .unwrap();
+
+    let auth_layer =
+        AuthManagerLayerBuilder::new(backend, SessionManagerLayer::new(MemoryStore::default()))
+            .build();
+    let app: Router = access_log_routes()
+        .with_state(AccessLogState { db })
+        .layer(from_fn(require_admin_auth))
+        .layer(auth_layer);
+
+    let status = |method: Method, token: Option<&str>| {
+        let app = app.clone();
+        let mut request = Request::builder()
+            .method(method)
+            .uri("/api/admin/access-logs");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        async move {
+            app.oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    assert_eq!(status(Method::GET, None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        status(Method::GET, Some("pat_nonsense")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(status(Method::GET, Some(&read_only)).await, StatusCode::OK);
+    assert_eq!(
+        status(Method::DELETE, Some(&read_only)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(Method::GET, Some(&clearer)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert!(status(Method::DELETE, Some(&clearer)).await.is_success());
+}
@@ -0,0 +1,105 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::aws::AwsEndpointConfig;
+use crate::s3::S3Service;
+use serial_test::serial;
+use std::collections::HashMap;
+
+fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+#[test]
+fn test_endpoint_config_defaults() {
+    let config = AwsEndpointConfig::from_lookup("S3", lookup(&[]));
+    assert_eq!(config, AwsEndpointConfig::default());
+}
+
+#[test]
+fn test_endpoint_config_service_overrides() {
+    let config = AwsEndpointConfig::from_lookup(
+        "S3",
+        lookup(&[
+            ("S3_ENDPOINT_URL", "http://localhost:9000"),
+            ("S3_REGION", "us-east-1"),
+            ("S3_FORCE_PATH_STYLE", "TRUE"),
+            ("AWS_REGION", "us-east-2"),
+            ("SES_REGION", "eu-west-1"),
+        ]),
+    );
+
+    assert_eq!(
+        config.endpoint_url.as_deref(),
+        Some("http://localhost:9000")
+    );
+    assert_eq!(config.region.as_deref(), Some("us-east-1"));
+    assert!(config.force_path_style);
+}
+
+#[test]
+fn test_endpoint_config_region_fallback() {
+    let config = AwsEndpointConfig::from_lookup(
+        "SES",
+        lookup(&[
+            ("SES_REGION", "  "),
+            ("SES_FORCE_PATH_STYLE", "no"),
+            ("AWS_REGION", "us-east-2"),
+        ]),
+    );
+
+    assert_eq!(config.endpoint_url, None);
+    assert_eq!(config.region.as_deref(), Some("us-east-2"));
+    assert!(!config.force_path_style);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_s3_client_uses_custom_endpoint() {
+    std::env::set_var("AWS_ACCESS_KEY_ID", "minioadmin");
+    std::env::set_var("AWS_SECRET_ACCESS_KEY", "minioadmin");
+
+    let config = AwsEndpointConfig {
+        endpoint_url: Some("http://localhost:9000".to_string()),
+        region: Some("us-east-1".to_string()),
+        force_path_style: true,
+    };
+    let client = S3Service::build_client(&config).await;
+    assert_eq!(
+        client.config().region().map(|r| r.as_ref()),
+        Some("us-east-1")
+    );
+
+    let s3 = S3Service::from_client(client, "test-bucket".to_string());
+    let url = s3
+        .presigned_get_url("ABC123", "Resume.pdf", "attachment")
+        .await
+        .expect("Presigning should not need network access");
+
+    std::env::remove_var("AWS_ACCESS_KEY_ID");
+    std::env::remove_var("AWS_SECRET_ACCESS_KEY");
+
+    assert!(
+        url.starts_with("http://localhost:9000/test-bucket/ABC123/Resume.pdf?"),
+        "Path-style URL expected, got: {}",
+        url
+    );
+}
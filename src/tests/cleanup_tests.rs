@@ -0,0 +1,152 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::cleanup::{plan_cleanup, reserved_archive_prefix, CleanupMode, CleanupReason};
+use crate::entities::access_code;
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn code(code: &str, expires_in: Option<Duration>) -> access_code::Model {
+    let now = Utc::now();
+    access_code::Model {
+        id: Uuid::new_v4(),
+        code: code.to_string(),
+        name: format!("Code {}", code),
+        expires_at: expires_in.map(|d| (now + d).into()),
+        created_at: now.into(),
+        created_by: Uuid::new_v4(),
+        usage_count: 0,
+    }
+}
+
+#[test]
+fn test_cleanup_mode_parse() {
+    assert_eq!(CleanupMode::parse("delete"), CleanupMode::Delete);
+    assert_eq!(CleanupMode::parse(" DELETE "), CleanupMode::Delete);
+    assert_eq!(CleanupMode::parse("archive"), CleanupMode::Archive);
+    assert_eq!(CleanupMode::parse("unknown"), CleanupMode::Archive);
+}
+
+#[test]
+fn test_plan_cleanup() {
+    let codes = vec![
+        code("active", None),
+        code("future", Some(Duration::days(30))),
+        code("recently-expired", Some(Duration::days(-2))),
+        code("long-expired", Some(Duration::days(-30))),
+    ];
+    let prefixes: Vec<String> = [
+        "active",
+        "future",
+        "recently-expired",
+        "long-expired",
+        "removed",
+        "_archive",
+        "_content",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect();
+
+    let last_uploaded = HashMap::from([("removed".to_string(), Utc::now() - Duration::days(30))]);
+    let reserved = vec!["_archive".to_string(), "_content".to_string()];
+    let plan = plan_cleanup(
+        &prefixes,
+        &codes,
+        &last_uploaded,
+        &reserved,
+        Utc::now(),
+        Duration::days(7),
+    );
+
+    assert_eq!(plan.len(), 2);
+    assert_eq!(plan[0].code, "long-expired");
+    assert_eq!(plan[0].reason, CleanupReason::Expired);
+    assert!(plan[0].expired_at.is_some());
+    assert_eq!(plan[1].code, "removed");
+    assert_eq!(plan[1].reason, CleanupReason::Deleted);
+    assert!(plan[1].expired_at.is_none());
+
+    // Without a grace period, anything past its expiry is eligible
+    let plan = plan_cleanup(
+        &prefixes,
+        &codes,
+        &last_uploaded,
+        &reserved,
+        Utc::now(),
+        Duration::zero(),
+    );
+    assert_eq!(plan.len(), 3);
+}
+
+#[test]
+fn test_plan_cleanup_with_nested_archive_prefix() {
+    let codes = vec![code("active", None)];
+    let prefixes: Vec<String> = ["active", "archive", "removed"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+    // Only the top segment is listed, so that is what has to be skipped
+    assert_eq!(reserved_archive_prefix("archive/docs"), "archive");
+    assert_eq!(reserved_archive_prefix("_archive"), "_archive");
+    let last_uploaded = HashMap::from([
+        ("archive".to_string(), Utc::now() - Duration::days(30)),
+        ("removed".to_string(), Utc::now() - Duration::days(30)),
+    ]);
+    let reserved = vec![reserved_archive_prefix("archive/docs")];
+    let plan = plan_cleanup(
+        &prefixes,
+        &codes,
+        &last_uploaded,
+        &reserved,
+        Utc::now(),
+        Duration::days(7),
+    );
+
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].code, "removed");
+}
+
+#[test]
+fn test_plan_cleanup_keeps_new_prefixes_without_a_code() {
+    let prefixes: Vec<String> = ["uploaded-early", "unknown-age", "stale"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+    let last_uploaded = HashMap::from([
+        (
+            "uploaded-early".to_string(),
+            Utc::now() - Duration::hours(1),
+        ),
+        ("stale".to_string(), Utc::now() - Duration::days(8)),
+    ]);
+
+    // Documents uploaded before their code is created wait out the grace period
+    let plan = plan_cleanup(
+        &prefixes,
+        &[],
+        &last_uploaded,
+        &[],
+        Utc::now(),
+        Duration::days(7),
+    );
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].code, "stale");
+    assert_eq!(plan[0].reason, CleanupReason::Deleted);
+}
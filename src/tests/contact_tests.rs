@@ -0,0 +1,110 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::contact::{is_plausible_email, ContactService, ContactSubmission, CONTACT_ACTION};
+use crate::email::EmailService;
+use crate::security::SecurityService;
+use crate::settings::SettingsService;
+use crate::tests::setup_test_db;
+use chrono::Duration;
+use serial_test::serial;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+#[test]
+fn test_contact_submission_parse() {
+    let submission =
+        ContactSubmission::parse("  Ada\n Lovelace ", " ada@example.com ", "  Hello!\n\n  ")
+            .expect("Should be valid");
+    assert_eq!(submission.name, "Ada Lovelace");
+    assert_eq!(submission.email, "ada@example.com");
+    assert_eq!(submission.message, "Hello!");
+
+    assert!(ContactSubmission::parse("", "ada@example.com", "Hi").is_err());
+    assert!(ContactSubmission::parse("Ada", "ada@example.com", "   ").is_err());
+    assert!(ContactSubmission::parse("Ada", "ada", "Hi").is_err());
+    assert!(ContactSubmission::parse(&"a".repeat(101), "ada@example.com", "Hi").is_err());
+    assert!(ContactSubmission::parse("Ada", "ada@example.com", &"a".repeat(5001)).is_err());
+}
+
+#[test]
+fn test_is_plausible_email() {
+    assert!(is_plausible_email("ada@example.com"));
+    assert!(is_plausible_email("ada+site@mail.example.co.uk"));
+    assert!(!is_plausible_email("ada@localhost"));
+    assert!(!is_plausible_email("@example.com"));
+    assert!(!is_plausible_email("ada@example.com."));
+    assert!(!is_plausible_email("ada @example.com"));
+    assert!(!is_plausible_email("ada@example.com\r\nBcc: x@example.com"));
+    assert!(!is_plausible_email("a@example.com,b@example.com"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_action_limit_is_per_ip_and_action() {
+    let db = setup_test_db().await;
+    let security = SecurityService::new(db, None);
+    let ip: IpAddr = "192.0.2.10".parse().unwrap();
+    let other_ip: IpAddr = "192.0.2.11".parse().unwrap();
+
+    for _ in 0..3 {
+        assert!(security.check_action_limit(ip, CONTACT_ACTION, 3, Duration::hours(1)));
+    }
+    assert!(!security.check_action_limit(ip, CONTACT_ACTION, 3, Duration::hours(1)));
+
+    // Other IPs and other actions have their own allowance
+    assert!(security.check_action_limit(other_ip, CONTACT_ACTION, 3, Duration::hours(1)));
+    assert!(security.check_action_limit(ip, "other", 3, Duration::hours(1)));
+
+    // An elapsed window starts counting again
+    assert!(security.check_action_limit(ip, CONTACT_ACTION, 3, Duration::zero()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_messages_stored_and_marked_read() {
+    let db = setup_test_db().await;
+    let settings = SettingsService::new(db.clone());
+    let email = Arc::new(EmailService::new().await.unwrap());
+    // No contact_email setting, so nothing is sent
+    let contact = ContactService::new(db, settings, email);
+
+    let submission = ContactSubmission::parse("Ada", "ada@example.com", "Hello").unwrap();
+    let ip: IpAddr = "192.0.2.10".parse().unwrap();
+    let first = contact
+        .submit(&submission, Some(ip), Some("Mozilla/5.0".to_string()))
+        .await
+        .unwrap();
+    assert!(!first.read);
+    assert!(!first.delivered);
+    assert_eq!(first.ip_address.as_deref(), Some("192.0.2.10"));
+
+    contact.submit(&submission, None, None).await.unwrap();
+
+    let (messages, total, total_pages) = contact.list(1, 1).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(total, 2);
+    assert_eq!(total_pages, 2);
+    assert_eq!(contact.unread_count().await.unwrap(), 2);
+
+    assert!(contact.set_read(first.id, true).await.unwrap());
+    assert_eq!(contact.unread_count().await.unwrap(), 1);
+    assert!(contact.set_read(first.id, false).await.unwrap());
+    assert_eq!(contact.unread_count().await.unwrap(), 2);
+
+    assert!(!contact.set_read(uuid::Uuid::new_v4(), true).await.unwrap());
+}
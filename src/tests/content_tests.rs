@@ -0,0 +1,229 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::content::{
+    feed, html, is_valid_slug, normalize_tag, parse_front_matter, parse_post, ContentService,
+    ContentSource,
+};
+use crate::s3::S3Service;
+use crate::tests::setup_test_db;
+use aws_sdk_s3::config::{BehaviorVersion, Region};
+use chrono::NaiveDate;
+use serial_test::serial;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Directory-backed content never touches storage, the client only has to exist
+fn unused_s3_service() -> S3Service {
+    let config = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-1"))
+        .build();
+    S3Service::from_client(
+        aws_sdk_s3::Client::from_conf(config),
+        "test-bucket".to_string(),
+    )
+}
+
+#[test]
+fn test_parse_front_matter() {
+    let source = "---\ntitle: \"Hello, world\"\ndate: 2025-01-20\ntags: [Rust, Web Dev]\ndraft: true\nsummary: First post\n---\n\n# Body\n";
+    let (front_matter, body) = parse_front_matter(source);
+
+    assert_eq!(front_matter.title.as_deref(), Some("Hello, world"));
+    assert_eq!(front_matter.date, NaiveDate::from_ymd_opt(2025, 1, 20));
+    assert_eq!(front_matter.tags, vec!["rust", "web-dev"]);
+    assert!(front_matter.draft);
+    assert_eq!(front_matter.summary.as_deref(), Some("First post"));
+    assert_eq!(body, "# Body\n");
+
+    // No front matter leaves the source untouched
+    let (front_matter, body) = parse_front_matter("Just text");
+    assert_eq!(front_matter.title, None);
+    assert_eq!(body, "Just text");
+}
+
+#[test]
+fn test_parse_post_renders_markdown() {
+    let post = parse_post("intro", "# Heading\n\n| a | b |\n|---|---|\n| 1 | 2 |\n");
+
+    assert_eq!(post.title, "intro");
+    assert!(!post.draft);
+    assert!(post.body_html.contains("<h1>Heading</h1>"));
+    assert!(post.body_html.contains("<table>"));
+}
+
+#[test]
+fn test_slug_and_tag_validation() {
+    assert!(is_valid_slug("hello-world_2"));
+    assert!(!is_valid_slug("Hello"));
+    assert!(!is_valid_slug("../etc"));
+    assert!(!is_valid_slug(""));
+
+    assert_eq!(normalize_tag(" Web Dev "), Some("web-dev".to_string()));
+    assert_eq!(normalize_tag("a/b"), None);
+    assert_eq!(normalize_tag("  "), None);
+}
+
+#[test]
+fn test_render_pages() {
+    let post = parse_post(
+        "hello",
+        "---\ntitle: <Hello>\ndate: 2025-01-20\ntags: rust\n---\nSome *text*\n",
+    );
+
+    let index = html::render_index(std::slice::from_ref(&post), Some("rust")).unwrap();
+    assert!(index.contains("/assets/styles.css"));
+    assert!(index.contains("/blog/hello"));
+    assert!(index.contains("&lt;Hello&gt;"));
+
+    let page = html::render_post(&post).unwrap();
+    assert!(page.contains("<em>text</em>"));
+    assert!(page.contains("/blog/tags/rust"));
+}
+
+#[test]
+fn test_updated_timestamp() {
+    let post = parse_post("a", "---\ndate: 2025-01-20\n---\n");
+    assert_eq!(
+        post.updated_at().unwrap().to_rfc3339(),
+        "2025-01-20T00:00:00+00:00"
+    );
+
+    let post = parse_post(
+        "b",
+        "---\ndate: 2025-01-20\nupdated: 2025-02-01T10:30:00+01:00\n---\n",
+    );
+    assert_eq!(
+        post.updated_at().unwrap().to_rfc3339(),
+        "2025-02-01T09:30:00+00:00"
+    );
+
+    assert!(parse_post("c", "No front matter").updated_at().is_none());
+}
+
+#[test]
+fn test_feeds_use_absolute_urls() {
+    let posts = vec![
+        parse_post(
+            "newer",
+            "---\ntitle: Tom & Jerry\ndate: 2025-01-20\nupdated: 2025-02-01\ntags: rust\n---\n<b>bold</b>\n",
+        ),
+        parse_post("older", "---\ntitle: Older\ndate: 2024-06-01\n---\nOld\n"),
+    ];
+
+    let atom = feed::render_atom(&posts, Some("https://example.com/"));
+    assert!(atom.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+    assert!(atom.contains("<id>https://example.com/blog/newer</id>"));
+    assert!(atom.contains("href=\"https://example.com/feed.xml\""));
+    // Feed updated is the newest entry
+    assert!(atom.contains("  <updated>2025-02-01T00:00:00Z</updated>"));
+    assert!(atom.contains("<published>2025-01-20T00:00:00Z</published>"));
+    assert!(atom.contains("<title>Tom &amp; Jerry</title>"));
+    assert!(atom.contains("&lt;b&gt;bold&lt;/b&gt;"));
+    assert!(atom.contains("<category term=\"rust\"/>"));
+
+    let rss = feed::render_rss(&posts, Some("https://example.com"));
+    assert!(rss.contains("<link>https://example.com/blog/older</link>"));
+    assert!(rss.contains("<lastBuildDate>Sat, 1 Feb 2025 00:00:00 +0000</lastBuildDate>"));
+    assert!(rss.contains("<pubDate>Sat, 1 Jun 2024 00:00:00 +0000</pubDate>"));
+
+    // Empty feeds are still valid documents
+    let empty = feed::render_atom(&[], None);
+    assert!(empty.contains("<updated>1970-01-01T00:00:00Z</updated>"));
+    assert!(!empty.contains("<entry>"));
+}
+
+#[test]
+fn test_feed_etag() {
+    let etag = feed::etag("<feed/>");
+    assert_eq!(etag, feed::etag("<feed/>"));
+    assert_ne!(etag, feed::etag("<feed></feed>"));
+    assert!(etag.starts_with('"') && etag.ends_with('"'));
+
+    assert!(feed::etag_matches(&etag, &etag));
+    assert!(feed::etag_matches(&format!("W/{}", etag), &etag));
+    assert!(feed::etag_matches(&format!("\"other\", {}", etag), &etag));
+    assert!(feed::etag_matches("*", &etag));
+    assert!(!feed::etag_matches("\"other\"", &etag));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_directory_posts_and_publishing() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    std::fs::write(
+        dir.path().join("older.md"),
+        "---\ntitle: Older\ndate: 2024-06-01\ntags: rust\n---\nOld\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("newer.md"),
+        "---\ntitle: Newer\ndate: 2025-01-20\ntags: [rust, web]\n---\nNew\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("wip.md"),
+        "---\ntitle: Work in progress\ndraft: true\n---\nSoon\n",
+    )
+    .unwrap();
+    // Ignored: not Markdown, or not a valid slug
+    std::fs::write(dir.path().join("notes.txt"), "skip").unwrap();
+    std::fs::write(dir.path().join("Bad Name.md"), "skip").unwrap();
+
+    let content = ContentService::new(
+        ContentSource::Directory(dir.path().to_path_buf()),
+        unused_s3_service(),
+        db,
+    )
+    .with_cache_ttl(Duration::ZERO);
+
+    let published = content.published_posts(None).await.unwrap();
+    let slugs: Vec<&str> = published.iter().map(|p| p.slug.as_str()).collect();
+    assert_eq!(slugs, vec!["newer", "older"]);
+
+    let web = content.published_posts(Some("web")).await.unwrap();
+    assert_eq!(web.len(), 1);
+    assert_eq!(web[0].slug, "newer");
+
+    assert!(content.published_post("wip").await.unwrap().is_none());
+
+    // Publishing overrides the draft flag, unpublishing hides a post
+    let admin_id = Uuid::new_v4();
+    assert!(content.set_published("wip", true, admin_id).await.unwrap());
+    assert!(content
+        .set_published("older", false, admin_id)
+        .await
+        .unwrap());
+    assert!(!content
+        .set_published("missing", true, admin_id)
+        .await
+        .unwrap());
+
+    assert!(content.published_post("wip").await.unwrap().is_some());
+    assert!(content.published_post("older").await.unwrap().is_none());
+
+    let statuses = content.post_statuses().await.unwrap();
+    assert_eq!(statuses.len(), 3);
+    let older = statuses.iter().find(|s| s.slug == "older").unwrap();
+    assert!(!older.published);
+    assert_eq!(older.published_override, Some(false));
+    let newer = statuses.iter().find(|s| s.slug == "newer").unwrap();
+    assert!(newer.published);
+    assert_eq!(newer.published_override, None);
+}
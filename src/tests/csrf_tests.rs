@@ -0,0 +1,69 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::csrf::{self, CSRF_COOKIE_NAME};
+use axum::http::{header, HeaderMap, HeaderValue};
+
+fn headers_with_cookie(cookie: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, HeaderValue::from_str(cookie).unwrap());
+    headers
+}
+
+#[test]
+fn test_generate_token() {
+    let token = csrf::generate_token();
+    assert_eq!(token.len(), 64);
+    assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(token, csrf::generate_token());
+}
+
+#[test]
+fn test_token_from_headers() {
+    let token = csrf::generate_token();
+    let headers = headers_with_cookie(&format!(
+        "theme=dark; {}={}; other=1",
+        CSRF_COOKIE_NAME, token
+    ));
+    assert_eq!(csrf::token_from_headers(&headers), Some(token.clone()));
+    assert_eq!(csrf::token_for_form(&headers), token);
+
+    // Malformed cookie values are ignored and a fresh token is issued
+    let headers = headers_with_cookie(&format!("{}=not-a-token", CSRF_COOKIE_NAME));
+    assert_eq!(csrf::token_from_headers(&headers), None);
+    assert_eq!(csrf::token_for_form(&headers).len(), 64);
+}
+
+#[test]
+fn test_verify() {
+    let token = csrf::generate_token();
+    let headers = headers_with_cookie(&format!("{}={}", CSRF_COOKIE_NAME, token));
+
+    assert!(csrf::verify(&headers, &token));
+    assert!(!csrf::verify(&headers, &csrf::generate_token()));
+    assert!(!csrf::verify(&headers, ""));
+    assert!(!csrf::verify(&HeaderMap::new(), &token));
+}
+
+#[test]
+fn test_cookie_header() {
+    let cookie = csrf::cookie_header("abc", "/access");
+    assert!(cookie.starts_with(&format!(
+        "{}=abc; Path=/access; HttpOnly; SameSite=Strict",
+        CSRF_COOKIE_NAME
+    )));
+}
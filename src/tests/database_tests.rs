@@ -81,7 +81,7 @@ async fn test_access_log_query_by_ip() {
             ip_address: Set(Some(test_ip_hash.to_string())),
             user_agent: Set(Some("test-user-agent".to_string())),
 
-            count: Set(Some(i as i32)),
+            count: Set(Some(i)),
             last_access_time: Set(Some(Utc::now().into())),
             last_delta_access: Set(Some(i as i64 * 1000)),
             action: Set("view".to_string()),
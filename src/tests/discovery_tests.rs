@@ -0,0 +1,124 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::content::parse_post;
+use crate::discovery::{
+    render_robots, render_sitemap, SecurityTxt, SECURITY_CONTACT_SETTING_KEY,
+    SECURITY_EXPIRES_SETTING_KEY,
+};
+use crate::settings::SettingsService;
+use crate::tests::setup_test_db;
+use serial_test::serial;
+
+#[test]
+fn test_robots_disallows_private_paths() {
+    let robots = render_robots(Some("https://example.com/"));
+
+    assert!(robots.starts_with("User-agent: *\n"));
+    for path in ["/access", "/resume", "/admin"] {
+        assert!(robots.contains(&format!("Disallow: {}\n", path)));
+    }
+    assert!(robots.contains("Sitemap: https://example.com/sitemap.xml"));
+
+    // Without SITE_URL there is no absolute sitemap URL to advertise
+    assert!(!render_robots(None).contains("Sitemap:"));
+}
+
+#[test]
+fn test_sitemap_lists_public_pages() {
+    let posts = vec![
+        parse_post(
+            "newer",
+            "---\ndate: 2025-01-20\nupdated: 2025-02-01\ntags: [rust, web]\n---\n",
+        ),
+        parse_post("older", "---\ndate: 2024-06-01\ntags: rust\n---\n"),
+    ];
+    let sitemap = render_sitemap(Some("https://example.com"), &posts);
+
+    assert!(sitemap.contains("<loc>https://example.com/</loc>"));
+    assert!(
+        sitemap.contains("<loc>https://example.com/blog</loc>\n    <lastmod>2025-02-01</lastmod>")
+    );
+    assert!(sitemap
+        .contains("<loc>https://example.com/blog/older</loc>\n    <lastmod>2024-06-01</lastmod>"));
+    assert!(sitemap.contains(
+        "<loc>https://example.com/blog/tags/rust</loc>\n    <lastmod>2025-02-01</lastmod>"
+    ));
+    assert!(sitemap.contains("<loc>https://example.com/blog/tags/web</loc>"));
+    assert!(!sitemap.contains("/access"));
+    assert!(!sitemap.contains("/resume"));
+    assert!(!sitemap.contains("/admin"));
+}
+
+#[test]
+fn test_security_txt_parse() {
+    let txt = SecurityTxt::parse(
+        "security@example.com, https://example.com/report",
+        "2026-01-01",
+        Some("https://example.com"),
+    )
+    .expect("Should parse");
+
+    assert_eq!(
+        txt.render(),
+        "Contact: mailto:security@example.com\n\
+         Contact: https://example.com/report\n\
+         Expires: 2026-01-01T00:00:00Z\n\
+         Canonical: https://example.com/.well-known/security.txt\n"
+    );
+
+    assert!(SecurityTxt::parse(" , ", "2026-01-01", None).is_none());
+    assert!(SecurityTxt::parse("security@example.com", "next year", None).is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_security_txt_from_settings() {
+    let db = setup_test_db().await;
+    let settings = SettingsService::new(db);
+
+    assert!(SecurityTxt::load(&settings, None).await.unwrap().is_none());
+
+    settings
+        .set(
+            SECURITY_CONTACT_SETTING_KEY,
+            "mailto:security@example.com",
+            Some("system"),
+            None,
+        )
+        .await
+        .unwrap();
+    // A contact alone is not enough, Expires is required
+    assert!(SecurityTxt::load(&settings, None).await.unwrap().is_none());
+
+    settings
+        .set(
+            SECURITY_EXPIRES_SETTING_KEY,
+            "2026-06-30T12:00:00Z",
+            Some("system"),
+            None,
+        )
+        .await
+        .unwrap();
+    let txt = SecurityTxt::load(&settings, None)
+        .await
+        .unwrap()
+        .expect("Should be configured");
+    assert_eq!(txt.contacts, vec!["mailto:security@example.com"]);
+    assert_eq!(txt.canonical, None);
+    assert!(txt.render().contains("Expires: 2026-06-30T12:00:00Z\n"));
+}
@@ -0,0 +1,120 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::engagement::{inject_beacon_script, is_bot, BeaconPayload, EngagementService};
+use crate::tests::setup_test_db;
+use serial_test::serial;
+use uuid::Uuid;
+
+#[test]
+fn test_inject_beacon_script_before_body_close() {
+    let html = "<html><body><p>Resume</p></BODY></html>";
+    let injected = inject_beacon_script(html);
+
+    let script_at = injected
+        .find("<script>")
+        .expect("Script should be injected");
+    let body_close_at = injected.find("</BODY>").expect("Body close should remain");
+    assert!(script_at < body_close_at);
+    assert!(injected.starts_with("<html><body><p>Resume</p>"));
+    assert!(injected.ends_with("</BODY></html>"));
+
+    // Fragments without a body tag get the script appended
+    let fragment = inject_beacon_script("<p>Resume</p>");
+    assert!(fragment.starts_with("<p>Resume</p><script>"));
+}
+
+#[test]
+fn test_is_bot() {
+    assert!(is_bot(None));
+    assert!(is_bot(Some("")));
+    assert!(is_bot(Some("Mozilla/5.0 (compatible; Googlebot/2.1)")));
+    assert!(is_bot(Some("Slackbot-LinkExpanding 1.0")));
+    assert!(is_bot(Some(
+        "Mozilla/5.0 (X11; Linux x86_64) HeadlessChrome/120.0"
+    )));
+    assert!(is_bot(Some("curl/8.4.0")));
+    assert!(!is_bot(Some(
+        "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_0) AppleWebKit/605.1.15 Safari/605.1.15"
+    )));
+}
+
+#[test]
+fn test_beacon_payload_parse() {
+    let payload =
+        BeaconPayload::parse(br#"{"view_id":"a1b2-c3","time_on_page_ms":-5,"scroll_depth":250}"#)
+            .expect("Valid payload should parse");
+    assert_eq!(payload.view_id, "a1b2-c3");
+    assert_eq!(payload.time_on_page_ms, 0);
+    assert_eq!(payload.scroll_depth, 100);
+
+    assert!(BeaconPayload::parse(b"not json").is_none());
+    assert!(BeaconPayload::parse(
+        br#"{"view_id":"<script>","time_on_page_ms":1,"scroll_depth":1}"#
+    )
+    .is_none());
+    assert!(BeaconPayload::parse(&vec![b' '; 2048]).is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_record_beacon_and_summary() {
+    let db = setup_test_db().await;
+    let engagement = EngagementService::new(db.clone());
+    let code_id = Uuid::new_v4();
+
+    let beacon = |view_id: &str, time_on_page_ms: i64, scroll_depth: i32| BeaconPayload {
+        view_id: view_id.to_string(),
+        time_on_page_ms,
+        scroll_depth,
+    };
+
+    // Same view reported twice keeps the highest values
+    engagement
+        .record_beacon(code_id, &beacon("view-1", 30_000, 95), None, None)
+        .await
+        .expect("Should record beacon");
+    engagement
+        .record_beacon(code_id, &beacon("view-1", 60_000, 40), None, None)
+        .await
+        .expect("Should update beacon");
+    engagement
+        .record_beacon(code_id, &beacon("view-2", 20_000, 25), None, None)
+        .await
+        .expect("Should record beacon");
+
+    // A view id from another code must not touch this code's data
+    engagement
+        .record_beacon(Uuid::new_v4(), &beacon("view-2", 999_000, 100), None, None)
+        .await
+        .expect("Should ignore reused view id");
+
+    let summary = engagement.summary(code_id).await.expect("Should summarize");
+    assert_eq!(summary.tracked_views, 2);
+    assert_eq!(summary.average_time_on_page_seconds, 40.0);
+    assert_eq!(summary.average_scroll_depth, 60.0);
+    assert_eq!(summary.read_through_views, 1);
+    assert_eq!(summary.recent_views.len(), 2);
+    assert!(summary.last_engaged_at.is_some());
+
+    let empty = engagement
+        .summary(Uuid::new_v4())
+        .await
+        .expect("Should summarize");
+    assert_eq!(empty.tracked_views, 0);
+    assert_eq!(empty.average_time_on_page_seconds, 0.0);
+}
@@ -0,0 +1,137 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::Credentials;
+use crate::entities::{admin_invite, AdminInvite};
+use crate::tests::{create_verified_admin, test_backend};
+use axum_login::AuthnBackend;
+use chrono::{Duration, Utc};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use serial_test::serial;
+
+const INVITEE: &str = "guest@elsewhere.org";
+const PASSWORD: &str = "amber falcon quiet harbor";
+const INVITER_PASSWORD: &str = "plum kettle orbit lantern";
+
+#[tokio::test]
+#[serial]
+async fn test_invite_creates_verified_admin_from_any_domain() {
+    let (backend, _db) = test_backend().await;
+    let inviter = create_verified_admin(&backend, "admin@example.com", INVITER_PASSWORD).await;
+
+    let (invite, token) = backend
+        .create_invite(inviter.id, " Guest@Elsewhere.org ")
+        .await
+        .unwrap();
+    assert_eq!(invite.email, INVITEE);
+    assert_eq!(invite.invited_by, Some(inviter.id));
+    assert_ne!(invite.token_hash, token, "only the token hash is stored");
+    assert_eq!(backend.find_invite(&token).await.unwrap().id, invite.id);
+
+    assert!(backend
+        .accept_invite(&token, "someone@elsewhere.org", PASSWORD)
+        .await
+        .is_err());
+    assert!(backend
+        .accept_invite(&token, INVITEE, "short")
+        .await
+        .is_err());
+
+    let admin = backend
+        .accept_invite(&token, INVITEE, PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(admin.email, INVITEE);
+    assert!(admin.email_verified);
+
+    let user = backend
+        .authenticate(Credentials::Password {
+            email: INVITEE.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(user.is_some());
+
+    // The link only works once
+    assert!(backend.find_invite(&token).await.is_err());
+    assert!(backend
+        .accept_invite(&token, INVITEE, PASSWORD)
+        .await
+        .is_err());
+
+    let invites = backend.list_invites().await.unwrap();
+    assert_eq!(invites.len(), 1);
+    assert!(invites[0].accepted_at.is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_expired_and_revoked_invites_are_rejected() {
+    let (backend, db) = test_backend().await;
+    let inviter = create_verified_admin(&backend, "admin@example.com", INVITER_PASSWORD).await;
+
+    let (invite, token) = backend.create_invite(inviter.id, INVITEE).await.unwrap();
+    let mut expired: admin_invite::ActiveModel = invite.into();
+    expired.expires_at = Set((Utc::now() - Duration::minutes(1)).into());
+    expired.update(&db).await.unwrap();
+    assert!(backend
+        .accept_invite(&token, INVITEE, PASSWORD)
+        .await
+        .is_err());
+
+    // Re-inviting replaces the stale invite
+    let (invite, token) = backend.create_invite(inviter.id, INVITEE).await.unwrap();
+    assert_eq!(AdminInvite::find().all(&db).await.unwrap().len(), 1);
+
+    backend.revoke_invite(invite.id).await.unwrap();
+    assert!(backend.revoke_invite(invite.id).await.is_err());
+    assert!(backend
+        .accept_invite(&token, INVITEE, PASSWORD)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_invites_for_existing_admins_are_rejected() {
+    let (backend, _db) = test_backend().await;
+    let inviter = create_verified_admin(&backend, "admin@example.com", INVITER_PASSWORD).await;
+
+    assert!(backend
+        .create_invite(inviter.id, "admin@example.com")
+        .await
+        .is_err());
+    assert!(backend
+        .create_invite(inviter.id, "not-an-email")
+        .await
+        .is_err());
+
+    // An account created some other way after the invite was sent
+    let (_, token) = backend
+        .create_invite(inviter.id, "second@example.com")
+        .await
+        .unwrap();
+    backend
+        .create_admin("second@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(backend
+        .accept_invite(&token, "second@example.com", PASSWORD)
+        .await
+        .is_err());
+    assert!(backend.find_invite(&token).await.is_ok());
+}
@@ -0,0 +1,92 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::locale::{normalize_tag, parse_accept_language, variant_locales, LocalePreference};
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn test_normalize_tag() {
+    assert_eq!(normalize_tag("de_DE"), Some("de-de".to_string()));
+    assert_eq!(normalize_tag(" EN "), Some("en".to_string()));
+    assert_eq!(normalize_tag("../etc"), None);
+    assert_eq!(normalize_tag(""), None);
+    assert_eq!(normalize_tag("en--us"), None);
+}
+
+#[test]
+fn test_parse_accept_language() {
+    assert_eq!(
+        parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"),
+        strings(&["fr-ch", "fr", "en", "de"])
+    );
+    assert_eq!(
+        parse_accept_language("en;q=0.5, de, es;q=0"),
+        strings(&["de", "en"])
+    );
+    assert!(parse_accept_language("").is_empty());
+}
+
+#[test]
+fn test_choose_locale() {
+    let available = strings(&["de", "fr-ca"]);
+
+    // Accept-Language exact and primary language matches
+    let pref = LocalePreference::new(None, Some("de-AT, en;q=0.5"), None);
+    assert_eq!(pref.choose(&available), Some("de".to_string()));
+    let pref = LocalePreference::new(None, Some("fr"), None);
+    assert_eq!(pref.choose(&available), Some("fr-ca".to_string()));
+
+    // ?lang= wins over Accept-Language
+    let pref = LocalePreference::new(Some("fr-CA"), Some("de"), None);
+    assert_eq!(pref.choose(&available), Some("fr-ca".to_string()));
+
+    // The default locale is served by the unsuffixed file
+    let pref = LocalePreference::new(None, Some("en-GB, de;q=0.5"), None);
+    assert_eq!(pref.choose(&available), None);
+    assert_eq!(pref.default_locale(), "en");
+
+    // Unmatched requests fall back to the configured default
+    let pref = LocalePreference::new(None, Some("ja"), Some("de"));
+    assert_eq!(pref.choose(&available), Some("de".to_string()));
+}
+
+#[test]
+fn test_select_file() {
+    let files = strings(&["index.html", "index.de.html", "Resume.pdf", "notes.txt"]);
+    assert_eq!(variant_locales(&files, "index", "html"), strings(&["de"]));
+
+    let pref = LocalePreference::new(None, Some("de"), None);
+    assert_eq!(
+        pref.select_file(&files, "index", "html"),
+        Some(("index.de.html".to_string(), "de".to_string()))
+    );
+    assert_eq!(
+        pref.select_file(&files, "Resume", "pdf"),
+        Some(("Resume.pdf".to_string(), "en".to_string()))
+    );
+    assert_eq!(pref.select_file(&files, "resume", "json"), None);
+
+    // Only variants uploaded, no default file
+    let files = strings(&["index.fr.html"]);
+    assert_eq!(
+        pref.select_file(&files, "index", "html"),
+        Some(("index.fr.html".to_string(), "fr".to_string()))
+    );
+}
@@ -0,0 +1,184 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::lockout::{
+    lockout_duration, minutes_remaining, FAILURE_WINDOW_HOURS, LOCKOUT_THRESHOLD,
+};
+use crate::admin::{AdminAuthBackend, AdminUserAuth, Credentials};
+use crate::entities::{admin_user, AdminUser};
+use crate::tests::{admin_app, create_verified_admin, post_json, test_backend};
+use axum::http::StatusCode;
+use axum_login::AuthnBackend;
+use chrono::{Duration, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde_json::json;
+use serial_test::serial;
+use uuid::Uuid;
+
+const EMAIL: &str = "admin@example.com";
+const PASSWORD: &str = "plum kettle orbit lantern";
+
+async fn log_in(
+    backend: &AdminAuthBackend,
+    password: &str,
+) -> Result<Option<AdminUserAuth>, String> {
+    backend
+        .authenticate(Credentials::Password {
+            email: EMAIL.to_string(),
+            password: password.to_string(),
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn admin(db: &DatabaseConnection, id: Uuid) -> admin_user::Model {
+    AdminUser::find_by_id(id).one(db).await.unwrap().unwrap()
+}
+
+#[test]
+fn test_lockout_schedule() {
+    for failures in 0..LOCKOUT_THRESHOLD {
+        assert_eq!(lockout_duration(failures), None);
+    }
+    assert_eq!(
+        lockout_duration(LOCKOUT_THRESHOLD),
+        Some(Duration::minutes(1))
+    );
+    assert_eq!(
+        lockout_duration(LOCKOUT_THRESHOLD + 1),
+        Some(Duration::minutes(2))
+    );
+    assert_eq!(
+        lockout_duration(LOCKOUT_THRESHOLD + 2),
+        Some(Duration::minutes(4))
+    );
+    assert_eq!(
+        lockout_duration(LOCKOUT_THRESHOLD + 10),
+        Some(Duration::hours(1))
+    );
+    assert_eq!(lockout_duration(i32::MAX), Some(Duration::hours(1)));
+
+    assert_eq!(minutes_remaining(Duration::seconds(1)), 1);
+    assert_eq!(minutes_remaining(Duration::seconds(61)), 2);
+    assert_eq!(minutes_remaining(Duration::minutes(10)), 10);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_account_locks_after_repeated_failures() {
+    let (backend, db) = test_backend().await;
+    let id = create_verified_admin(&backend, EMAIL, PASSWORD).await.id;
+    let app = admin_app(backend.clone(), &db).await;
+
+    for _ in 1..LOCKOUT_THRESHOLD {
+        assert!(log_in(&backend, "wrong password guess")
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(
+        admin(&db, id).await.failed_login_attempts,
+        LOCKOUT_THRESHOLD - 1
+    );
+
+    // A login before the threshold clears the count
+    let response = post_json(
+        &app,
+        "/api/admin/login",
+        json!({ "email": EMAIL, "password": PASSWORD }),
+        &mut None,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(admin(&db, id).await.failed_login_attempts, 0);
+
+    for _ in 0..LOCKOUT_THRESHOLD {
+        assert!(log_in(&backend, "wrong password guess")
+            .await
+            .unwrap()
+            .is_none());
+    }
+    let locked = admin(&db, id).await;
+    assert!(locked.locked_until.unwrap().with_timezone(&Utc) > Utc::now());
+
+    // Even the right password is refused while locked, and it isn't counted
+    let error = log_in(&backend, PASSWORD).await.unwrap_err();
+    assert!(error.contains("temporarily locked"), "{}", error);
+    assert_eq!(
+        admin(&db, id).await.failed_login_attempts,
+        LOCKOUT_THRESHOLD
+    );
+    assert!(backend.ensure_login_allowed(id).await.is_err());
+
+    // Once the lock runs out the next failure locks for twice as long
+    let mut expired: admin_user::ActiveModel = locked.into();
+    expired.locked_until = Set(Some((Utc::now() - Duration::seconds(1)).into()));
+    expired.update(&db).await.unwrap();
+    let until = backend.record_failed_login(id).await.unwrap().unwrap();
+    assert!(until - Utc::now() > Duration::seconds(90));
+
+    // Resetting the password lifts the lock
+    let (_, token) = backend
+        .request_password_reset(EMAIL)
+        .await
+        .unwrap()
+        .unwrap();
+    backend
+        .reset_password(&token, "violet canyon drift signal")
+        .await
+        .unwrap();
+    assert!(log_in(&backend, "violet canyon drift signal")
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_old_failures_are_forgotten() {
+    let (backend, db) = test_backend().await;
+    let id = create_verified_admin(&backend, EMAIL, PASSWORD).await.id;
+
+    let mut stale: admin_user::ActiveModel = admin(&db, id).await.into();
+    stale.failed_login_attempts = Set(LOCKOUT_THRESHOLD - 1);
+    stale.last_failed_login_at = Set(Some(
+        (Utc::now() - Duration::hours(FAILURE_WINDOW_HOURS + 1)).into(),
+    ));
+    stale.update(&db).await.unwrap();
+
+    assert!(backend.record_failed_login(id).await.unwrap().is_none());
+    assert_eq!(admin(&db, id).await.failed_login_attempts, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_refused_login_keeps_failures() {
+    let (backend, db) = test_backend().await;
+    let id = create_verified_admin(&backend, EMAIL, PASSWORD).await.id;
+
+    assert!(log_in(&backend, "wrong password guess")
+        .await
+        .unwrap()
+        .is_none());
+    let mut disabled: admin_user::ActiveModel = admin(&db, id).await.into();
+    disabled.disabled = Set(true);
+    disabled.update(&db).await.unwrap();
+
+    // The right password for an account that can't log in doesn't wipe the count
+    let error = log_in(&backend, PASSWORD).await.unwrap_err();
+    assert!(error.contains("disabled"), "{}", error);
+    assert_eq!(admin(&db, id).await.failed_login_attempts, 1);
+}
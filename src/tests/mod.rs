@@ -15,13 +15,53 @@
  *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+pub mod api_token_tests;
+pub mod aws_tests;
+pub mod cleanup_tests;
+pub mod contact_tests;
+pub mod content_tests;
+pub mod csrf_tests;
 pub mod database_tests;
+pub mod discovery_tests;
+pub mod engagement_tests;
+pub mod invite_tests;
+pub mod locale_tests;
+pub mod lockout_tests;
 pub mod middleware_tests;
+pub mod oidc_tests;
+pub mod passkey_tests;
+pub mod password_policy_tests;
+pub mod password_reset_tests;
+pub mod resume_tests;
+pub mod roles_tests;
+pub mod s3_tests;
 pub mod security_tests;
+pub mod session_tests;
+pub mod templates_tests;
+pub mod two_factor_tests;
+pub mod user_management_tests;
+pub mod verification_tests;
+pub mod watermark_tests;
 
+use crate::admin::routes::{admin_api_routes, AdminState};
+use crate::admin::AdminAuthBackend;
 use crate::database;
+use crate::email::EmailService;
+use crate::entities::admin_user;
+use crate::middleware::track_admin_session;
 use crate::migration::{Migrator, MigratorTrait};
+use crate::settings::SettingsService;
+use axum::{
+    body::Body,
+    http::{header, Request, Response},
+    middleware::from_fn,
+    Router,
+};
+use axum_login::AuthManagerLayerBuilder;
 use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_sessions::{MemoryStore, SessionManagerLayer};
 
 pub async fn setup_test_db() -> DatabaseConnection {
     let db = database::establish_test_connection()
@@ -36,6 +76,24 @@ pub async fn setup_test_db() -> DatabaseConnection {
     db
 }
 
+/// An admin auth backend on a fresh database, with example.com as the admin email domain
+/// Tests add the email service, session store or OIDC provider they need with its `with_*` methods
+pub async fn test_backend() -> (AdminAuthBackend, DatabaseConnection) {
+    std::env::set_var("SITE_DOMAIN", "example.com");
+    let db = setup_test_db().await;
+    (AdminAuthBackend::new(db.clone()), db)
+}
+
+/// Register an admin and verify their email, the first one on a database is the owner
+pub async fn create_verified_admin(
+    backend: &AdminAuthBackend,
+    email: &str,
+    password: &str,
+) -> admin_user::Model {
+    let (_, token) = backend.create_admin(email, password).await.unwrap();
+    backend.verify_email(&token).await.unwrap()
+}
+
 pub async fn cleanup_test_db(db: &DatabaseConnection) {
     use crate::entities::AccessLog;
     use sea_orm::EntityTrait;
@@ -43,3 +101,46 @@ pub async fn cleanup_test_db(db: &DatabaseConnection) {
     // Clean up test data
     AccessLog::delete_many().exec(db).await.ok();
 }
+
+/// The admin API with sessions kept in memory, without the CSRF and rate limit layers
+pub async fn admin_app(backend: AdminAuthBackend, db: &DatabaseConnection) -> Router {
+    let auth_layer = AuthManagerLayerBuilder::new(
+        backend.clone(),
+        SessionManagerLayer::new(MemoryStore::default()),
+    )
+    .build();
+    admin_api_routes()
+        .with_state(AdminState {
+            auth_backend: backend,
+            email_service: Arc::new(EmailService::new().await.unwrap()),
+            settings: SettingsService::new(db.clone()),
+        })
+        .layer(from_fn(track_admin_session))
+        .layer(auth_layer)
+}
+
+/// POST a JSON body to the app, keeping the session cookie up to date
+pub async fn post_json(
+    app: &Router,
+    uri: &str,
+    body: serde_json::Value,
+    cookie: &mut Option<String>,
+) -> Response<Body> {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(cookie) = cookie.as_deref() {
+        request = request.header(header::COOKIE, cookie);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    if let Some(set_cookie) = response.headers().get(header::SET_COOKIE) {
+        let set_cookie = set_cookie.to_str().unwrap();
+        *cookie = Some(set_cookie.split(';').next().unwrap().to_string());
+    }
+    response
+}
@@ -0,0 +1,252 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::lockout::LOCKOUT_THRESHOLD;
+use crate::admin::passkeys::{normalize_passkey_name, MAX_PASSKEY_NAME_LENGTH};
+use crate::admin::{AdminAuthBackend, Credentials};
+use crate::tests::{admin_app, create_verified_admin, post_json, test_backend};
+use axum::http::StatusCode;
+use axum_login::AuthnBackend;
+use serde_json::json;
+use serial_test::serial;
+use uuid::Uuid;
+use webauthn_authenticator_rs::{softpasskey::SoftPasskey, WebauthnAuthenticator};
+use webauthn_rs::prelude::Url;
+
+const EMAIL: &str = "admin@example.com";
+const PASSWORD: &str = "plum kettle orbit lantern";
+
+/// Origin the backend accepts when SITE_URL is not set
+fn origin() -> Url {
+    Url::parse("http://localhost:3000").unwrap()
+}
+
+/// A software authenticator that reports user verification, as platform passkeys do
+fn authenticator() -> WebauthnAuthenticator<SoftPasskey> {
+    WebauthnAuthenticator::new(SoftPasskey::new(true))
+}
+
+async fn register(
+    backend: &AdminAuthBackend,
+    authenticator: &mut WebauthnAuthenticator<SoftPasskey>,
+    admin_id: Uuid,
+    name: &str,
+) -> Uuid {
+    let (challenge, state) = backend.begin_passkey_registration(admin_id).await.unwrap();
+    let response = authenticator
+        .do_registration(origin(), challenge)
+        .expect("Soft passkey should register");
+    backend
+        .finish_passkey_registration(admin_id, name, &response, &state)
+        .await
+        .unwrap()
+        .id
+}
+
+async fn sign_in(
+    backend: &AdminAuthBackend,
+    authenticator: &mut WebauthnAuthenticator<SoftPasskey>,
+) -> Credentials {
+    let (challenge, state) = backend
+        .begin_passkey_authentication(EMAIL)
+        .await
+        .unwrap()
+        .expect("Admin should have passkeys");
+    let response = authenticator
+        .do_authentication(origin(), challenge)
+        .expect("Soft passkey should sign the challenge");
+    Credentials::Passkey {
+        response: Box::new(response),
+        state: Box::new(state),
+    }
+}
+
+#[test]
+fn test_normalize_passkey_name() {
+    assert_eq!(normalize_passkey_name("  Laptop ").unwrap(), "Laptop");
+    assert!(normalize_passkey_name("   ").is_err());
+    assert!(normalize_passkey_name(&"a".repeat(MAX_PASSKEY_NAME_LENGTH)).is_ok());
+    assert!(normalize_passkey_name(&"a".repeat(MAX_PASSKEY_NAME_LENGTH + 1)).is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_passkey_registration_and_login() {
+    let (backend, _db) = test_backend().await;
+    let admin_id = create_verified_admin(&backend, EMAIL, PASSWORD).await.id;
+
+    // No passkeys yet, nothing to challenge
+    assert!(backend
+        .begin_passkey_authentication(EMAIL)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(backend
+        .begin_passkey_authentication("nobody@example.com")
+        .await
+        .unwrap()
+        .is_none());
+
+    let mut laptop = authenticator();
+    let mut phone = authenticator();
+    register(&backend, &mut laptop, admin_id, " Laptop ").await;
+    register(&backend, &mut phone, admin_id, "Phone").await;
+
+    let passkeys = backend.list_passkeys(admin_id).await.unwrap();
+    assert_eq!(passkeys.len(), 2);
+    assert_eq!(passkeys[0].name, "Laptop");
+    assert!(passkeys.iter().all(|p| p.last_used_at.is_none()));
+
+    // Either passkey signs in
+    for authenticator in [&mut laptop, &mut phone] {
+        let creds = sign_in(&backend, authenticator).await;
+        let user = backend
+            .authenticate(creds)
+            .await
+            .unwrap()
+            .expect("Passkey should authenticate");
+        assert_eq!(user.id, admin_id);
+    }
+    let passkeys = backend.list_passkeys(admin_id).await.unwrap();
+    assert!(passkeys.iter().all(|p| p.last_used_at.is_some()));
+
+    // The response only answers the challenge it was made for
+    let first = sign_in(&backend, &mut laptop).await;
+    let second = sign_in(&backend, &mut laptop).await;
+    let (Credentials::Passkey { response, .. }, Credentials::Passkey { state, .. }) =
+        (first, second)
+    else {
+        unreachable!();
+    };
+    assert!(backend
+        .authenticate(Credentials::Passkey { response, state })
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_passkey_login_lifts_lockout() {
+    let (backend, db) = test_backend().await;
+    let admin_id = create_verified_admin(&backend, EMAIL, PASSWORD).await.id;
+    let mut laptop = authenticator();
+    register(&backend, &mut laptop, admin_id, "Laptop").await;
+
+    for _ in 0..LOCKOUT_THRESHOLD {
+        backend.record_failed_login(admin_id).await.unwrap();
+    }
+    assert!(backend.ensure_login_allowed(admin_id).await.is_err());
+
+    // Passkeys can't be guessed, so they still sign in and clear the lock
+    let app = admin_app(backend.clone(), &db).await;
+    let mut cookie = None;
+    let response = post_json(
+        &app,
+        "/api/admin/login/passkey/start",
+        json!({ "email": EMAIL }),
+        &mut cookie,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let challenge = serde_json::from_slice(&body).unwrap();
+    let credential = laptop
+        .do_authentication(origin(), challenge)
+        .expect("Soft passkey should sign the challenge");
+    let response = post_json(
+        &app,
+        "/api/admin/login/passkey/finish",
+        serde_json::to_value(&credential).unwrap(),
+        &mut cookie,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    backend.ensure_login_allowed(admin_id).await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_passkey_start_does_not_reveal_accounts() {
+    let (backend, db) = test_backend().await;
+    create_verified_admin(&backend, EMAIL, PASSWORD).await;
+    let app = admin_app(backend, &db).await;
+
+    // An admin without passkeys looks the same as an address with no account
+    let mut answers = Vec::new();
+    for email in [EMAIL, "nobody@example.com"] {
+        let response = post_json(
+            &app,
+            "/api/admin/login/passkey/start",
+            json!({ "email": email }),
+            &mut None,
+        )
+        .await;
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        answers.push((status, body));
+    }
+    assert_eq!(answers[0].0, StatusCode::UNAUTHORIZED);
+    assert_eq!(answers[0], answers[1]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_passkey_rename_and_revoke() {
+    let (backend, _db) = test_backend().await;
+    let admin_id = create_verified_admin(&backend, EMAIL, PASSWORD).await.id;
+    let (other, _) = backend
+        .create_admin("other@example.com", "violet canyon drift signal")
+        .await
+        .unwrap();
+
+    let mut laptop = authenticator();
+    let passkey_id = register(&backend, &mut laptop, admin_id, "Laptop").await;
+
+    let renamed = backend
+        .rename_passkey(admin_id, passkey_id, "Work laptop")
+        .await
+        .unwrap();
+    assert_eq!(renamed.name, "Work laptop");
+    assert!(backend
+        .rename_passkey(admin_id, passkey_id, "")
+        .await
+        .is_err());
+
+    // Passkeys belong to one admin
+    assert!(backend
+        .rename_passkey(other.id, passkey_id, "Mine now")
+        .await
+        .is_err());
+    assert!(backend.delete_passkey(other.id, passkey_id).await.is_err());
+
+    // A ceremony started before revocation can't finish after it
+    let creds = sign_in(&backend, &mut laptop).await;
+    backend.delete_passkey(admin_id, passkey_id).await.unwrap();
+    assert!(backend.authenticate(creds).await.unwrap().is_none());
+
+    assert!(backend.list_passkeys(admin_id).await.unwrap().is_empty());
+    assert!(backend.delete_passkey(admin_id, passkey_id).await.is_err());
+    assert!(backend
+        .begin_passkey_authentication(EMAIL)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,130 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::password_policy::{is_common_password, PasswordPolicy, MAX_PASSWORD_LENGTH};
+use crate::tests::test_backend;
+use serial_test::serial;
+
+const EMAIL: &str = "grant@example.com";
+
+#[test]
+fn test_password_policy_rules() {
+    let policy = PasswordPolicy { min_length: 12 };
+
+    assert!(policy.check("plum kettle orbit lantern", EMAIL).is_ok());
+    assert!(policy.check("", EMAIL).is_err());
+    assert!(policy.check("short pass", EMAIL).is_err());
+    assert!(policy
+        .check(&"x".repeat(MAX_PASSWORD_LENGTH), EMAIL)
+        .is_ok());
+    assert!(policy
+        .check(&"x".repeat(MAX_PASSWORD_LENGTH + 1), EMAIL)
+        .is_err());
+
+    // Length counts characters, not bytes
+    assert!(policy.check("ééééééééééé", EMAIL).is_err());
+
+    // Common passwords are rejected whatever the case
+    assert!(policy.check("Password1234", EMAIL).is_err());
+    assert!(policy.check("QWERTYUIOP123", EMAIL).is_err());
+
+    // Containing the email local part
+    assert!(policy.check("my name is Grant really", EMAIL).is_err());
+    // Very short local parts are ignored
+    assert!(policy
+        .check("a perfectly fine one", "al@example.com")
+        .is_ok());
+}
+
+#[test]
+fn test_common_password_list() {
+    assert!(is_common_password("password"));
+    assert!(is_common_password(" LetMeIn "));
+    assert!(is_common_password("correct horse battery staple"));
+    assert!(!is_common_password("plum kettle orbit lantern"));
+    // Comment lines in the bundled list aren't entries
+    assert!(!is_common_password("# Lines starting with # are ignored"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_policy_enforced_on_registration_and_change() {
+    let (backend, _) = test_backend().await;
+
+    assert!(backend.create_admin(EMAIL, "").await.is_err());
+    assert!(backend.create_admin(EMAIL, "password123!").await.is_err());
+    assert!(backend
+        .create_admin(EMAIL, "grant's own password")
+        .await
+        .is_err());
+
+    let (admin, token) = backend
+        .create_admin(EMAIL, "plum kettle orbit lantern")
+        .await
+        .unwrap();
+    backend.verify_email(&token).await.unwrap();
+
+    // The current password has to be right
+    assert!(backend
+        .change_password(
+            admin.id,
+            "wrong password here",
+            "violet canyon drift signal"
+        )
+        .await
+        .is_err());
+    // The new one has to pass the policy and differ from the current one
+    assert!(backend
+        .change_password(admin.id, "plum kettle orbit lantern", "letmein")
+        .await
+        .is_err());
+    assert!(backend
+        .change_password(
+            admin.id,
+            "plum kettle orbit lantern",
+            "plum kettle orbit lantern"
+        )
+        .await
+        .is_err());
+
+    backend
+        .change_password(
+            admin.id,
+            "plum kettle orbit lantern",
+            "violet canyon drift signal",
+        )
+        .await
+        .unwrap();
+    assert!(backend
+        .verify_admin_password(admin.id, "violet canyon drift signal")
+        .await
+        .unwrap());
+
+    // Reset goes through the same policy
+    let (_, reset_token) = backend
+        .request_password_reset(EMAIL)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(backend
+        .reset_password(&reset_token, "qwerty123")
+        .await
+        .is_err());
+    backend
+        .reset_password(&reset_token, "amber falcon quiet harbor")
+        .await
+        .unwrap();
+}
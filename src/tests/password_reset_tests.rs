@@ -0,0 +1,150 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::{AdminAuthBackend, AdminUserAuth, Credentials};
+use crate::entities::{admin_user, AdminUser};
+use crate::tests::{create_verified_admin, test_backend};
+use axum_login::{AuthUser, AuthnBackend};
+use chrono::Duration;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serial_test::serial;
+
+const EMAIL: &str = "admin@example.com";
+const OLD_PASSWORD: &str = "plum kettle orbit lantern";
+const NEW_PASSWORD: &str = "a brand new passphrase";
+
+async fn can_log_in(backend: &AdminAuthBackend, password: &str) -> bool {
+    backend
+        .authenticate(Credentials::Password {
+            email: EMAIL.to_string(),
+            password: password.to_string(),
+        })
+        .await
+        .unwrap()
+        .is_some()
+}
+
+/// Move the current token's issue time into the past so another one can be requested
+async fn age_reset_token(db: &DatabaseConnection, admin: &admin_user::Model, minutes: i64) {
+    let admin = AdminUser::find_by_id(admin.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    let expires_at = admin.password_reset_expires_at.unwrap() - Duration::minutes(minutes);
+    let mut admin: admin_user::ActiveModel = admin.into();
+    admin.password_reset_expires_at = Set(Some(expires_at));
+    admin.update(db).await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_password_reset_flow() {
+    let (backend, db) = test_backend().await;
+    let admin = create_verified_admin(&backend, EMAIL, OLD_PASSWORD).await;
+    let session_hash = AdminUserAuth::from(admin.clone())
+        .session_auth_hash()
+        .to_vec();
+
+    assert!(backend
+        .request_password_reset("nobody@example.com")
+        .await
+        .unwrap()
+        .is_none());
+
+    let (_, token) = backend
+        .request_password_reset(EMAIL)
+        .await
+        .unwrap()
+        .expect("Verified admin should get a token");
+
+    // Only the hash is stored
+    let stored = AdminUser::find_by_id(admin.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(
+        stored.password_reset_token_hash.as_deref(),
+        Some(token.as_str())
+    );
+
+    assert!(backend
+        .reset_password("not-the-token", NEW_PASSWORD)
+        .await
+        .is_err());
+
+    let updated = backend.reset_password(&token, NEW_PASSWORD).await.unwrap();
+    assert!(updated.password_reset_token_hash.is_none());
+    assert!(!can_log_in(&backend, OLD_PASSWORD).await);
+    assert!(can_log_in(&backend, NEW_PASSWORD).await);
+
+    // Existing sessions no longer match the account
+    assert_ne!(
+        AdminUserAuth::from(updated).session_auth_hash(),
+        session_hash.as_slice()
+    );
+
+    // Single use
+    assert!(backend.reset_password(&token, OLD_PASSWORD).await.is_err());
+    assert!(can_log_in(&backend, NEW_PASSWORD).await);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_password_reset_expiry_and_resend() {
+    let (backend, db) = test_backend().await;
+    let admin = create_verified_admin(&backend, EMAIL, OLD_PASSWORD).await;
+
+    let (_, first) = backend
+        .request_password_reset(EMAIL)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // A second email right away is suppressed
+    assert!(backend
+        .request_password_reset(EMAIL)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Later requests replace the earlier token
+    age_reset_token(&db, &admin, 2).await;
+    let (_, second) = backend
+        .request_password_reset(EMAIL)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(backend.reset_password(&first, NEW_PASSWORD).await.is_err());
+
+    // Expired tokens are refused
+    age_reset_token(&db, &admin, 61).await;
+    assert!(backend.reset_password(&second, NEW_PASSWORD).await.is_err());
+    assert!(can_log_in(&backend, OLD_PASSWORD).await);
+
+    // Unverified accounts can't reset
+    let (other, _) = backend
+        .create_admin("other@example.com", OLD_PASSWORD)
+        .await
+        .unwrap();
+    assert!(!other.email_verified);
+    assert!(backend
+        .request_password_reset("other@example.com")
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,135 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::resume::{self, html::render_html, pdf::render_pdf, Resume};
+use lopdf::Document;
+
+const SAMPLE_RESUME: &str = r#"{
+    "basics": {
+        "name": "Jane Doe",
+        "label": "Systems Engineer",
+        "email": "jane@example.com",
+        "location": { "city": "Columbus", "region": "OH", "countryCode": "US" },
+        "profiles": [{ "network": "GitHub", "url": "https://github.com/janedoe" }],
+        "summary": "Builds <reliable> backend systems."
+    },
+    "work": [{
+        "name": "Acme Corp",
+        "position": "Senior Engineer",
+        "startDate": "2021-03-01",
+        "highlights": ["Cut p99 latency by 40%", "Led the Rust migration"]
+    }],
+    "education": [{
+        "institution": "State University",
+        "area": "Computer Science",
+        "studyType": "BS",
+        "startDate": "2012",
+        "endDate": "2016"
+    }],
+    "skills": [{ "name": "Languages", "keywords": ["Rust", "Go"] }],
+    "unknownSection": { "ignored": true }
+}"#;
+
+fn sample_resume() -> Resume {
+    Resume::from_json(SAMPLE_RESUME.as_bytes()).expect("Sample resume should parse")
+}
+
+#[test]
+fn test_resume_parses_json_resume_schema() {
+    let resume = sample_resume();
+    assert_eq!(resume.basics.name, "Jane Doe");
+    assert_eq!(resume.work.len(), 1);
+    assert_eq!(resume.work[0].start_date.as_deref(), Some("2021-03-01"));
+    assert_eq!(resume.education[0].study_type.as_deref(), Some("BS"));
+    assert!(
+        resume.projects.is_empty(),
+        "Missing sections default to empty"
+    );
+}
+
+#[test]
+fn test_resume_rejects_invalid_json() {
+    assert!(Resume::from_json(b"{ not json").is_err());
+}
+
+#[test]
+fn test_format_dates() {
+    assert_eq!(resume::format_date("2021-03-01"), "Mar 2021");
+    assert_eq!(resume::format_date("2021-11"), "Nov 2021");
+    assert_eq!(resume::format_date("2016"), "2016");
+    assert_eq!(
+        resume::format_date_range(Some("2021-03"), None),
+        "Mar 2021 - Present"
+    );
+    assert_eq!(
+        resume::format_date_range(Some("2012"), Some("2016")),
+        "2012 - 2016"
+    );
+    assert_eq!(resume::format_date_range(None, None), "");
+}
+
+#[test]
+fn test_resume_pdf_filename() {
+    assert_eq!(sample_resume().pdf_filename(), "Jane_Doe_Resume.pdf");
+    assert_eq!(Resume::default().pdf_filename(), "Resume.pdf");
+}
+
+#[test]
+fn test_render_html() {
+    let html = render_html(&sample_resume(), "abc 123", "en", None).unwrap();
+
+    assert!(html.contains("<h1>Jane Doe</h1>"));
+    assert!(html.contains("Senior Engineer - Acme Corp"));
+    assert!(html.contains("Mar 2021 - Present"));
+    assert!(html.contains("Columbus, OH, US"));
+    assert!(html.contains("Rust, Go"));
+    assert!(
+        html.contains("&lt;reliable&gt;"),
+        "Resume content must be HTML escaped"
+    );
+    assert!(html.contains("href=\"abc%20123/download\""));
+    assert!(html.contains("<html lang=\"en\">"));
+
+    // An explicit ?lang= override is carried over to the PDF download
+    let html = render_html(&sample_resume(), "abc", "de", Some("de")).unwrap();
+    assert!(html.contains("<html lang=\"de\">"));
+    assert!(html.contains("href=\"abc/download?lang=de\""));
+}
+
+#[test]
+fn test_render_pdf() {
+    let pdf = render_pdf(&sample_resume()).unwrap();
+    let doc = Document::load_mem(&pdf).expect("Generated PDF should parse");
+
+    assert_eq!(doc.get_pages().len(), 1);
+    let text = doc.extract_text(&[1]).unwrap();
+    assert!(text.contains("Jane Doe"));
+    assert!(text.contains("Senior Engineer - Acme Corp"));
+    assert!(text.contains("Led the Rust migration"));
+}
+
+#[test]
+fn test_render_pdf_paginates_long_resumes() {
+    let mut resume = sample_resume();
+    resume.work[0].highlights = (0..200)
+        .map(|i| format!("Highlight number {} with enough words to fill the line", i))
+        .collect();
+
+    let pdf = render_pdf(&resume).unwrap();
+    let doc = Document::load_mem(&pdf).unwrap();
+    assert!(doc.get_pages().len() > 1, "Long resumes should span pages");
+}
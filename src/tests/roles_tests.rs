@@ -0,0 +1,118 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::access_logs::{access_log_routes, AccessLogState};
+use crate::admin::roles::{Permission, Role};
+use crate::admin::AdminUserAuth;
+use crate::tests::test_backend;
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    Extension,
+};
+use sea_orm::DatabaseConnection;
+use serial_test::serial;
+use tower::ServiceExt;
+
+const PASSWORD: &str = "amber falcon quiet harbor";
+
+#[test]
+fn test_roles_are_ordered_by_what_they_allow() {
+    for permission in Role::Viewer.permissions() {
+        assert!(Role::Editor.allows(*permission));
+    }
+    for permission in Role::Editor.permissions() {
+        assert!(Role::Owner.allows(*permission));
+    }
+
+    assert!(Role::Editor.allows(Permission::ManageAccessCodes));
+    assert!(!Role::Viewer.allows(Permission::ManageAccessCodes));
+    assert!(!Role::Editor.allows(Permission::ClearAccessLogs));
+    assert!(!Role::Editor.allows(Permission::ManageSettings));
+    assert!(!Role::Editor.allows(Permission::ManageAdmins));
+}
+
+#[test]
+fn test_role_names_round_trip() {
+    for role in Role::ALL {
+        assert_eq!(role.as_str().parse::<Role>(), Ok(role));
+    }
+    assert!("admin".parse::<Role>().is_err());
+    assert!("Owner".parse::<Role>().is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_first_admin_is_owner_and_later_ones_get_default_role() {
+    let (backend, _) = test_backend().await;
+
+    let (first, _) = backend
+        .create_admin("first@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(first.role, "owner");
+
+    let (second, _) = backend
+        .create_admin("second@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(second.role, Role::DEFAULT.as_str());
+
+    let (_, token) = backend
+        .create_invite(first.id, "guest@elsewhere.org")
+        .await
+        .unwrap();
+    let invited = backend
+        .accept_invite(&token, "guest@elsewhere.org", PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(AdminUserAuth::from(invited).role, Role::DEFAULT);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_routes_enforce_declared_permissions() {
+    let (backend, db) = test_backend().await;
+
+    let (admin, _) = backend
+        .create_admin("admin@example.com", PASSWORD)
+        .await
+        .unwrap();
+
+    let mut user = AdminUserAuth::from(admin);
+    user.role = Role::Editor;
+    assert_eq!(status(&db, &user, Method::GET).await, StatusCode::OK);
+    assert_eq!(
+        status(&db, &user, Method::DELETE).await,
+        StatusCode::FORBIDDEN
+    );
+
+    user.role = Role::Owner;
+    assert!(status(&db, &user, Method::DELETE).await.is_success());
+}
+
+async fn status(db: &DatabaseConnection, user: &AdminUserAuth, method: Method) -> StatusCode {
+    // Stands in for require_admin_auth, which puts the logged in admin on the request
+    let app = access_log_routes()
+        .with_state(AccessLogState { db: db.clone() })
+        .layer(Extension(user.clone()));
+    let request = Request::builder()
+        .method(method)
+        .uri("/api/admin/access-logs")
+        .body(Body::empty())
+        .unwrap();
+    app.oneshot(request).await.unwrap().status()
+}
@@ -0,0 +1,81 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::s3::{DownloadMode, S3Service};
+use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+
+/// Client for a local S3-compatible endpoint (e.g. MinIO on port 9000)
+/// Presigning happens locally, so no server needs to be running
+fn local_s3_service() -> S3Service {
+    let config = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-1"))
+        .endpoint_url("http://localhost:9000")
+        .force_path_style(true)
+        .credentials_provider(Credentials::new(
+            "minioadmin",
+            "minioadmin",
+            None,
+            None,
+            "test",
+        ))
+        .build();
+
+    S3Service::from_client(
+        aws_sdk_s3::Client::from_conf(config),
+        "test-bucket".to_string(),
+    )
+}
+
+#[test]
+fn test_download_mode_parse() {
+    assert_eq!(
+        DownloadMode::parse("presigned"),
+        DownloadMode::PresignedRedirect
+    );
+    assert_eq!(
+        DownloadMode::parse("Redirect"),
+        DownloadMode::PresignedRedirect
+    );
+    assert_eq!(DownloadMode::parse("proxy"), DownloadMode::Proxy);
+    assert_eq!(DownloadMode::parse(""), DownloadMode::Proxy);
+}
+
+#[tokio::test]
+async fn test_presigned_get_url() {
+    let s3 = local_s3_service();
+
+    let url = s3
+        .presigned_get_url(
+            "ABC123",
+            "Resume.pdf",
+            "attachment; filename=\"Resume.pdf\"",
+        )
+        .await
+        .expect("Presigning should not need network access");
+
+    assert!(
+        url.starts_with("http://localhost:9000/test-bucket/ABC123/Resume.pdf?"),
+        "Unexpected presigned URL: {}",
+        url
+    );
+    assert!(url.contains("X-Amz-Signature="));
+    assert!(url.contains("X-Amz-Expires=60"));
+    assert!(
+        url.contains("response-content-disposition=attachment%3B%20filename%3D%22Resume.pdf%22")
+    );
+}
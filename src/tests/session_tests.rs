@@ -0,0 +1,183 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::routes::AdminAuthSession;
+use crate::admin::{AdminAuthBackend, AdminUserAuth};
+use crate::middleware::track_admin_session;
+use crate::tests::{create_verified_admin, test_backend};
+use axum::{
+    body::Body,
+    extract::Path,
+    http::{header, Request, Response, StatusCode},
+    middleware::from_fn,
+    routing::{get, post},
+    Router,
+};
+use axum_login::AuthManagerLayerBuilder;
+use sea_orm::DatabaseConnection;
+use serial_test::serial;
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_sessions::{session::Id, MemoryStore, SessionManagerLayer, SessionStore};
+use uuid::Uuid;
+
+const PASSWORD: &str = "amber falcon quiet harbor";
+
+async fn setup() -> (AdminAuthBackend, DatabaseConnection, MemoryStore, Uuid) {
+    let (backend, db) = test_backend().await;
+    let store = MemoryStore::default();
+    let backend = backend.with_session_store(Arc::new(store.clone()));
+
+    let admin = create_verified_admin(&backend, "admin@example.com", PASSWORD).await;
+    (backend, db, store, admin.id)
+}
+
+/// Just enough of the admin router to log in and make authenticated requests
+fn app(backend: AdminAuthBackend, store: MemoryStore) -> Router {
+    async fn login(mut auth_session: AdminAuthSession, Path(id): Path<Uuid>) -> StatusCode {
+        let admin = auth_session.backend.find_admin(id).await.unwrap();
+        auth_session
+            .login(&AdminUserAuth::from(admin))
+            .await
+            .unwrap();
+        StatusCode::OK
+    }
+
+    async fn me(auth_session: AdminAuthSession) -> StatusCode {
+        if auth_session.user.is_some() {
+            StatusCode::OK
+        } else {
+            StatusCode::UNAUTHORIZED
+        }
+    }
+
+    let auth_layer = AuthManagerLayerBuilder::new(backend, SessionManagerLayer::new(store)).build();
+    Router::new()
+        .route("/login/{id}", post(login))
+        .route("/me", get(me))
+        .layer(from_fn(track_admin_session))
+        .layer(auth_layer)
+}
+
+async fn send(app: &Router, method: &str, uri: &str, cookie: Option<&str>) -> Response<Body> {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(cookie) = cookie {
+        request = request.header(header::COOKIE, cookie);
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn me(app: &Router, cookie: &str) -> StatusCode {
+    send(app, "GET", "/me", Some(cookie)).await.status()
+}
+
+/// Log in and make a first request, returns the session cookie
+async fn log_in(app: &Router, admin_id: Uuid) -> String {
+    let response = send(app, "POST", &format!("/login/{}", admin_id), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+    let cookie = cookie.split(';').next().unwrap().to_string();
+    assert_eq!(me(app, &cookie).await, StatusCode::OK);
+    cookie
+}
+
+/// The admin_sessions row of a session cookie
+async fn row_of(backend: &AdminAuthBackend, admin_id: Uuid, cookie: &str) -> Uuid {
+    let session_id = cookie.split_once('=').unwrap().1;
+    backend
+        .list_sessions(admin_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|s| s.session_id.as_deref() == Some(session_id))
+        .unwrap()
+        .id
+}
+
+#[tokio::test]
+#[serial]
+async fn test_sessions_are_listed_and_revoked() {
+    let (backend, _db, store, admin_id) = setup().await;
+    let app = app(backend.clone(), store.clone());
+
+    let laptop = log_in(&app, admin_id).await;
+    let phone = log_in(&app, admin_id).await;
+    assert_eq!(backend.list_sessions(admin_id).await.unwrap().len(), 2);
+
+    let phone_row = row_of(&backend, admin_id, &phone).await;
+    backend.revoke_session(admin_id, phone_row).await.unwrap();
+    assert!(backend.revoke_session(admin_id, phone_row).await.is_err());
+
+    // Gone from the store too
+    let phone_id = phone.split_once('=').unwrap().1.parse::<Id>().unwrap();
+    assert!(store.load(&phone_id).await.unwrap().is_none());
+
+    assert_eq!(me(&app, &phone).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(me(&app, &laptop).await, StatusCode::OK);
+    assert_eq!(backend.list_sessions(admin_id).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revoked_sessions_end_even_if_left_in_the_store() {
+    let (_, db, store, admin_id) = setup().await;
+    let backend = AdminAuthBackend::new(db);
+    let app = app(backend.clone(), store);
+
+    let cookie = log_in(&app, admin_id).await;
+    let row = row_of(&backend, admin_id, &cookie).await;
+    backend.revoke_session(admin_id, row).await.unwrap();
+
+    assert_eq!(me(&app, &cookie).await, StatusCode::UNAUTHORIZED);
+    // Logged out rather than tracked again
+    assert!(backend.list_sessions(admin_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revoking_all_sessions_can_keep_the_current_one() {
+    let (backend, _db, store, admin_id) = setup().await;
+    let app = app(backend.clone(), store);
+
+    let current = log_in(&app, admin_id).await;
+    let others = [log_in(&app, admin_id).await, log_in(&app, admin_id).await];
+
+    let keep = row_of(&backend, admin_id, &current).await;
+    assert_eq!(
+        backend.revoke_sessions(admin_id, Some(keep)).await.unwrap(),
+        2
+    );
+    assert_eq!(me(&app, &current).await, StatusCode::OK);
+    for cookie in &others {
+        assert_eq!(me(&app, cookie).await, StatusCode::UNAUTHORIZED);
+    }
+
+    // A password reset ends every session
+    let (_, token) = backend
+        .request_password_reset("admin@example.com")
+        .await
+        .unwrap()
+        .unwrap();
+    backend
+        .reset_password(&token, "violet canyon drift signal")
+        .await
+        .unwrap();
+    assert!(backend.list_sessions(admin_id).await.unwrap().is_empty());
+    assert_eq!(me(&app, &current).await, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,310 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::settings::SettingsService;
+use crate::templates::{SiteConfig, TemplateService, TemplateSource};
+use crate::tests::setup_test_db;
+use serial_test::serial;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+fn site() -> SiteConfig {
+    SiteConfig {
+        domain: Some("example.com".to_string()),
+        url: Some("https://www.example.com".to_string()),
+    }
+}
+
+/// Write a template and push its mtime forward so the change is seen regardless of timestamp resolution
+fn write_template(path: &Path, content: &str, age_offset_secs: u64) {
+    fs::write(path, content).expect("Should write template");
+    let modified = SystemTime::now() + Duration::from_secs(age_offset_secs);
+    File::options()
+        .write(true)
+        .open(path)
+        .and_then(|f| f.set_modified(modified))
+        .expect("Should set mtime");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_render_with_site_config_and_settings() {
+    let db = setup_test_db().await;
+    let settings = SettingsService::new(db);
+    settings
+        .set("landing_tagline", "Hello there", Some("system"), None)
+        .await
+        .expect("Should set setting");
+
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    let path = dir.path().join("landing.html");
+    write_template(
+        &path,
+        "{{SITE_URL}}/resume|admin@{{ site.domain }}|{{ settings.landing_tagline }}",
+        0,
+    );
+
+    let templates = TemplateService::new(
+        vec![
+            TemplateSource::new("landing.html", &path, true),
+            TemplateSource::new("admin.html", dir.path().join("missing.html"), false),
+        ],
+        site(),
+        settings.clone(),
+    )
+    .expect("Should compile templates");
+
+    let html = templates
+        .render("landing.html")
+        .await
+        .expect("Should render")
+        .expect("Template should exist");
+    assert_eq!(
+        html,
+        "https://www.example.com/resume|admin@example.com|Hello there"
+    );
+
+    // Settings are cached between renders but a change shows up on the next one
+    settings
+        .set("landing_tagline", "Welcome back", Some("system"), None)
+        .await
+        .expect("Should set setting");
+    let html = templates.render("landing.html").await.unwrap().unwrap();
+    assert!(html.ends_with("|Welcome back"));
+
+    // Optional templates that don't exist render as None
+    assert!(templates
+        .render("admin.html")
+        .await
+        .expect("Should not error")
+        .is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_required_template_must_exist() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+
+    let result = TemplateService::new(
+        vec![TemplateSource::new(
+            "landing.html",
+            dir.path().join("landing.html"),
+            true,
+        )],
+        site(),
+        SettingsService::new(db),
+    );
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reload_if_changed() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    let path = dir.path().join("landing.html");
+    write_template(&path, "Version 1", 0);
+
+    let templates = TemplateService::new(
+        vec![TemplateSource::new("landing.html", &path, true)],
+        site(),
+        SettingsService::new(db),
+    )
+    .expect("Should compile templates");
+
+    assert!(!templates.reload_if_changed().expect("Should check"));
+
+    write_template(&path, "Version 2", 10);
+    assert!(templates.reload_if_changed().expect("Should reload"));
+    let html = templates.render("landing.html").await.unwrap().unwrap();
+    assert_eq!(html, "Version 2");
+
+    // A broken edit keeps the last good version
+    write_template(&path, "{% if %}", 20);
+    assert!(templates.reload_if_changed().is_err());
+    let html = templates.render("landing.html").await.unwrap().unwrap();
+    assert_eq!(html, "Version 2");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_landing_page_access_form() {
+    let db = setup_test_db().await;
+    let templates = TemplateService::new(
+        vec![TemplateSource::new("landing.html", "landing.html", true)],
+        site(),
+        SettingsService::new(db),
+    )
+    .expect("Bundled landing page should compile");
+
+    let html = templates
+        .render_with(
+            "landing.html",
+            minijinja::context! {
+                csrf_token => "token123",
+                csrf_field => "csrf_token",
+                access_error => "That access code is not valid.",
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(html.contains(r#"<html lang="en">"#));
+    assert!(html.contains(r#"<form class="access-form" method="post" action="/access">"#));
+    assert!(html.contains(r#"name="csrf_token" value="token123""#));
+    assert!(html.contains("Error: That access code is not valid."));
+    assert!(html.contains("https://www.example.com/resume/"));
+    assert!(html.contains("admin@example.com"));
+    assert!(html.contains(r#"<form class="contact-form" method="post" action="/contact#contact">"#));
+    assert!(html.contains(r#"<div class="contact-extra" aria-hidden="true">"#));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_landing_page_contact_feedback() {
+    let db = setup_test_db().await;
+    let templates = TemplateService::new(
+        vec![TemplateSource::new("landing.html", "landing.html", true)],
+        site(),
+        SettingsService::new(db),
+    )
+    .expect("Bundled landing page should compile");
+
+    // Rejected messages are shown again with the error
+    let html = templates
+        .render_with(
+            "landing.html",
+            minijinja::context! {
+                csrf_token => "token123",
+                csrf_field => "csrf_token",
+                honeypot_field => "website",
+                contact_error => "Please enter a valid email address.",
+                contact => minijinja::context! {
+                    name => "Ada",
+                    email => "not-an-email",
+                    message => "<b>Hi</b>",
+                },
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(html.contains("Error: Please enter a valid email address."));
+    assert!(html.contains(r#"value="Ada""#));
+    assert!(html.contains("&lt;b&gt;Hi&lt;&#x2f;b&gt;</textarea>"));
+
+    let html = templates
+        .render_with("landing.html", minijinja::context! { contact_sent => true })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(html.contains("Thanks, your message has been sent."));
+    assert!(!html.contains("contact-form\""));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_locale_variants_loaded_and_reloaded() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    let path = dir.path().join("landing.html");
+    write_template(&path, "Hello", 0);
+    write_template(&dir.path().join("landing.de.html"), "Hallo", 0);
+    write_template(&dir.path().join("landing.DE_x.html"), "Ignored", 0);
+
+    let templates = TemplateService::new(
+        vec![TemplateSource::new("landing.html", &path, true).localized()],
+        site(),
+        SettingsService::new(db),
+    )
+    .expect("Should compile templates");
+
+    assert_eq!(templates.locales("landing.html"), vec!["de".to_string()]);
+    let html = templates.render("landing.de.html").await.unwrap().unwrap();
+    assert_eq!(html, "Hallo");
+
+    // New variants are picked up by the hot reload check
+    write_template(&dir.path().join("landing.fr.html"), "Bonjour", 10);
+    assert!(templates.reload_if_changed().expect("Should reload"));
+    assert_eq!(
+        templates.locales("landing.html"),
+        vec!["de".to_string(), "fr".to_string()]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_optional_template_that_does_not_compile_is_served_raw() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    let landing = dir.path().join("landing.html");
+    let admin = dir.path().join("admin.html");
+    write_template(&landing, "landing", 0);
+    // Bundled JavaScript can contain anything that looks like template syntax
+    let shell = "<script>const t = `{% not a tag`;</script>";
+    write_template(&admin, shell, 0);
+
+    let templates = TemplateService::new(
+        vec![
+            TemplateSource::new("landing.html", &landing, true),
+            TemplateSource::new("admin.html", &admin, false).raw_fallback(),
+        ],
+        site(),
+        SettingsService::new(db.clone()),
+    )
+    .expect("An optional template should not stop the service");
+    let html = templates.render("admin.html").await.unwrap().unwrap();
+    assert_eq!(html, shell);
+
+    // Required templates still have to be valid
+    write_template(&landing, "{% not a tag", 0);
+    assert!(TemplateService::new(
+        vec![TemplateSource::new("landing.html", &landing, true)],
+        site(),
+        SettingsService::new(db),
+    )
+    .is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_locale_variant_that_does_not_compile_is_skipped() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().expect("Should create temp dir");
+    let path = dir.path().join("landing.html");
+    write_template(&path, "Hello", 0);
+    write_template(&dir.path().join("landing.de.html"), "{{ csrf_token", 0);
+
+    let templates = TemplateService::new(
+        vec![TemplateSource::new("landing.html", &path, true).localized()],
+        site(),
+        SettingsService::new(db),
+    )
+    .expect("A broken variant should not stop the service");
+
+    // Never offered, so the default page is served rather than raw placeholders
+    assert!(templates.locales("landing.html").is_empty());
+    assert!(templates.render("landing.de.html").await.unwrap().is_none());
+
+    // Fixing it brings it back
+    write_template(&dir.path().join("landing.de.html"), "Hallo", 10);
+    assert!(templates.reload_if_changed().expect("Should reload"));
+    assert_eq!(templates.locales("landing.html"), vec!["de".to_string()]);
+}
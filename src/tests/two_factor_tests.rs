@@ -0,0 +1,221 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::admin::lockout::LOCKOUT_THRESHOLD;
+use crate::admin::two_factor::{
+    generate_recovery_codes, generate_secret, hash_recovery_code, is_totp_code, provisioning_uri,
+    verify_totp, RECOVERY_CODE_COUNT, TOTP_STEP_SECONDS,
+};
+use crate::tests::{admin_app, create_verified_admin, post_json, test_backend};
+use axum::http::StatusCode;
+use chrono::Utc;
+use serde_json::json;
+use serial_test::serial;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+fn code_at(secret: &str, time: u64) -> String {
+    let bytes = Secret::Encoded(secret.to_string()).to_bytes().unwrap();
+    TOTP::new_unchecked(
+        Algorithm::SHA1,
+        6,
+        0,
+        TOTP_STEP_SECONDS,
+        bytes,
+        None,
+        String::new(),
+    )
+    .generate(time)
+}
+
+fn now() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+#[test]
+fn test_verify_totp_window_and_replay() {
+    let secret = generate_secret();
+    let time = 1_700_000_000;
+    let step = (time / TOTP_STEP_SECONDS) as i64;
+
+    let code = code_at(&secret, time);
+    assert_eq!(verify_totp(&secret, &code, None, time).unwrap(), Some(step));
+    // One step of clock drift either way is accepted
+    assert_eq!(
+        verify_totp(&secret, &code, None, time + TOTP_STEP_SECONDS).unwrap(),
+        Some(step)
+    );
+    assert_eq!(
+        verify_totp(&secret, &code, None, time + 2 * TOTP_STEP_SECONDS).unwrap(),
+        None
+    );
+    // Already used steps are rejected
+    assert_eq!(verify_totp(&secret, &code, Some(step), time).unwrap(), None);
+
+    assert_eq!(verify_totp(&secret, "12345", None, time).unwrap(), None);
+    assert_eq!(verify_totp(&secret, "abcdef", None, time).unwrap(), None);
+}
+
+#[test]
+fn test_provisioning_uri() {
+    let secret = generate_secret();
+    let uri = provisioning_uri(&secret, "example.com", "admin@example.com").unwrap();
+
+    assert!(uri.starts_with("otpauth://totp/example.com:admin%40example.com?"));
+    assert!(uri.contains(&format!("secret={}", secret)));
+    assert!(uri.contains("issuer=example.com"));
+}
+
+#[test]
+fn test_recovery_codes() {
+    let codes = generate_recovery_codes();
+    assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+    for code in &codes {
+        assert_eq!(code.len(), 19);
+        assert_eq!(code.matches('-').count(), 3);
+        assert!(!is_totp_code(code));
+    }
+
+    let unique: std::collections::HashSet<_> = codes.iter().collect();
+    assert_eq!(unique.len(), codes.len());
+
+    // Formatting and case don't matter
+    let code = &codes[0];
+    assert_eq!(
+        hash_recovery_code(code),
+        hash_recovery_code(&code.replace('-', " ").to_uppercase())
+    );
+    assert_ne!(hash_recovery_code(code), hash_recovery_code(&codes[1]));
+
+    assert!(is_totp_code(" 123456 "));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_enrollment_and_second_factor() {
+    let (backend, _) = test_backend().await;
+    let admin =
+        create_verified_admin(&backend, "admin@example.com", "plum kettle orbit lantern").await;
+
+    // Not enrolled yet, nothing is accepted
+    assert!(!backend
+        .verify_second_factor(admin.id, "123456")
+        .await
+        .unwrap());
+
+    let (secret, uri) = backend.begin_totp_enrollment(admin.id).await.unwrap();
+    assert!(uri.contains(&secret));
+    let code = code_at(&secret, now());
+    let wrong: String = code
+        .chars()
+        .map(|c| char::from_digit((c.to_digit(10).unwrap() + 1) % 10, 10).unwrap())
+        .collect();
+    assert!(backend
+        .confirm_totp_enrollment(admin.id, &wrong)
+        .await
+        .is_err());
+
+    let recovery_codes = backend
+        .confirm_totp_enrollment(admin.id, &code)
+        .await
+        .unwrap();
+    assert_eq!(recovery_codes.len(), RECOVERY_CODE_COUNT);
+    assert!(backend.find_admin(admin.id).await.unwrap().totp_enabled);
+    assert!(backend.begin_totp_enrollment(admin.id).await.is_err());
+
+    // The enrollment code can't be replayed, the next step's code works once
+    assert!(!backend.verify_second_factor(admin.id, &code).await.unwrap());
+    let next = code_at(&secret, now() + TOTP_STEP_SECONDS);
+    assert!(backend.verify_second_factor(admin.id, &next).await.unwrap());
+    assert!(!backend.verify_second_factor(admin.id, &next).await.unwrap());
+
+    // Recovery codes are single use
+    let recovery = recovery_codes[3].to_uppercase();
+    assert!(backend
+        .verify_second_factor(admin.id, &recovery)
+        .await
+        .unwrap());
+    assert!(!backend
+        .verify_second_factor(admin.id, &recovery)
+        .await
+        .unwrap());
+    assert!(!backend
+        .verify_second_factor(admin.id, "aaaa-bbbb-cccc-dddd")
+        .await
+        .unwrap());
+
+    let stored = backend.find_admin(admin.id).await.unwrap();
+    let remaining: Vec<String> =
+        serde_json::from_str(stored.recovery_codes.as_deref().unwrap()).unwrap();
+    assert_eq!(remaining.len(), RECOVERY_CODE_COUNT - 1);
+    assert!(!stored.recovery_codes.unwrap().contains(&recovery_codes[0]));
+
+    let regenerated = backend.regenerate_recovery_codes(admin.id).await.unwrap();
+    assert!(!backend
+        .verify_second_factor(admin.id, &recovery_codes[0])
+        .await
+        .unwrap());
+    assert!(backend
+        .verify_second_factor(admin.id, &regenerated[0])
+        .await
+        .unwrap());
+
+    backend.disable_totp(admin.id).await.unwrap();
+    let disabled = backend.find_admin(admin.id).await.unwrap();
+    assert!(!disabled.totp_enabled);
+    assert!(disabled.totp_secret.is_none());
+    assert!(!backend
+        .verify_second_factor(admin.id, &regenerated[1])
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reentering_the_password_does_not_reset_code_failures() {
+    let (backend, db) = test_backend().await;
+    let admin =
+        create_verified_admin(&backend, "admin@example.com", "plum kettle orbit lantern").await;
+    let (secret, _) = backend.begin_totp_enrollment(admin.id).await.unwrap();
+    backend
+        .confirm_totp_enrollment(admin.id, &code_at(&secret, now()))
+        .await
+        .unwrap();
+    let app = admin_app(backend.clone(), &db).await;
+    let credentials = json!({
+        "email": "admin@example.com",
+        "password": "plum kettle orbit lantern",
+    });
+
+    // The failure count only resets once the whole login succeeds
+    for _ in 0..LOCKOUT_THRESHOLD {
+        let mut cookie = None;
+        let response = post_json(&app, "/api/admin/login", credentials.clone(), &mut cookie).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = post_json(
+            &app,
+            "/api/admin/login/2fa",
+            json!({ "code": "not-a-code" }),
+            &mut cookie,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    assert!(backend.ensure_login_allowed(admin.id).await.is_err());
+
+    let response = post_json(&app, "/api/admin/login", credentials, &mut None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,207 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::roles::Role;
+use crate::admin::{AdminAuthBackend, Credentials};
+use crate::entities::admin_user;
+use crate::tests::{admin_app, create_verified_admin, post_json, test_backend};
+use axum::http::StatusCode;
+use axum_login::AuthnBackend;
+use serde_json::json;
+use serial_test::serial;
+
+const PASSWORD: &str = "amber falcon quiet harbor";
+
+async fn create_verified(backend: &AdminAuthBackend, email: &str) -> admin_user::Model {
+    create_verified_admin(backend, email, PASSWORD).await
+}
+
+async fn log_in(backend: &AdminAuthBackend, email: &str) -> bool {
+    matches!(
+        backend
+            .authenticate(Credentials::Password {
+                email: email.to_string(),
+                password: PASSWORD.to_string(),
+            })
+            .await,
+        Ok(Some(_))
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn test_last_active_owner_cannot_be_removed() {
+    let (backend, _) = test_backend().await;
+    let owner = create_verified(&backend, "owner@example.com").await;
+    let other = create_verified(&backend, "other@example.com").await;
+    assert_eq!(owner.role, "owner");
+
+    assert!(backend
+        .set_admin_role(owner.id, Role::Editor)
+        .await
+        .is_err());
+    assert!(backend.set_admin_disabled(owner.id, true).await.is_err());
+    assert!(backend.delete_admin(owner.id).await.is_err());
+
+    // A disabled owner doesn't count as a remaining one
+    backend.set_admin_role(other.id, Role::Owner).await.unwrap();
+    backend.set_admin_disabled(other.id, true).await.unwrap();
+    assert!(backend.delete_admin(owner.id).await.is_err());
+
+    backend.set_admin_disabled(other.id, false).await.unwrap();
+    backend
+        .set_admin_role(owner.id, Role::Viewer)
+        .await
+        .unwrap();
+    assert!(backend
+        .set_admin_role(other.id, Role::Editor)
+        .await
+        .is_err());
+
+    // Non-owners can always be removed
+    backend.delete_admin(owner.id).await.unwrap();
+    assert_eq!(backend.list_admins().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_owners_demoting_each_other_at_once_leave_one_owner() {
+    let (backend, _) = test_backend().await;
+    let first = create_verified(&backend, "owner@example.com").await;
+    let second = create_verified(&backend, "other@example.com").await;
+    backend
+        .set_admin_role(second.id, Role::Owner)
+        .await
+        .unwrap();
+
+    let (a, b) = tokio::join!(
+        backend.set_admin_role(first.id, Role::Editor),
+        backend.set_admin_disabled(second.id, true),
+    );
+    assert!(a.is_err() || b.is_err());
+    let owners = backend
+        .list_admins()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|admin| admin.role == "owner" && !admin.disabled)
+        .count();
+    assert_eq!(owners, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_rule_violations_are_conflicts() {
+    let (backend, db) = test_backend().await;
+    let owner = create_verified(&backend, "owner@example.com").await;
+    let app = admin_app(backend, &db).await;
+
+    let mut cookie = None;
+    let response = post_json(
+        &app,
+        "/api/admin/login",
+        json!({ "email": "owner@example.com", "password": PASSWORD }),
+        &mut cookie,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Refused, but the session is still fine
+    let response = post_json(
+        &app,
+        &format!("/api/admin/users/{}/disable", owner.id),
+        json!({}),
+        &mut cookie,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = post_json(
+        &app,
+        &format!("/api/admin/users/{}/resend-verification", owner.id),
+        json!({}),
+        &mut cookie,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_disabled_admins_cannot_log_in_or_keep_sessions() {
+    let (backend, _) = test_backend().await;
+    create_verified(&backend, "owner@example.com").await;
+    let admin = create_verified(&backend, "editor@example.com").await;
+    assert!(log_in(&backend, "editor@example.com").await);
+
+    let admin = backend.set_admin_disabled(admin.id, true).await.unwrap();
+    assert!(admin.disabled);
+    assert!(!log_in(&backend, "editor@example.com").await);
+    assert!(backend.get_user(&admin.id).await.unwrap().is_none());
+    assert!(backend
+        .request_password_reset("editor@example.com")
+        .await
+        .unwrap()
+        .is_none());
+
+    backend.set_admin_disabled(admin.id, false).await.unwrap();
+    assert!(log_in(&backend, "editor@example.com").await);
+    assert!(backend.get_user(&admin.id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_resend_verification_replaces_the_token() {
+    let (backend, _) = test_backend().await;
+    let owner = create_verified(&backend, "owner@example.com").await;
+    let (admin, old_token) = backend
+        .create_admin("new@example.com", PASSWORD)
+        .await
+        .unwrap();
+
+    let (_, new_token) = backend.resend_verification(admin.id).await.unwrap();
+    assert_ne!(old_token, new_token);
+    assert!(backend.verify_email(&old_token).await.is_err());
+    assert!(
+        backend
+            .verify_email(&new_token)
+            .await
+            .unwrap()
+            .email_verified
+    );
+
+    assert!(backend.resend_verification(admin.id).await.is_err());
+    assert!(backend.resend_verification(owner.id).await.is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_emails_match_in_any_case() {
+    let (backend, _) = test_backend().await;
+    let admin = create_verified(&backend, " Editor@Example.COM ").await;
+    assert_eq!(admin.email, "editor@example.com");
+
+    assert!(log_in(&backend, "editor@example.com").await);
+    assert!(log_in(&backend, "EDITOR@example.com").await);
+    assert!(backend
+        .create_admin("editor@EXAMPLE.com", PASSWORD)
+        .await
+        .is_err());
+    assert!(backend
+        .request_password_reset("Editor@Example.com")
+        .await
+        .unwrap()
+        .is_some());
+}
@@ -0,0 +1,147 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::admin::auth::unverified_admin_retention;
+use crate::entities::{admin_user, AdminUser};
+use crate::tests::test_backend;
+use chrono::{Duration, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serial_test::serial;
+use uuid::Uuid;
+
+const EMAIL: &str = "admin@example.com";
+const PASSWORD: &str = "plum kettle orbit lantern";
+
+/// Move an account's creation and verification link into the past
+async fn age_admin(db: &DatabaseConnection, id: Uuid, created_days: i64, link_minutes: i64) {
+    let admin = AdminUser::find_by_id(id).one(db).await.unwrap().unwrap();
+    let created_at = admin.created_at - Duration::days(created_days);
+    let expires_at = admin
+        .verification_token_expires_at
+        .map(|expires_at| expires_at - Duration::minutes(link_minutes));
+    let mut admin: admin_user::ActiveModel = admin.into();
+    admin.created_at = Set(created_at);
+    admin.verification_token_expires_at = Set(expires_at);
+    admin.update(db).await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_verification_resend_rotates_and_throttles() {
+    let (backend, db) = test_backend().await;
+    let (admin, first) = backend.create_admin(EMAIL, PASSWORD).await.unwrap();
+
+    // Registering again points to the resend instead
+    let error = backend
+        .create_admin(EMAIL, PASSWORD)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("request a new verification link"),
+        "{}",
+        error
+    );
+
+    // A second email right away is suppressed
+    assert!(backend
+        .request_verification_resend(EMAIL)
+        .await
+        .unwrap()
+        .is_none());
+
+    // An expired link can be replaced, and the old one stops working
+    age_admin(&db, admin.id, 0, 25 * 60).await;
+    assert!(backend.verify_email(&first).await.is_err());
+    let (resent, second) = backend
+        .request_verification_resend(EMAIL)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(first, second);
+    assert!(resent.verification_token_expires_at.unwrap() > Utc::now() + Duration::hours(23));
+    assert!(backend.verify_email(&first).await.is_err());
+    backend.verify_email(&second).await.unwrap();
+
+    // Nothing to resend once verified, or for unknown addresses
+    age_admin(&db, admin.id, 0, 0).await;
+    assert!(backend
+        .request_verification_resend(EMAIL)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(backend
+        .request_verification_resend("nobody@example.com")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unverified_admins_are_purged() {
+    let (backend, db) = test_backend().await;
+    let (verified, token) = backend.create_admin(EMAIL, PASSWORD).await.unwrap();
+    backend.verify_email(&token).await.unwrap();
+    let (stale, _) = backend
+        .create_admin("stale@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let (resent, _) = backend
+        .create_admin("resent@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let (recent, _) = backend
+        .create_admin("recent@example.com", PASSWORD)
+        .await
+        .unwrap();
+
+    age_admin(&db, verified.id, 30, 0).await;
+    age_admin(&db, stale.id, 8, 8 * 24 * 60).await;
+    // Old, but an owner sent a fresh link that still works
+    age_admin(&db, resent.id, 8, 0).await;
+
+    let purged = backend
+        .purge_unverified_admins(Duration::days(7))
+        .await
+        .unwrap();
+    assert_eq!(purged, 1);
+
+    let remaining: Vec<String> = AdminUser::find()
+        .all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|admin| admin.email)
+        .collect();
+    assert!(!remaining.contains(&"stale@example.com".to_string()));
+    assert_eq!(remaining.len(), 3);
+    assert!(remaining.contains(&recent.email));
+
+    // The purged address can register again
+    backend
+        .create_admin("stale@example.com", PASSWORD)
+        .await
+        .unwrap();
+
+    // Never shorter than a verification link
+    std::env::set_var("UNVERIFIED_ADMIN_PURGE_DAYS", "0");
+    assert_eq!(unverified_admin_retention(), Duration::hours(24));
+    std::env::set_var("UNVERIFIED_ADMIN_PURGE_DAYS", "14");
+    assert_eq!(unverified_admin_retention(), Duration::days(14));
+    std::env::remove_var("UNVERIFIED_ADMIN_PURGE_DAYS");
+    assert_eq!(unverified_admin_retention(), Duration::days(7));
+}
@@ -0,0 +1,143 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::watermark::{watermark_pdf, Watermark, WatermarkMode};
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Document, Object, Stream,
+};
+
+/// Build a minimal single page PDF whose resources are inherited from the page tree
+fn sample_pdf() -> Vec<u8> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Courier",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+    let content = Content {
+        operations: vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 24.into()]),
+            Operation::new("Td", vec![72.into(), 700.into()]),
+            Operation::new("Tj", vec![Object::string_literal("Resume body")]),
+            Operation::new("ET", vec![]),
+        ],
+    };
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+    });
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).unwrap();
+    bytes
+}
+
+fn test_watermark() -> Watermark {
+    Watermark {
+        recipient: "Acme Recruiting".to_string(),
+        date: "2025-01-30".to_string(),
+    }
+}
+
+#[test]
+fn test_watermark_mode_parse() {
+    assert_eq!(WatermarkMode::parse("footer"), WatermarkMode::Footer);
+    assert_eq!(WatermarkMode::parse("Metadata"), WatermarkMode::Metadata);
+    assert_eq!(WatermarkMode::parse("both"), WatermarkMode::Both);
+    assert_eq!(WatermarkMode::parse("true"), WatermarkMode::Both);
+    assert_eq!(WatermarkMode::parse("off"), WatermarkMode::Off);
+    assert_eq!(WatermarkMode::parse("garbage"), WatermarkMode::Off);
+}
+
+#[test]
+fn test_watermark_off_returns_original() {
+    let pdf = sample_pdf();
+    let output = watermark_pdf(&pdf, &test_watermark(), WatermarkMode::Off).unwrap();
+    assert_eq!(
+        output, pdf,
+        "Disabled watermarking should not touch the PDF"
+    );
+}
+
+#[test]
+fn test_watermark_footer_and_metadata() {
+    let pdf = sample_pdf();
+    let output = watermark_pdf(&pdf, &test_watermark(), WatermarkMode::Both).unwrap();
+
+    let doc = Document::load_mem(&output).expect("Watermarked PDF should still parse");
+
+    let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+    let info = doc.get_dictionary(info_id).unwrap();
+    let recipient = info.get(b"Recipient").unwrap().as_str().unwrap();
+    assert_eq!(recipient, b"Acme Recruiting");
+
+    let page_id = *doc.get_pages().get(&1).unwrap();
+    let text = String::from_utf8_lossy(&doc.get_page_content(page_id)).to_string();
+    assert!(
+        text.contains("Resume body"),
+        "Original content should be kept"
+    );
+    assert!(
+        text.contains("Prepared for Acme Recruiting on 2025-01-30"),
+        "Footer should be stamped on the page"
+    );
+
+    // Inherited fonts must still resolve once resources are copied onto the page
+    let fonts = doc.get_page_fonts(page_id).unwrap();
+    assert!(fonts.contains_key(b"F1".as_slice()));
+    assert!(fonts.contains_key(b"PSWatermarkFont".as_slice()));
+}
+
+#[test]
+fn test_watermark_metadata_only_leaves_content() {
+    let pdf = sample_pdf();
+    let output = watermark_pdf(&pdf, &test_watermark(), WatermarkMode::Metadata).unwrap();
+
+    let doc = Document::load_mem(&output).unwrap();
+    let page_id = *doc.get_pages().get(&1).unwrap();
+    let text = String::from_utf8_lossy(&doc.get_page_content(page_id)).to_string();
+    assert!(!text.contains("Prepared for"));
+}
+
+#[test]
+fn test_watermark_rejects_invalid_pdf() {
+    let result = watermark_pdf(b"not a pdf", &test_watermark(), WatermarkMode::Footer);
+    assert!(result.is_err());
+}
@@ -0,0 +1,274 @@
+/*  This file is part of a personal website project codename personal-site
+ *  Copyright (C) 2025  Grant DeFayette
+ *
+ *  personal-site is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  personal-site is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with personal-site.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+// Per-recipient PDF watermarking so leaked documents can be traced back to an access code
+
+use anyhow::Result;
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Dictionary, Document, Object, ObjectId, Stream,
+};
+
+/// Setting key controlling watermarking, either globally (category "system")
+/// or per access code (category "access_code", entity_id = code id)
+pub const WATERMARK_SETTING_KEY: &str = "pdf_watermark";
+
+/// Resource name used for the footer font, unlikely to clash with existing fonts
+const FONT_RESOURCE_NAME: &str = "PSWatermarkFont";
+
+/// Maximum depth when walking the page tree for inherited attributes
+const MAX_PAGE_TREE_DEPTH: usize = 32;
+
+/// Maximum number of characters of the recipient name stamped into the footer
+const MAX_RECIPIENT_LENGTH: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkMode {
+    Off,
+    Footer,
+    Metadata,
+    Both,
+}
+
+impl WatermarkMode {
+    /// Parse a setting value, treating anything unrecognized as Off
+    /// "true" is accepted as an alias for Both so the setting can be toggled like other booleans
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "footer" => WatermarkMode::Footer,
+            "metadata" => WatermarkMode::Metadata,
+            "both" | "true" => WatermarkMode::Both,
+            _ => WatermarkMode::Off,
+        }
+    }
+
+    pub fn is_enabled(self) -> bool {
+        self != WatermarkMode::Off
+    }
+
+    fn stamps_footer(self) -> bool {
+        matches!(self, WatermarkMode::Footer | WatermarkMode::Both)
+    }
+
+    fn stamps_metadata(self) -> bool {
+        matches!(self, WatermarkMode::Metadata | WatermarkMode::Both)
+    }
+}
+
+/// Details stamped into the document
+#[derive(Debug, Clone)]
+pub struct Watermark {
+    pub recipient: String,
+    pub date: String,
+}
+
+impl Watermark {
+    pub fn new(recipient: &str, date: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            recipient: recipient.to_string(),
+            date: date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    fn footer_text(&self) -> String {
+        format!(
+            "Prepared for {} on {} - not for redistribution",
+            pdf_safe_text(&self.recipient, MAX_RECIPIENT_LENGTH),
+            pdf_safe_text(&self.date, 32)
+        )
+    }
+}
+
+/// Stamp a PDF with the recipient and date
+/// Footer mode appends a small line of text to the bottom of every page,
+/// metadata mode records the recipient in the document info dictionary
+pub fn watermark_pdf(pdf: &[u8], watermark: &Watermark, mode: WatermarkMode) -> Result<Vec<u8>> {
+    if !mode.is_enabled() {
+        return Ok(pdf.to_vec());
+    }
+
+    let mut doc =
+        Document::load_mem(pdf).map_err(|e| anyhow::anyhow!("Failed to parse PDF: {}", e))?;
+
+    if doc.is_encrypted() {
+        anyhow::bail!("Cannot watermark an encrypted PDF");
+    }
+
+    if mode.stamps_metadata() {
+        stamp_metadata(&mut doc, watermark)?;
+    }
+
+    if mode.stamps_footer() {
+        stamp_footer(&mut doc, watermark)?;
+    }
+
+    let mut output = Vec::with_capacity(pdf.len() + 1024);
+    doc.save_to(&mut output)
+        .map_err(|e| anyhow::anyhow!("Failed to write watermarked PDF: {}", e))?;
+
+    Ok(output)
+}
+
+fn stamp_metadata(doc: &mut Document, watermark: &Watermark) -> Result<()> {
+    let info_id = match doc.trailer.get(b"Info").and_then(Object::as_reference) {
+        Ok(id) => id,
+        Err(_) => {
+            let id = doc.add_object(Dictionary::new());
+            doc.trailer.set("Info", id);
+            id
+        }
+    };
+
+    let info = doc
+        .get_dictionary_mut(info_id)
+        .map_err(|e| anyhow::anyhow!("Invalid PDF info dictionary: {}", e))?;
+    info.set(
+        "Recipient",
+        Object::string_literal(pdf_safe_text(&watermark.recipient, MAX_RECIPIENT_LENGTH)),
+    );
+    info.set(
+        "WatermarkDate",
+        Object::string_literal(pdf_safe_text(&watermark.date, 32)),
+    );
+
+    Ok(())
+}
+
+fn stamp_footer(doc: &mut Document, watermark: &Watermark) -> Result<()> {
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+
+    // Existing content is wrapped in q/Q so any graphics state it leaves behind
+    // (transforms, colors) can't move or hide the footer
+    let save_state_id = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+    let footer_text = watermark.footer_text();
+
+    let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    for page_id in page_ids {
+        let media_box = inherited_media_box(doc, page_id);
+        let resources = inherited_resources(doc, page_id, font_id)?;
+
+        let footer = Content {
+            operations: vec![
+                Operation::new("Q", vec![]),
+                Operation::new("q", vec![]),
+                Operation::new("BT", vec![]),
+                Operation::new(
+                    "Tf",
+                    vec![
+                        Object::Name(FONT_RESOURCE_NAME.as_bytes().to_vec()),
+                        7.into(),
+                    ],
+                ),
+                Operation::new("rg", vec![0.45.into(), 0.45.into(), 0.45.into()]),
+                Operation::new(
+                    "Td",
+                    vec![(media_box[0] + 36.0).into(), (media_box[1] + 14.0).into()],
+                ),
+                Operation::new("Tj", vec![Object::string_literal(footer_text.clone())]),
+                Operation::new("ET", vec![]),
+                Operation::new("Q", vec![]),
+            ],
+        };
+        let footer_data = footer
+            .encode()
+            .map_err(|e| anyhow::anyhow!("Failed to encode watermark: {}", e))?;
+        let footer_id = doc.add_object(Stream::new(Dictionary::new(), footer_data));
+
+        let mut contents: Vec<Object> = vec![save_state_id.into()];
+        contents.extend(doc.get_page_contents(page_id).into_iter().map(Object::from));
+        contents.push(footer_id.into());
+
+        let page = doc
+            .get_dictionary_mut(page_id)
+            .map_err(|e| anyhow::anyhow!("Invalid PDF page: {}", e))?;
+        page.set("Resources", resources);
+        page.set("Contents", contents);
+    }
+
+    Ok(())
+}
+
+/// Build a page-local copy of the resources that apply to a page with the watermark font added
+/// Copying inherited resources onto the page keeps the original fonts and images resolvable
+fn inherited_resources(doc: &Document, page_id: ObjectId, font_id: ObjectId) -> Result<Dictionary> {
+    let mut resources = find_inherited(doc, page_id, b"Resources")
+        .and_then(|obj| obj.as_dict().ok().cloned())
+        .unwrap_or_default();
+
+    let mut fonts = resources
+        .get(b"Font")
+        .ok()
+        .and_then(|obj| doc.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok().cloned())
+        .unwrap_or_default();
+    fonts.set(FONT_RESOURCE_NAME, font_id);
+    resources.set("Font", fonts);
+
+    Ok(resources)
+}
+
+/// Page media box, defaulting to US Letter when missing or malformed
+fn inherited_media_box(doc: &Document, page_id: ObjectId) -> [f32; 4] {
+    let default = [0.0, 0.0, 612.0, 792.0];
+
+    let Some(Ok(values)) = find_inherited(doc, page_id, b"MediaBox").map(Object::as_array) else {
+        return default;
+    };
+
+    let numbers: Vec<f32> = values
+        .iter()
+        .filter_map(|obj| doc.dereference(obj).ok())
+        .filter_map(|(_, obj)| obj.as_float().ok())
+        .collect();
+
+    match numbers.as_slice() {
+        [x0, y0, x1, y1] => [x0.min(*x1), y0.min(*y1), x0.max(*x1), y0.max(*y1)],
+        _ => default,
+    }
+}
+
+/// Look up an inheritable page attribute, walking up the page tree via Parent
+fn find_inherited<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node_id = page_id;
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        let node = doc.get_dictionary(node_id).ok()?;
+        if let Ok(value) = node.get(key) {
+            return doc.dereference(value).ok().map(|(_, obj)| obj);
+        }
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+    }
+    None
+}
+
+/// Restrict text to printable ASCII so it renders with the standard Helvetica encoding
+fn pdf_safe_text(text: &str, max_len: usize) -> String {
+    text.chars()
+        .take(max_len)
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}